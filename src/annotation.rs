@@ -0,0 +1,75 @@
+/// What an annotation can be applied to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnnotationTarget {
+    Class,
+    /// A member variable.
+    Var,
+    /// A member function.
+    Func,
+    /// On its own at class level, like `@export_group`.
+    Standalone,
+    /// A statement inside a function body, including local variables.
+    Stmt,
+}
+
+pub struct AnnotationInfo {
+    pub name: &'static str,
+    pub targets: &'static [AnnotationTarget],
+    pub min_args: usize,
+    /// `None` for variadic annotations.
+    pub max_args: Option<usize>,
+}
+
+const fn info(name: &'static str, targets: &'static [AnnotationTarget], min_args: usize, max_args: Option<usize>) -> AnnotationInfo {
+    AnnotationInfo { name, targets, min_args, max_args }
+}
+
+use AnnotationTarget::*;
+
+static ANNOTATIONS: &[AnnotationInfo] = &[
+    info("tool", &[Class], 0, Some(0)),
+    info("icon", &[Class], 1, Some(1)),
+    info("static_unload", &[Class], 0, Some(0)),
+    info("abstract", &[Class, Func], 0, Some(0)),
+    info("onready", &[Var], 0, Some(0)),
+    info("export", &[Var], 0, Some(0)),
+    info("export_enum", &[Var], 1, None),
+    info("export_file", &[Var], 0, None),
+    info("export_dir", &[Var], 0, Some(0)),
+    info("export_global_file", &[Var], 0, None),
+    info("export_global_dir", &[Var], 0, Some(0)),
+    info("export_multiline", &[Var], 0, Some(0)),
+    info("export_placeholder", &[Var], 1, Some(1)),
+    info("export_range", &[Var], 2, None),
+    info("export_exp_easing", &[Var], 0, None),
+    info("export_color_no_alpha", &[Var], 0, Some(0)),
+    info("export_node_path", &[Var], 0, None),
+    info("export_flags", &[Var], 1, None),
+    info("export_flags_2d_render", &[Var], 0, Some(0)),
+    info("export_flags_2d_physics", &[Var], 0, Some(0)),
+    info("export_flags_2d_navigation", &[Var], 0, Some(0)),
+    info("export_flags_3d_render", &[Var], 0, Some(0)),
+    info("export_flags_3d_physics", &[Var], 0, Some(0)),
+    info("export_flags_3d_navigation", &[Var], 0, Some(0)),
+    info("export_flags_avoidance", &[Var], 0, Some(0)),
+    info("export_storage", &[Var], 0, Some(0)),
+    info("export_custom", &[Var], 2, Some(3)),
+    info("export_tool_button", &[Var], 1, Some(2)),
+    info("export_category", &[Standalone], 1, Some(1)),
+    info("export_group", &[Standalone], 1, Some(2)),
+    info("export_subgroup", &[Standalone], 1, Some(2)),
+    info("rpc", &[Func], 0, Some(4)),
+    info("warning_ignore", &[Var, Func, Stmt], 1, None),
+    info("warning_ignore_start", &[Standalone, Stmt], 1, None),
+    info("warning_ignore_restore", &[Standalone, Stmt], 1, None),
+];
+
+pub fn annotation_info(name: &str) -> Option<&'static AnnotationInfo> {
+    ANNOTATIONS.iter().find(|info| info.name == name)
+}
+
+/// Returns what the annotation called `name` (without `@`) can be applied to,
+/// or `None` if it isn't a known annotation.
+pub fn annotation_targets(name: &str) -> Option<&'static [AnnotationTarget]> {
+    annotation_info(name).map(|info| info.targets)
+}
@@ -1,24 +1,69 @@
+use std::hash::{Hash, Hasher};
+
 use crate::{ident::IdentName, lexer::Span};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Node<'a> {
     Class(&'a Class<'a>),
+    Extends(&'a Extends<'a>),
     StmtList(&'a StmtList<'a>),
     Stmt(&'a Stmt<'a>),
     Expr(&'a Expr<'a>),
     FuncDef(&'a FuncDef<'a>),
     ParamList(&'a ParamList<'a>),
     VarDef(&'a VarDef<'a>),
+    Accessor(&'a Accessor<'a>),
+    ConstDef(&'a ConstDef<'a>),
+    EnumDef(&'a EnumDef<'a>),
+    EnumVariant(&'a EnumVariant<'a>),
+    SignalDef(&'a SignalDef<'a>),
     IdentDef(&'a IdentDef<'a>),
     Ident(&'a Ident<'a>),
     Lit(&'a Lit<'a>),
     BinOp(&'a BinOp<'a>),
+    UnaryOp(&'a UnaryOp<'a>),
+    Ternary(&'a Ternary<'a>),
+    Assign(&'a Assign<'a>),
+    If(&'a If<'a>),
+    Elif(&'a Elif<'a>),
+    While(&'a While<'a>),
+    For(&'a For<'a>),
+    Match(&'a Match<'a>),
+    MatchBranch(&'a MatchBranch<'a>),
+    Pattern(&'a Pattern<'a>),
+    DictPatternEntry(&'a DictPatternEntry<'a>),
+    Call(&'a Call<'a>),
+    ArgList(&'a ArgList<'a>),
+    Attr(&'a Attr<'a>),
+    Index(&'a Index<'a>),
+    Await(&'a Await<'a>),
+    Lambda(&'a Lambda<'a>),
+    Array(&'a Array<'a>),
+    Dict(&'a Dict<'a>),
+    DictEntry(&'a DictEntry<'a>),
+    GetNode(&'a GetNode<'a>),
+    Annotation(&'a Annotation<'a>),
 }
 
+/// A script, or an inner `class Name extends Base:` block inside one.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Class<'a> {
     pub span: Span,
+    pub annotations: &'a [&'a Annotation<'a>],
+    /// The `class_name` of a script, or the name of an inner class.
+    pub name: Option<&'a Ident<'a>>,
+    pub extends: Option<&'a Extends<'a>>,
     pub stmt_list: &'a StmtList<'a>,
+    pub doc: Option<&'a str>,
+}
+
+/// `extends Base.Inner` or `extends "res://base.gd".Inner`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Extends<'a> {
+    pub span: Span,
+    pub path: Option<&'a str>,
+    /// The class names, outermost first. Empty when extending a script by path alone.
+    pub names: &'a [&'a Ident<'a>],
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -37,8 +82,26 @@ pub struct Stmt<'a> {
 pub enum StmtKind<'a> {
     Pass,
     Expr(&'a Expr<'a>),
+    Assign(&'a Assign<'a>),
     VarDef(&'a VarDef<'a>),
     FuncDef(&'a FuncDef<'a>),
+    ConstDef(&'a ConstDef<'a>),
+    EnumDef(&'a EnumDef<'a>),
+    SignalDef(&'a SignalDef<'a>),
+    /// An inner class.
+    Class(&'a Class<'a>),
+    If(&'a If<'a>),
+    While(&'a While<'a>),
+    For(&'a For<'a>),
+    Match(&'a Match<'a>),
+    Break,
+    Continue,
+    Return(Option<&'a Expr<'a>>),
+    /// Annotations that don't precede a declaration they could apply to,
+    /// such as `@export_group` or `@warning_ignore` before a plain statement.
+    Annotations(&'a [&'a Annotation<'a>]),
+    /// A statement with a syntax error, skipped up to the end of its line or indented block.
+    Error,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -52,15 +115,33 @@ pub enum ExprKind<'a> {
     Ident(&'a Ident<'a>),
     Lit(&'a Lit<'a>),
     BinOp(&'a BinOp<'a>),
+    UnaryOp(&'a UnaryOp<'a>),
+    Ternary(&'a Ternary<'a>),
+    Call(&'a Call<'a>),
+    Attr(&'a Attr<'a>),
+    Index(&'a Index<'a>),
+    Await(&'a Await<'a>),
+    Self_,
+    Super,
+    Lambda(&'a Lambda<'a>),
+    Array(&'a Array<'a>),
+    Dict(&'a Dict<'a>),
+    GetNode(&'a GetNode<'a>),
+    /// A value with a syntax error, skipped up to the next delimiter.
+    Error,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct FuncDef<'a> {
     pub span: Span,
+    pub annotations: &'a [&'a Annotation<'a>],
+    /// `static func`, which has no `self`.
+    pub is_static: bool,
     pub name: &'a Ident<'a>,
     pub param_list: &'a ParamList<'a>,
     pub result_ty: Option<&'a Expr<'a>>,
     pub body: &'a StmtList<'a>,
+    pub doc: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -71,8 +152,63 @@ pub struct ParamList<'a> {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct VarDef<'a> {
+    pub span: Span,
+    pub annotations: &'a [&'a Annotation<'a>],
+    /// `static var`, shared by all instances of the class.
+    pub is_static: bool,
+    pub def: &'a IdentDef<'a>,
+    pub getter: Option<&'a Accessor<'a>>,
+    pub setter: Option<&'a Accessor<'a>>,
+    pub doc: Option<&'a str>,
+}
+
+/// The `get` or `set` of a property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Accessor<'a> {
+    pub span: Span,
+    pub kind: AccessorKind<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessorKind<'a> {
+    /// `get = _get_hp` or `set = _set_hp`
+    Func(&'a Ident<'a>),
+    /// `get:` or `set(value):` followed by a body. `param` is only set for setters.
+    Body { param: Option<&'a Ident<'a>>, body: &'a StmtList<'a> },
+}
+
+/// `const NAME: Type = val`, where `def.val` is always set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstDef<'a> {
     pub span: Span,
     pub def: &'a IdentDef<'a>,
+    pub doc: Option<&'a str>,
+}
+
+/// `enum Name {A, B = 2}`, or an anonymous enum whose variants are class constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EnumDef<'a> {
+    pub span: Span,
+    pub name: Option<&'a Ident<'a>>,
+    pub variants: &'a [&'a EnumVariant<'a>],
+    pub doc: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EnumVariant<'a> {
+    pub span: Span,
+    pub name: &'a Ident<'a>,
+    pub val: Option<&'a Expr<'a>>,
+    pub doc: Option<&'a str>,
+}
+
+/// `signal name(arg: Type)`. The parameter list is optional.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignalDef<'a> {
+    pub span: Span,
+    pub name: &'a Ident<'a>,
+    pub param_list: Option<&'a ParamList<'a>>,
+    pub doc: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -84,6 +220,14 @@ pub struct IdentDef<'a> {
     pub strict_type: bool,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Annotation<'a> {
+    pub span: Span,
+    /// The name without the leading `@`.
+    pub name: &'a Ident<'a>,
+    pub args: &'a [&'a Expr<'a>],
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Ident<'a> {
     pub span: Span,
@@ -99,7 +243,194 @@ pub struct Lit<'a> {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LitKind<'a> {
     Int(i128),
+    Float(FloatVal),
     Str(&'a str),
+    /// `&"name"`
+    StringName(&'a str),
+    /// `^"path"`
+    NodePath(&'a str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct If<'a> {
+    pub span: Span,
+    pub cond: &'a Expr<'a>,
+    pub body: &'a StmtList<'a>,
+    pub elifs: &'a [&'a Elif<'a>],
+    pub else_: Option<&'a StmtList<'a>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Elif<'a> {
+    pub span: Span,
+    pub cond: &'a Expr<'a>,
+    pub body: &'a StmtList<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct While<'a> {
+    pub span: Span,
+    pub cond: &'a Expr<'a>,
+    pub body: &'a StmtList<'a>,
+}
+
+/// `for var in iter:`, where `var` never has a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct For<'a> {
+    pub span: Span,
+    pub var: &'a IdentDef<'a>,
+    pub iter: &'a Expr<'a>,
+    pub body: &'a StmtList<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Match<'a> {
+    pub span: Span,
+    pub expr: &'a Expr<'a>,
+    pub branches: &'a [&'a MatchBranch<'a>],
+}
+
+/// `pattern, pattern when guard: body`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MatchBranch<'a> {
+    pub span: Span,
+    pub patterns: &'a [&'a Pattern<'a>],
+    pub guard: Option<&'a Expr<'a>>,
+    pub body: &'a StmtList<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pattern<'a> {
+    pub span: Span,
+    pub kind: PatternKind<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PatternKind<'a> {
+    /// A literal or a constant like `Color.RED`, compared with `==`.
+    Expr(&'a Expr<'a>),
+    /// `_`
+    Wildcard,
+    /// `var name`
+    Bind(&'a Ident<'a>),
+    /// `[a, b, ..]`, where `rest` is whether it ends with `..`.
+    Array { items: &'a [&'a Pattern<'a>], rest: bool },
+    /// `{"key": pattern, "other", ..}`
+    Dict { entries: &'a [&'a DictPatternEntry<'a>], rest: bool },
+}
+
+impl PatternKind<'_> {
+    /// Whether the pattern matches every value.
+    pub fn is_irrefutable(&self) -> bool {
+        matches!(self, PatternKind::Wildcard | PatternKind::Bind(_))
+    }
+}
+
+/// A dictionary pattern entry. Without a value pattern it only checks that the key exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DictPatternEntry<'a> {
+    pub span: Span,
+    pub key: &'a Expr<'a>,
+    pub val: Option<&'a Pattern<'a>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Call<'a> {
+    pub span: Span,
+    pub callee: &'a Expr<'a>,
+    pub arg_list: &'a ArgList<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArgList<'a> {
+    pub span: Span,
+    pub args: &'a [&'a Expr<'a>],
+}
+
+/// `expr.name`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Attr<'a> {
+    pub span: Span,
+    pub expr: &'a Expr<'a>,
+    pub name: &'a Ident<'a>,
+}
+
+/// `expr[index]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Index<'a> {
+    pub span: Span,
+    pub expr: &'a Expr<'a>,
+    pub index: &'a Expr<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Await<'a> {
+    pub span: Span,
+    pub expr: &'a Expr<'a>,
+}
+
+/// `func(x): return x * 2`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Lambda<'a> {
+    pub span: Span,
+    pub param_list: &'a ParamList<'a>,
+    pub result_ty: Option<&'a Expr<'a>>,
+    pub body: &'a StmtList<'a>,
+}
+
+/// `[a, b, c]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Array<'a> {
+    pub span: Span,
+    pub items: &'a [&'a Expr<'a>],
+}
+
+/// `{"k": v}` or `{k = v}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Dict<'a> {
+    pub span: Span,
+    pub entries: &'a [&'a DictEntry<'a>],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DictEntry<'a> {
+    pub span: Span,
+    pub key: DictKey<'a>,
+    pub val: &'a Expr<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DictKey<'a> {
+    /// `key: val`
+    Expr(&'a Expr<'a>),
+    /// Lua-style `key = val`
+    Ident(&'a Ident<'a>),
+}
+
+/// `$Path/To/Node`, `$"Quoted"` or `%UniqueName`, which fetch a node relative to `self`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GetNode<'a> {
+    pub span: Span,
+    /// The node path, starting with `%` for scene-unique names.
+    pub path: &'a str,
+}
+
+/// An `f64` compared and hashed by its bit pattern, so that literals stay `Eq` and `Hash`.
+#[derive(Debug, Clone, Copy)]
+pub struct FloatVal(pub f64);
+
+impl PartialEq for FloatVal {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for FloatVal {}
+
+impl Hash for FloatVal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -116,4 +447,104 @@ pub enum BinOpKind {
     Sub,
     Mul,
     Div,
+    Mod,
+    Pow,
+    Shl,
+    Shr,
+    BitAnd,
+    BitXor,
+    BitOr,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    In,
+    NotIn,
+    And,
+    Or,
+    /// `x is T`, where the right-hand side is a type.
+    Is,
+    IsNot,
+    /// `x as T`, where the right-hand side is a type.
+    As,
+}
+
+impl BinOpKind {
+    /// The operator as it's written in GDScript.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BinOpKind::Add => "+",
+            BinOpKind::Sub => "-",
+            BinOpKind::Mul => "*",
+            BinOpKind::Div => "/",
+            BinOpKind::Mod => "%",
+            BinOpKind::Pow => "**",
+            BinOpKind::Shl => "<<",
+            BinOpKind::Shr => ">>",
+            BinOpKind::BitAnd => "&",
+            BinOpKind::BitXor => "^",
+            BinOpKind::BitOr => "|",
+            BinOpKind::Eq => "==",
+            BinOpKind::Ne => "!=",
+            BinOpKind::Lt => "<",
+            BinOpKind::Le => "<=",
+            BinOpKind::Gt => ">",
+            BinOpKind::Ge => ">=",
+            BinOpKind::In => "in",
+            BinOpKind::NotIn => "not in",
+            BinOpKind::And => "and",
+            BinOpKind::Or => "or",
+            BinOpKind::Is => "is",
+            BinOpKind::IsNot => "is not",
+            BinOpKind::As => "as",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnaryOp<'a> {
+    pub span: Span,
+    pub kind: UnaryOpKind,
+    pub expr: &'a Expr<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnaryOpKind {
+    Neg,
+    Pos,
+    BitNot,
+    /// `not x` or `!x`
+    Not,
+}
+
+impl UnaryOpKind {
+    /// The operator as it's written in GDScript.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UnaryOpKind::Neg => "-",
+            UnaryOpKind::Pos => "+",
+            UnaryOpKind::BitNot => "~",
+            UnaryOpKind::Not => "not",
+        }
+    }
+}
+
+/// `then if cond else else_`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ternary<'a> {
+    pub span: Span,
+    pub cond: &'a Expr<'a>,
+    pub then: &'a Expr<'a>,
+    pub else_: &'a Expr<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Assign<'a> {
+    pub span: Span,
+    /// The operator of a compound assignment like `+=`, `None` for plain `=`.
+    pub op: Option<BinOpKind>,
+    pub target: &'a Expr<'a>,
+    pub val: &'a Expr<'a>,
 }
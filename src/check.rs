@@ -0,0 +1,245 @@
+use crate::{
+    annotation::{annotation_info, AnnotationTarget::{self, *}},
+    ast::{self, AccessorKind, Annotation, Expr, ExprKind, Match, Pattern, PatternKind, StmtKind, StmtList},
+    lexer::Span,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckError {
+    pub span: Span,
+    pub kind: CheckErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckErrorKind {
+    UnknownAnnotation,
+    AnnotationNotAllowed(AnnotationTarget),
+    AnnotationArgCount { min: usize, max: Option<usize>, found: usize },
+    DuplicateAnnotation,
+    /// A match branch that comes after one matching everything.
+    UnreachableMatchBranch,
+    /// A pattern that an earlier unguarded pattern of the same match already covers.
+    DuplicateMatchPattern,
+}
+
+pub fn check(class: &ast::Class) -> Vec<CheckError> {
+    let mut checker = Checker { errors: Vec::new() };
+    checker.annotations(class.annotations, Class);
+    checker.stmt_list(class.stmt_list, true);
+    checker.errors
+}
+
+struct Checker {
+    errors: Vec<CheckError>,
+}
+
+impl Checker {
+    fn error(&mut self, span: Span, kind: CheckErrorKind) {
+        self.errors.push(CheckError { span, kind });
+    }
+
+    fn stmt_list(&mut self, stmt_list: &StmtList, class_level: bool) {
+        for stmt in stmt_list.stmts {
+            match stmt.kind {
+                StmtKind::VarDef(var_def) => {
+                    self.annotations(var_def.annotations, if class_level { Var } else { Stmt });
+                    for accessor in [var_def.getter, var_def.setter].into_iter().flatten() {
+                        if let AccessorKind::Body { body, .. } = accessor.kind {
+                            self.stmt_list(body, false);
+                        }
+                    }
+                }
+                StmtKind::FuncDef(func_def) => {
+                    self.annotations(func_def.annotations, if class_level { Func } else { Stmt });
+                    self.stmt_list(func_def.body, false);
+                }
+                StmtKind::Annotations(annotations) => {
+                    self.annotations(annotations, if class_level { Standalone } else { Stmt });
+                }
+                StmtKind::If(if_) => {
+                    self.stmt_list(if_.body, false);
+                    for elif in if_.elifs {
+                        self.stmt_list(elif.body, false);
+                    }
+                    if let Some(else_) = if_.else_ {
+                        self.stmt_list(else_, false);
+                    }
+                }
+                StmtKind::Class(class) => {
+                    self.annotations(class.annotations, Class);
+                    self.stmt_list(class.stmt_list, true);
+                }
+                StmtKind::While(while_) => self.stmt_list(while_.body, false),
+                StmtKind::For(for_) => self.stmt_list(for_.body, false),
+                StmtKind::Match(match_) => {
+                    self.match_branches(match_);
+                    for branch in match_.branches {
+                        self.stmt_list(branch.body, false);
+                    }
+                }
+                StmtKind::Pass | StmtKind::Expr(_) | StmtKind::Assign(_)
+                    | StmtKind::ConstDef(_) | StmtKind::EnumDef(_) | StmtKind::SignalDef(_)
+                    | StmtKind::Break | StmtKind::Continue | StmtKind::Return(_) | StmtKind::Error => (),
+            }
+        }
+    }
+
+    fn match_branches(&mut self, match_: &Match) {
+        // Patterns of earlier branches without a `when` guard.
+        let mut seen: Vec<&Pattern> = Vec::new();
+        for branch in match_.branches {
+            if seen.iter().any(|pattern| pattern.kind.is_irrefutable()) {
+                self.error(branch.span, CheckErrorKind::UnreachableMatchBranch);
+                continue;
+            }
+            for (i, pattern) in branch.patterns.iter().enumerate() {
+                let earlier = seen.iter().chain(&branch.patterns[..i]);
+                if earlier.clone().any(|prev| same_pattern(prev, pattern)) {
+                    self.error(pattern.span, CheckErrorKind::DuplicateMatchPattern);
+                }
+            }
+            if branch.guard.is_none() {
+                seen.extend(branch.patterns);
+            }
+        }
+    }
+
+    fn annotations(&mut self, annotations: &[&Annotation], target: AnnotationTarget) {
+        for (i, annotation) in annotations.iter().enumerate() {
+            let name = annotation.name.name;
+            if annotations[..i].iter().any(|prev| prev.name.name == name) {
+                self.error(annotation.span, CheckErrorKind::DuplicateAnnotation);
+            }
+            let Some(info) = annotation_info(name.as_str()) else {
+                self.error(annotation.name.span, CheckErrorKind::UnknownAnnotation);
+                continue;
+            };
+            if !info.targets.contains(&target) {
+                self.error(annotation.span, CheckErrorKind::AnnotationNotAllowed(target));
+            }
+            let found = annotation.args.len();
+            if found < info.min_args || info.max_args.is_some_and(|max| found > max) {
+                self.error(annotation.span, CheckErrorKind::AnnotationArgCount { min: info.min_args, max: info.max_args, found });
+            }
+        }
+    }
+}
+
+/// Compares patterns ignoring spans.
+fn same_pattern(a: &Pattern, b: &Pattern) -> bool {
+    match (a.kind, b.kind) {
+        (PatternKind::Expr(a), PatternKind::Expr(b)) => same_expr(a, b),
+        (PatternKind::Wildcard | PatternKind::Bind(_), PatternKind::Wildcard | PatternKind::Bind(_)) => true,
+        (PatternKind::Array { items: a, rest: a_rest }, PatternKind::Array { items: b, rest: b_rest }) => {
+            a_rest == b_rest && a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_pattern(a, b))
+        }
+        (PatternKind::Dict { entries: a, rest: a_rest }, PatternKind::Dict { entries: b, rest: b_rest }) => {
+            a_rest == b_rest && a.len() == b.len() && a.iter().zip(b).all(|(a, b)| {
+                same_expr(a.key, b.key) && match (a.val, b.val) {
+                    (Some(a), Some(b)) => same_pattern(a, b),
+                    (a, b) => a.is_none() && b.is_none(),
+                }
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Compares the constant expressions allowed in patterns, ignoring spans.
+fn same_expr(a: &Expr, b: &Expr) -> bool {
+    match (a.kind, b.kind) {
+        (ExprKind::Lit(a), ExprKind::Lit(b)) => a.kind == b.kind,
+        (ExprKind::Ident(a), ExprKind::Ident(b)) => a.name == b.name,
+        (ExprKind::Attr(a), ExprKind::Attr(b)) => a.name.name == b.name.name && same_expr(a.expr, b.expr),
+        (ExprKind::UnaryOp(a), ExprKind::UnaryOp(b)) => a.kind == b.kind && same_expr(a.expr, b.expr),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::{context, lexer, parser};
+
+    use super::*;
+
+    fn check_source(source: &str) -> Vec<CheckErrorKind> {
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parser::parse(source, &tokens, &ctx).unwrap();
+        check(class).into_iter().map(|e| e.kind).collect()
+    }
+
+    #[test]
+    fn annotations() {
+        assert_eq!(check_source(indoc! {r#"
+            @tool
+            @export_range(0, 10) var a = 1
+            @export_category("Misc")
+            @rpc("any_peer")
+            func f():
+                @warning_ignore("unused_variable")
+                var b = 2
+        "#}), vec![]);
+        // Annotations that can stand on their own line don't attach to the declaration below.
+        assert_eq!(check_source(indoc! {r#"
+            @warning_ignore_start("unused_variable")
+            var x = 1
+            @warning_ignore_restore("unused_variable")
+            func f():
+                @warning_ignore_start("unused_variable")
+                var y = 2
+        "#}), vec![]);
+        assert_eq!(check_source(indoc! {"
+            @export
+            func f():
+                @export var b = 2
+        "}), vec![
+            CheckErrorKind::AnnotationNotAllowed(Func),
+            CheckErrorKind::AnnotationNotAllowed(Stmt),
+        ]);
+        assert_eq!(check_source(indoc! {"
+            var a = 1
+            @tool
+            var b = 2
+        "}), vec![CheckErrorKind::AnnotationNotAllowed(Var)]);
+        assert_eq!(check_source("@nope var a = 1\n"), vec![CheckErrorKind::UnknownAnnotation]);
+        assert_eq!(check_source("@export @export var a = 1\n"), vec![CheckErrorKind::DuplicateAnnotation]);
+        assert_eq!(check_source("@export_range(1) var a = 1\n"), vec![
+            CheckErrorKind::AnnotationArgCount { min: 2, max: None, found: 1 },
+        ]);
+    }
+
+    #[test]
+    fn match_branches() {
+        assert_eq!(check_source(indoc! {"
+            match x:
+                1, -1, Color.RED, \"s\":
+                    pass
+                [1, var a, ..], {\"k\": _, \"other\"}:
+                    pass
+                var y when y > 2:
+                    pass
+                _:
+                    pass
+        "}), vec![]);
+        assert_eq!(check_source(indoc! {"
+            match x:
+                1, 2, 1:
+                    pass
+                Color.RED when y: pass
+                Color.RED: pass
+                [_, ..], {\"k\": 1}: pass
+                2, [var z, ..], {\"k\": 1}: pass
+                var w: pass
+                3: pass
+        "}), vec![
+            CheckErrorKind::DuplicateMatchPattern,
+            CheckErrorKind::DuplicateMatchPattern,
+            CheckErrorKind::DuplicateMatchPattern,
+            CheckErrorKind::DuplicateMatchPattern,
+            CheckErrorKind::UnreachableMatchBranch,
+        ]);
+    }
+}
@@ -1,77 +1,652 @@
-use crate::ast::{Class, Expr, Lit, LitKind, Stmt, VarDef};
-
+use std::io::Write;
+use crate::{
+    ast::{BinOpKind, UnaryOpKind},
+    thir::{ty::{Ty, TyKind}, Block, BuiltinFunc, Class, Expr, ExprKind, Func, FuncKind, Global, Lambda, Local, LocalId, Place, Stmt, StmtKind},
+};
 
+/// Declarations every generated program starts with: closures, strings, and the
+/// `Variant` tagged union with the operations that check its type at runtime.
+const PRELUDE: &str = include_str!("codegen/prelude.c");
 
 pub struct Codegen<'a, Dst: std::io::Write> {
-    ast: &'a Class<'a>,
+    class: &'a Class<'a>,
     dst: &'a mut Dst,
+    /// The function currently being generated.
+    out: Vec<u8>,
+    /// Definitions of lambda bodies, emitted before `main`.
+    /// Inner lambdas are finished first, so they're defined before the lambdas using them.
+    lambdas: Vec<u8>,
+    lambda_count: usize,
+    /// Numbers the labels that end `match` statements.
+    match_count: usize,
+    /// Functions of the class, emitted as free functions before `main`.
+    funcs: Vec<u8>,
+    /// Locals of the function or lambda currently being generated.
+    locals: &'a [Local<'a>],
+    /// The return type of the function currently being generated,
+    /// `None` in lambdas, which always return a `gdx_variant`.
+    ret_ty: Option<Ty<'a>>,
 }
 
 type Result<T> = std::result::Result<T, std::io::Error>;
 
 impl<'a, Dst: std::io::Write> Codegen<'a, Dst> {
-    pub fn new(ast: &'a Class<'a>, dst: &'a mut Dst) -> Self {
+    pub fn new(class: &'a Class<'a>, dst: &'a mut Dst) -> Self {
         Self {
-            ast,
+            class,
             dst,
+            out: Vec::new(),
+            lambdas: Vec::new(),
+            lambda_count: 0,
+            match_count: 0,
+            funcs: Vec::new(),
+            locals: &[],
+            ret_ty: None,
         }
     }
 
     pub fn generate(&mut self) -> Result<()> {
-        self.gen_program(self.ast)?;
+        self.gen_program(self.class)?;
         Ok(())
     }
 
-    fn gen_program(&mut self, prog: &Class) -> Result<()> {
-        writeln!(self.dst, "int main() {{")?;
-        for stmt in prog.stmt_list.stmts.iter() {
-            self.gen_stmt(stmt)?;
+    fn gen_program(&mut self, class: &'a Class<'a>) -> Result<()> {
+        for func in class.funcs {
+            self.gen_func(func)?;
+        }
+
+        // Globals are initialized before the top-level statements run, starting
+        // out as zero values in case an initializer reads another one.
+        writeln!(self.out, "int main() {{")?;
+        self.locals = class.main.locals;
+        self.ret_ty = Some(class.main.ret_ty);
+        for global in class.globals {
+            writeln!(self.out, "{} = {};", global_name(global), zero(global.ty))?;
         }
-        writeln!(self.dst, "}}")?;
+        for global in class.globals {
+            if let Some(val) = global.val {
+                write!(self.out, "{} = ", global_name(global))?;
+                self.gen_expr(val)?;
+                writeln!(self.out, ";")?;
+            }
+        }
+        self.gen_stmts(class.main.body)?;
+        writeln!(self.out, "}}")?;
+
+        write!(self.dst, "{PRELUDE}")?;
+        for global in class.globals {
+            writeln!(self.dst, "static {} {};", c_type(global.ty), global_name(global))?;
+        }
+        for func in class.funcs {
+            writeln!(self.dst, "{};", func_signature(func))?;
+        }
+        self.dst.write_all(&self.lambdas)?;
+        self.dst.write_all(&self.funcs)?;
+        self.dst.write_all(&self.out)?;
         Ok(())
     }
 
-    fn gen_stmt(&mut self, stmt: &Stmt) -> Result<()> {
-        // match stmt {
-        //     Stmt::Expr(expr) => {
-        //         self.gen_expr(expr)?;
-        //     }
-        //     Stmt::VarDef(var_decl) => {
+    /// Emits a function of the class as a free function.
+    fn gen_func(&mut self, func: &'a Func<'a>) -> Result<()> {
+        self.locals = func.locals;
+        self.ret_ty = Some(func.ret_ty);
+        writeln!(self.out, "{} {{", func_signature(func))?;
+        self.gen_stmts(func.body)?;
+        if *func.ret_ty != TyKind::Void {
+            writeln!(self.out, "return {};", zero(func.ret_ty))?;
+        }
+        writeln!(self.out, "}}")?;
+        let func = std::mem::take(&mut self.out);
+        self.funcs.extend_from_slice(&func);
+        Ok(())
+    }
 
-        //     }
-        //     Stmt::FuncDef(func_def) => {
+    /// Emits the statements of a block, without braces.
+    fn gen_stmts(&mut self, block: &Block<'a>) -> Result<()> {
+        for stmt in block.stmts {
+            self.gen_stmt(stmt)?;
+        }
+        Ok(())
+    }
 
-        //     }
-        //     Stmt::Pass => {
+    fn gen_stmt(&mut self, stmt: &Stmt<'a>) -> Result<()> {
+        match stmt.kind {
+            StmtKind::Expr(expr) => {
+                self.gen_expr(expr)?;
+                writeln!(self.out, ";")?;
+            }
+            StmtKind::Var { local, val } => {
+                let ty = self.locals[local.0 as usize].ty;
+                write!(self.out, "{} {} = ", c_type(ty), self.local(local))?;
+                match val {
+                    Some(val) => self.gen_expr(val)?,
+                    None => write!(self.out, "{}", zero(ty))?,
+                }
+                writeln!(self.out, ";")?;
+            }
+            StmtKind::Assign { place, op, val } => {
+                match place {
+                    Place::Local(local) => write!(self.out, "{}", self.local(local))?,
+                    Place::Global(global) => write!(self.out, "{}", global_name(&self.class.globals[global.0 as usize]))?,
+                }
+                match op {
+                    Some(op) => write!(self.out, " {}= ", bin_op(op))?,
+                    None => write!(self.out, " = ")?,
+                }
+                self.gen_expr(val)?;
+                writeln!(self.out, ";")?;
+            }
+            StmtKind::If { cond, then, else_ } => {
+                write!(self.out, "if (")?;
+                self.gen_cond(cond)?;
+                writeln!(self.out, ") {{")?;
+                self.gen_stmts(then)?;
+                if let Some(else_) = else_ {
+                    writeln!(self.out, "}} else {{")?;
+                    self.gen_stmts(else_)?;
+                }
+                writeln!(self.out, "}}")?;
+            }
+            StmtKind::While { cond, body } => {
+                write!(self.out, "while (")?;
+                self.gen_cond(cond)?;
+                writeln!(self.out, ") {{")?;
+                self.gen_stmts(body)?;
+                writeln!(self.out, "}}")?;
+            }
+            StmtKind::For { var, start, end, step, body } => {
+                // The bounds are evaluated once, before the first iteration.
+                let name = self.local(var);
+                writeln!(self.out, "{{")?;
+                for (suffix, val) in [("start", start), ("end", end), ("step", step)] {
+                    write!(self.out, "int64_t {name}_{suffix} = ")?;
+                    self.gen_expr(val)?;
+                    writeln!(self.out, ";")?;
+                }
+                writeln!(self.out, "if ({name}_step == 0) {{")?;
+                writeln!(self.out, "gdx_fail(\"range step is zero\");")?;
+                writeln!(self.out, "}}")?;
+                writeln!(
+                    self.out,
+                    "for (int64_t {name}_i = {name}_start; {name}_step > 0 ? {name}_i < {name}_end : {name}_i > {name}_end; \
+                     {name}_i = (int64_t)((uint64_t){name}_i + (uint64_t){name}_step)) {{",
+                )?;
+                let ty = self.locals[var.0 as usize].ty;
+                match *ty {
+                    TyKind::Variant => writeln!(self.out, "gdx_variant {name} = gdx_variant_from_int({name}_i);")?,
+                    _ => writeln!(self.out, "{} {name} = {name}_i;", c_type(ty))?,
+                }
+                self.gen_stmts(body)?;
+                writeln!(self.out, "}}")?;
+                writeln!(self.out, "}}")?;
+            }
+            // Branches jump past the others once they've run, which `break` can't do inside a loop.
+            StmtKind::Match { local, val, branches } => {
+                let id = self.match_count;
+                self.match_count += 1;
+                write!(self.out, "{{\n{} {} = ", c_type(self.locals[local.0 as usize].ty), self.local(local))?;
+                self.gen_expr(val)?;
+                writeln!(self.out, ";")?;
+                for branch in branches {
+                    if let Some(cond) = branch.cond {
+                        write!(self.out, "if (")?;
+                        self.gen_cond(cond)?;
+                        write!(self.out, ") ")?;
+                    }
+                    writeln!(self.out, "{{")?;
+                    for bind in branch.binds {
+                        self.gen_stmt(bind)?;
+                    }
+                    if let Some(guard) = branch.guard {
+                        write!(self.out, "if (")?;
+                        self.gen_cond(guard)?;
+                        writeln!(self.out, ") {{")?;
+                    }
+                    self.gen_stmts(branch.body)?;
+                    writeln!(self.out, "goto match_{id}_end;")?;
+                    if branch.guard.is_some() {
+                        writeln!(self.out, "}}")?;
+                    }
+                    writeln!(self.out, "}}")?;
+                }
+                writeln!(self.out, "}}")?;
+                writeln!(self.out, "match_{id}_end:;")?;
+            }
+            StmtKind::Break => writeln!(self.out, "break;")?,
+            StmtKind::Continue => writeln!(self.out, "continue;")?,
+            StmtKind::Return(val) => {
+                write!(self.out, "return")?;
+                match (val, self.ret_ty) {
+                    (Some(val), _) => {
+                        write!(self.out, " ")?;
+                        self.gen_expr(val)?;
+                    }
+                    (None, Some(ty)) if *ty == TyKind::Void => (),
+                    (None, Some(ty)) => write!(self.out, " {}", zero(ty))?,
+                    (None, None) => write!(self.out, " {NIL}")?,
+                }
+                writeln!(self.out, ";")?;
+            }
+        }
+        Ok(())
+    }
 
-        //     }
-        // }
-        // writeln!(self.dst, ";")?;
+    fn gen_expr(&mut self, expr: &Expr<'a>) -> Result<()> {
+        match expr.kind {
+            ExprKind::Int(val) => write!(self.out, "INT64_C({val})")?,
+            ExprKind::Float(val) if val.is_nan() => write!(self.out, "NAN")?,
+            ExprKind::Float(val) if val.is_infinite() => write!(self.out, "{}INFINITY", if val < 0.0 { "-" } else { "" })?,
+            // Debug formatting keeps the decimal point, so that C reads a double.
+            ExprKind::Float(val) => write!(self.out, "{val:?}")?,
+            ExprKind::Bool(val) => write!(self.out, "{val}")?,
+            ExprKind::Str(val) => write!(self.out, "{}", c_string(val))?,
+            ExprKind::Nil => write!(self.out, "{NIL}")?,
+            ExprKind::Local(local) => write!(self.out, "{}", self.local(local))?,
+            ExprKind::Global(global) => write!(self.out, "{}", global_name(&self.class.globals[global.0 as usize]))?,
+            ExprKind::BinOp { kind: kind @ (BinOpKind::And | BinOpKind::Or), lhs, rhs } => {
+                write!(self.out, "(")?;
+                self.gen_cond(lhs)?;
+                write!(self.out, " {} ", bin_op(kind))?;
+                self.gen_cond(rhs)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::BinOp { kind: kind @ (BinOpKind::In | BinOpKind::NotIn), lhs, rhs } => {
+                write!(self.out, "({}gdx_variant_in(", if kind == BinOpKind::NotIn { "!" } else { "" })?;
+                self.gen_expr(lhs)?;
+                write!(self.out, ", ")?;
+                self.gen_expr(rhs)?;
+                write!(self.out, "))")?;
+            }
+            ExprKind::BinOp { kind, lhs, rhs } if *lhs.ty == TyKind::Variant => {
+                write!(self.out, "gdx_variant_op({}, ", variant_op(kind))?;
+                self.gen_expr(lhs)?;
+                write!(self.out, ", ")?;
+                self.gen_expr(rhs)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::BinOp { kind: BinOpKind::Add, lhs, rhs } if *lhs.ty == TyKind::String => {
+                write!(self.out, "gdx_string_concat(")?;
+                self.gen_expr(lhs)?;
+                write!(self.out, ", ")?;
+                self.gen_expr(rhs)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::BinOp { kind, lhs, rhs } if *lhs.ty == TyKind::String => {
+                write!(self.out, "(strcmp(")?;
+                self.gen_expr(lhs)?;
+                write!(self.out, ", ")?;
+                self.gen_expr(rhs)?;
+                write!(self.out, ") {} 0)", bin_op(kind))?;
+            }
+            // Ints go through the runtime, which wraps on overflow and fails where C is undefined.
+            ExprKind::BinOp { kind, lhs, rhs } if matches!(*expr.ty, TyKind::Int(_)) => {
+                write!(self.out, "gdx_int_op({}, ", variant_op(kind))?;
+                self.gen_expr(lhs)?;
+                write!(self.out, ", ")?;
+                self.gen_expr(rhs)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::BinOp { kind: BinOpKind::Pow, lhs, rhs } => {
+                write!(self.out, "pow(")?;
+                self.gen_expr(lhs)?;
+                write!(self.out, ", ")?;
+                self.gen_expr(rhs)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::BinOp { kind, lhs, rhs } => {
+                write!(self.out, "(")?;
+                self.gen_expr(lhs)?;
+                write!(self.out, " {} ", bin_op(kind))?;
+                self.gen_expr(rhs)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::UnaryOp { kind: UnaryOpKind::Not, expr } => {
+                write!(self.out, "(!")?;
+                self.gen_cond(expr)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::UnaryOp { kind, expr } if *expr.ty == TyKind::Variant => {
+                let op = match kind {
+                    UnaryOpKind::Neg => "GDX_OP_NEG",
+                    UnaryOpKind::Pos => "GDX_OP_POS",
+                    UnaryOpKind::BitNot => "GDX_OP_BIT_NOT",
+                    UnaryOpKind::Not => unreachable!(),
+                };
+                write!(self.out, "gdx_variant_unary({op}, ")?;
+                self.gen_expr(expr)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::UnaryOp { kind: UnaryOpKind::Neg, expr } if matches!(*expr.ty, TyKind::Int(_)) => {
+                write!(self.out, "gdx_int_op(GDX_OP_NEG, ")?;
+                self.gen_expr(expr)?;
+                write!(self.out, ", 0)")?;
+            }
+            ExprKind::UnaryOp { kind, expr } => {
+                let op_str = match kind {
+                    UnaryOpKind::Neg => "-",
+                    UnaryOpKind::Pos => "+",
+                    UnaryOpKind::BitNot => "~",
+                    UnaryOpKind::Not => unreachable!(),
+                };
+                write!(self.out, "({op_str}")?;
+                self.gen_expr(expr)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::Call { func, args } => {
+                write!(self.out, "{}(", func_name(self.class.funcs[func.0 as usize]))?;
+                self.gen_args(args)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::CallClosure { callee, args } => {
+                write!(self.out, "gdx_call(")?;
+                self.gen_expr(callee)?;
+                write!(self.out, ", ")?;
+                self.gen_variant_args(args)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::CallBuiltin { func, args } => {
+                let name = match func {
+                    BuiltinFunc::Print => "gdx_print",
+                    BuiltinFunc::Str => "gdx_str",
+                };
+                write!(self.out, "{name}({}, ", args.len())?;
+                self.gen_variant_args(args)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::Ternary { cond, then, else_ } => {
+                write!(self.out, "(")?;
+                self.gen_cond(cond)?;
+                write!(self.out, " ? ")?;
+                self.gen_expr(then)?;
+                write!(self.out, " : ")?;
+                self.gen_expr(else_)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::Is { expr, ty } => {
+                write!(self.out, "((")?;
+                self.gen_expr(expr)?;
+                write!(self.out, ").tag == {})", variant_tag(ty))?;
+            }
+            ExprKind::Lambda(lambda) => {
+                self.gen_lambda(lambda)?;
+            }
+            ExprKind::ToVariant(val) => {
+                write!(self.out, "gdx_variant_from_{}(", variant_suffix(val.ty))?;
+                self.gen_expr(val)?;
+                write!(self.out, ")")?;
+            }
+            ExprKind::FromVariant(val) => {
+                write!(self.out, "gdx_variant_to_{}(", variant_suffix(expr.ty))?;
+                self.gen_expr(val)?;
+                write!(self.out, ")")?;
+            }
+        }
         Ok(())
     }
 
-    fn gen_expr(&mut self, expr: &Expr) -> Result<()> {
-        // match expr {
-        //     Expr::Lit(lit) => {
-        //         self.gen_lit(lit)?;
-        //     }
-        //     _ => unreachable!(),
-        // }
+    /// Emits an expression as a C condition, testing whether its value is truthy.
+    fn gen_cond(&mut self, expr: &Expr<'a>) -> Result<()> {
+        match *expr.ty {
+            TyKind::Variant => {
+                write!(self.out, "gdx_variant_truthy(")?;
+                self.gen_expr(expr)?;
+                write!(self.out, ")")?;
+            }
+            TyKind::Callable => {
+                write!(self.out, "((")?;
+                self.gen_expr(expr)?;
+                write!(self.out, ").fn != NULL)")?;
+            }
+            // Only the empty string is falsy.
+            TyKind::String => {
+                write!(self.out, "((")?;
+                self.gen_expr(expr)?;
+                write!(self.out, ")[0] != '\\0')")?;
+            }
+            _ => self.gen_expr(expr)?,
+        }
         Ok(())
     }
 
-    fn gen_lit(&mut self, lit: &Lit) -> Result<()> {
-        match lit.kind {
-            LitKind::Int(val) => {
-                write!(self.dst, "{:}", val)?;
+    fn gen_args(&mut self, args: &[&Expr<'a>]) -> Result<()> {
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                write!(self.out, ", ")?;
             }
-            _ => todo!(),
+            self.gen_expr(arg)?;
         }
         Ok(())
     }
 
-    fn gen_var_decl(&mut self, var_decl: &VarDef) -> Result<()> {
-        // write!(self.dst, "")
+    /// Emits `Variant` arguments as an array, or `NULL` when there are none.
+    fn gen_variant_args(&mut self, args: &[&Expr<'a>]) -> Result<()> {
+        // An empty initializer list isn't valid C.
+        if args.is_empty() {
+            write!(self.out, "NULL")?;
+        } else {
+            write!(self.out, "(const gdx_variant[]){{")?;
+            self.gen_args(args)?;
+            write!(self.out, "}}")?;
+        }
         Ok(())
     }
+
+    /// Defines the lambda's function and environment, and emits a call creating the closure.
+    /// Captured locals are copied into the environment, so later changes on either side
+    /// aren't seen by the other.
+    fn gen_lambda(&mut self, lambda: &'a Lambda<'a>) -> Result<()> {
+        let id = self.lambda_count;
+        self.lambda_count += 1;
+
+        let outer_out = std::mem::take(&mut self.out);
+        let outer_locals = std::mem::replace(&mut self.locals, lambda.locals);
+        let outer_ret_ty = self.ret_ty.take();
+        writeln!(self.out, "static gdx_variant lambda_{id}(void *env_, const gdx_variant *args) {{")?;
+        if !lambda.captures.is_empty() {
+            writeln!(self.out, "struct lambda_{id}_env *env = env_;")?;
+        } else {
+            writeln!(self.out, "(void)env_;")?;
+        }
+        for capture in lambda.captures {
+            let name = self.local(capture.inner);
+            writeln!(self.out, "{} {name} = env->{name};", c_type(self.locals[capture.inner.0 as usize].ty))?;
+        }
+        // Arguments arrive as `Variant`s, and typed parameters check them.
+        for (i, &param) in lambda.params.iter().enumerate() {
+            let ty = self.locals[param.0 as usize].ty;
+            match *ty {
+                TyKind::Variant => writeln!(self.out, "gdx_variant {} = args[{i}];", self.local(param))?,
+                _ => writeln!(self.out, "{} {} = gdx_variant_to_{}(args[{i}]);", c_type(ty), self.local(param), variant_suffix(ty))?,
+            }
+        }
+        if lambda.params.is_empty() {
+            writeln!(self.out, "(void)args;")?;
+        }
+        self.gen_stmts(lambda.body)?;
+        writeln!(self.out, "return {NIL};")?;
+        writeln!(self.out, "}}")?;
+        let func = std::mem::replace(&mut self.out, outer_out);
+        let inner: Vec<_> = lambda.captures.iter()
+            .map(|capture| (c_type(self.locals[capture.inner.0 as usize].ty), self.local(capture.inner)))
+            .collect();
+        self.locals = outer_locals;
+        self.ret_ty = outer_ret_ty;
+
+        if !inner.is_empty() {
+            writeln!(self.lambdas, "struct lambda_{id}_env {{")?;
+            for (ty, name) in inner.iter() {
+                writeln!(self.lambdas, "{ty} {name};")?;
+            }
+            writeln!(self.lambdas, "}};")?;
+        }
+        self.lambdas.extend_from_slice(&func);
+        let ctor_params: Vec<_> = inner.iter().map(|(ty, name)| format!("{ty} {name}")).collect();
+        writeln!(self.lambdas, "static gdx_closure lambda_{id}_new({}) {{", if inner.is_empty() { "void".to_string() } else { ctor_params.join(", ") })?;
+        if inner.is_empty() {
+            writeln!(self.lambdas, "return (gdx_closure){{ NULL, lambda_{id} }};")?;
+        } else {
+            writeln!(self.lambdas, "struct lambda_{id}_env *env = malloc(sizeof *env);")?;
+            for (_, name) in inner.iter() {
+                writeln!(self.lambdas, "env->{name} = {name};")?;
+            }
+            writeln!(self.lambdas, "return (gdx_closure){{ env, lambda_{id} }};")?;
+        }
+        writeln!(self.lambdas, "}}")?;
+
+        let args: Vec<_> = lambda.captures.iter().map(|capture| self.local(capture.outer)).collect();
+        write!(self.out, "lambda_{id}_new({})", args.join(", "))?;
+        Ok(())
+    }
+
+    /// Returns the C name of a local of the current function. The ID keeps shadowed locals apart.
+    fn local(&self, local: LocalId) -> String {
+        format!("{}_{}", self.locals[local.0 as usize].name.as_str(), local.0)
+    }
+}
+
+/// A `Variant` holding `null`.
+const NIL: &str = "(gdx_variant){ GDX_NIL }";
+
+fn c_type(ty: Ty) -> &'static str {
+    match *ty {
+        TyKind::Int(_) => "int64_t",
+        TyKind::Float => "double",
+        TyKind::Bool => "bool",
+        TyKind::String => "gdx_string",
+        TyKind::Void => "void",
+        TyKind::Variant => "gdx_variant",
+        TyKind::Callable => "gdx_closure",
+        _ => unreachable!("lowering rejects values of type `{ty}`"),
+    }
+}
+
+/// The value of a variable of type `ty` that isn't initialized.
+fn zero(ty: Ty) -> &'static str {
+    match *ty {
+        TyKind::Bool => "false",
+        TyKind::String => "\"\"",
+        TyKind::Variant => NIL,
+        TyKind::Callable => "(gdx_closure){0}",
+        _ => "0",
+    }
+}
+
+/// Names the runtime functions converting `ty` to and from a `Variant`.
+fn variant_suffix(ty: Ty) -> &'static str {
+    match *ty {
+        TyKind::Int(_) => "int",
+        TyKind::Float => "float",
+        TyKind::Bool => "bool",
+        TyKind::String => "string",
+        TyKind::Callable => "callable",
+        _ => unreachable!("`{ty}` values aren't converted to or from `Variant`s"),
+    }
+}
+
+/// The tag of a `Variant` holding a value of type `ty`.
+fn variant_tag(ty: Ty) -> &'static str {
+    match *ty {
+        TyKind::Int(_) => "GDX_INT",
+        TyKind::Float => "GDX_FLOAT",
+        TyKind::Bool => "GDX_BOOL",
+        TyKind::String => "GDX_STRING",
+        TyKind::Callable => "GDX_CALLABLE",
+        _ => unreachable!("`Variant`s don't record holding `{ty}`"),
+    }
+}
+
+/// Spells a string as a C string literal. Octal escapes are used, as they take at most three digits.
+fn c_string(s: &str) -> String {
+    let mut lit = String::from("\"");
+    for &byte in s.as_bytes() {
+        match byte {
+            b'"' | b'\\' => {
+                lit.push('\\');
+                lit.push(byte as char);
+            }
+            // `??` starts a trigraph in older C standards.
+            b' '..=b'~' if byte != b'?' => lit.push(byte as char),
+            _ => lit.push_str(&format!("\\{byte:03o}")),
+        }
+    }
+    lit.push('"');
+    lit
+}
+
+/// Names of the script are prefixed with `gdxu_`, which neither the runtime's `gdx_` nor the C
+/// library uses. Accessors get their own prefix, so no function of the script can take their name.
+fn func_name(func: &Func) -> String {
+    let name = func.name.as_str();
+    match func.kind {
+        FuncKind::Main => "main".to_string(),
+        FuncKind::Static | FuncKind::Method => format!("gdxu_{name}"),
+        FuncKind::Getter => format!("gdxa_get_{name}"),
+        FuncKind::Setter => format!("gdxa_set_{name}"),
+    }
+}
+
+/// Globals share the namespace of functions, as they do in the script.
+fn global_name(global: &Global) -> String {
+    format!("gdxu_{}", global.name.as_str())
+}
+
+fn func_signature(func: &Func) -> String {
+    let params: Vec<_> = func.params.iter().map(|&param| {
+        let local = func.locals[param.0 as usize];
+        format!("{} {}_{}", c_type(local.ty), local.name.as_str(), param.0)
+    }).collect();
+    let params = if params.is_empty() { "void".to_string() } else { params.join(", ") };
+    format!("static {} {}({params})", c_type(func.ret_ty), func_name(func))
+}
+
+/// The runtime operator applied to two `Variant`s.
+fn variant_op(kind: BinOpKind) -> &'static str {
+    match kind {
+        BinOpKind::Add => "GDX_OP_ADD",
+        BinOpKind::Sub => "GDX_OP_SUB",
+        BinOpKind::Mul => "GDX_OP_MUL",
+        BinOpKind::Div => "GDX_OP_DIV",
+        BinOpKind::Mod => "GDX_OP_MOD",
+        BinOpKind::Shl => "GDX_OP_SHL",
+        BinOpKind::Shr => "GDX_OP_SHR",
+        BinOpKind::BitAnd => "GDX_OP_BIT_AND",
+        BinOpKind::BitXor => "GDX_OP_BIT_XOR",
+        BinOpKind::BitOr => "GDX_OP_BIT_OR",
+        BinOpKind::Eq => "GDX_OP_EQ",
+        BinOpKind::Ne => "GDX_OP_NE",
+        BinOpKind::Lt => "GDX_OP_LT",
+        BinOpKind::Le => "GDX_OP_LE",
+        BinOpKind::Gt => "GDX_OP_GT",
+        BinOpKind::Ge => "GDX_OP_GE",
+        BinOpKind::Pow => "GDX_OP_POW",
+        // These have their own code paths.
+        BinOpKind::In | BinOpKind::NotIn | BinOpKind::Is | BinOpKind::IsNot | BinOpKind::As | BinOpKind::And
+            | BinOpKind::Or => unreachable!(),
+    }
+}
+
+fn bin_op(kind: BinOpKind) -> &'static str {
+    match kind {
+        BinOpKind::Add => "+",
+        BinOpKind::Sub => "-",
+        BinOpKind::Mul => "*",
+        BinOpKind::Div => "/",
+        BinOpKind::Mod => "%",
+        BinOpKind::Shl => "<<",
+        BinOpKind::Shr => ">>",
+        BinOpKind::BitAnd => "&",
+        BinOpKind::BitXor => "^",
+        BinOpKind::BitOr => "|",
+        BinOpKind::Eq => "==",
+        BinOpKind::Ne => "!=",
+        BinOpKind::Lt => "<",
+        BinOpKind::Le => "<=",
+        BinOpKind::Gt => ">",
+        BinOpKind::Ge => ">=",
+        BinOpKind::And => "&&",
+        BinOpKind::Or => "||",
+        // These have their own code paths.
+        BinOpKind::Pow | BinOpKind::In | BinOpKind::NotIn | BinOpKind::Is | BinOpKind::IsNot
+            | BinOpKind::As => unreachable!(),
+    }
 }
@@ -0,0 +1,365 @@
+#include <math.h>
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+typedef struct gdx_variant gdx_variant;
+
+/* Strings are immutable and NUL-terminated. Like closure environments, they're never freed. */
+typedef const char *gdx_string;
+
+/* A closure pairs a heap-allocated environment with the function taking it. */
+typedef struct {
+    void *env;
+    gdx_variant (*fn)(void *env, const gdx_variant *args);
+} gdx_closure;
+
+typedef enum {
+    GDX_NIL,
+    GDX_BOOL,
+    GDX_INT,
+    GDX_FLOAT,
+    GDX_STRING,
+    GDX_CALLABLE,
+} gdx_tag;
+
+static const char *const gdx_tag_names[] = { "Nil", "bool", "int", "float", "String", "Callable" };
+
+/* A value of any type, tagged with its type. A zeroed variant is `null`. */
+struct gdx_variant {
+    gdx_tag tag;
+    union {
+        bool b;
+        int64_t i;
+        double f;
+        gdx_string s;
+        gdx_closure c;
+    } as;
+};
+
+typedef enum {
+    GDX_OP_ADD,
+    GDX_OP_SUB,
+    GDX_OP_MUL,
+    GDX_OP_DIV,
+    GDX_OP_MOD,
+    GDX_OP_SHL,
+    GDX_OP_SHR,
+    GDX_OP_BIT_AND,
+    GDX_OP_BIT_XOR,
+    GDX_OP_BIT_OR,
+    GDX_OP_EQ,
+    GDX_OP_NE,
+    GDX_OP_LT,
+    GDX_OP_LE,
+    GDX_OP_GT,
+    GDX_OP_GE,
+    GDX_OP_POW,
+    GDX_OP_IN,
+    GDX_OP_NEG,
+    GDX_OP_POS,
+    GDX_OP_BIT_NOT,
+} gdx_op;
+
+static const char *const gdx_op_names[] = {
+    "+", "-", "*", "/", "%", "<<", ">>", "&", "^", "|", "==", "!=", "<", "<=", ">", ">=", "**", "in", "-", "+", "~",
+};
+
+/* Reports an error the type checker couldn't rule out, and exits. */
+static void gdx_fail(const char *format, ...) {
+    va_list args;
+    va_start(args, format);
+    fputs("runtime error: ", stderr);
+    vfprintf(stderr, format, args);
+    fputc('\n', stderr);
+    va_end(args);
+    exit(1);
+}
+
+static gdx_variant gdx_variant_from_bool(bool b) {
+    return (gdx_variant){ GDX_BOOL, { .b = b } };
+}
+
+static gdx_variant gdx_variant_from_int(int64_t i) {
+    return (gdx_variant){ GDX_INT, { .i = i } };
+}
+
+static gdx_variant gdx_variant_from_float(double f) {
+    return (gdx_variant){ GDX_FLOAT, { .f = f } };
+}
+
+static gdx_variant gdx_variant_from_string(gdx_string s) {
+    return (gdx_variant){ GDX_STRING, { .s = s } };
+}
+
+static gdx_variant gdx_variant_from_callable(gdx_closure c) {
+    return (gdx_variant){ GDX_CALLABLE, { .c = c } };
+}
+
+static void gdx_conversion_failed(gdx_variant v, const char *to) {
+    gdx_fail("cannot convert a Variant holding `%s` to `%s`", gdx_tag_names[v.tag], to);
+}
+
+static bool gdx_variant_to_bool(gdx_variant v) {
+    if (v.tag != GDX_BOOL) {
+        gdx_conversion_failed(v, "bool");
+    }
+    return v.as.b;
+}
+
+/* Integers and floats convert to each other, like they do between typed variables. */
+static int64_t gdx_variant_to_int(gdx_variant v) {
+    if (v.tag == GDX_FLOAT) {
+        return (int64_t)v.as.f;
+    }
+    if (v.tag != GDX_INT) {
+        gdx_conversion_failed(v, "int");
+    }
+    return v.as.i;
+}
+
+static double gdx_variant_to_float(gdx_variant v) {
+    if (v.tag == GDX_INT) {
+        return (double)v.as.i;
+    }
+    if (v.tag != GDX_FLOAT) {
+        gdx_conversion_failed(v, "float");
+    }
+    return v.as.f;
+}
+
+static gdx_string gdx_variant_to_string(gdx_variant v) {
+    if (v.tag != GDX_STRING) {
+        gdx_conversion_failed(v, "String");
+    }
+    return v.as.s;
+}
+
+static gdx_closure gdx_variant_to_callable(gdx_variant v) {
+    if (v.tag != GDX_CALLABLE) {
+        gdx_conversion_failed(v, "Callable");
+    }
+    return v.as.c;
+}
+
+/* Whether `if` takes the branch for the value. */
+static bool gdx_variant_truthy(gdx_variant v) {
+    switch (v.tag) {
+    case GDX_NIL: return false;
+    case GDX_BOOL: return v.as.b;
+    case GDX_INT: return v.as.i != 0;
+    case GDX_FLOAT: return v.as.f != 0.0;
+    case GDX_STRING: return v.as.s[0] != '\0';
+    case GDX_CALLABLE: return v.as.c.fn != NULL;
+    }
+    return false;
+}
+
+static bool gdx_variant_is_numeric(gdx_variant v) {
+    return v.tag == GDX_INT || v.tag == GDX_FLOAT;
+}
+
+/* Values of unrelated types are never equal. */
+static bool gdx_variant_eq(gdx_variant a, gdx_variant b) {
+    if (gdx_variant_is_numeric(a) && gdx_variant_is_numeric(b)) {
+        if (a.tag == GDX_INT && b.tag == GDX_INT) {
+            return a.as.i == b.as.i;
+        }
+        return gdx_variant_to_float(a) == gdx_variant_to_float(b);
+    }
+    if (a.tag != b.tag) {
+        return false;
+    }
+    switch (a.tag) {
+    case GDX_NIL: return true;
+    case GDX_BOOL: return a.as.b == b.as.b;
+    case GDX_STRING: return strcmp(a.as.s, b.as.s) == 0;
+    case GDX_CALLABLE: return a.as.c.fn == b.as.c.fn && a.as.c.env == b.as.c.env;
+    default: return false;
+    }
+}
+
+static gdx_string gdx_string_concat(gdx_string a, gdx_string b) {
+    size_t a_len = strlen(a), b_len = strlen(b);
+    char *s = malloc(a_len + b_len + 1);
+    memcpy(s, a, a_len);
+    memcpy(s + a_len, b, b_len + 1);
+    return s;
+}
+
+static gdx_string gdx_string_format(const char *format, ...) {
+    va_list args;
+    va_start(args, format);
+    int len = vsnprintf(NULL, 0, format, args);
+    va_end(args);
+    char *s = malloc((size_t)len + 1);
+    va_start(args, format);
+    vsnprintf(s, (size_t)len + 1, format, args);
+    va_end(args);
+    return s;
+}
+
+/* Raises `x` to the power `y` by squaring, wrapping on overflow like the other int operators. */
+static int64_t gdx_int_pow(int64_t x, int64_t y) {
+    if (y < 0) {
+        gdx_fail("negative exponent %lld", (long long)y);
+    }
+    uint64_t base = (uint64_t)x, result = 1;
+    for (; y > 0; y >>= 1) {
+        if (y & 1) {
+            result *= base;
+        }
+        base *= base;
+    }
+    return (int64_t)result;
+}
+
+/* Applies an arithmetic operator to two ints. Overflow wraps like in GDScript, and what C leaves undefined fails. */
+static int64_t gdx_int_op(gdx_op op, int64_t x, int64_t y) {
+    switch (op) {
+    case GDX_OP_ADD: return (int64_t)((uint64_t)x + (uint64_t)y);
+    case GDX_OP_SUB: return (int64_t)((uint64_t)x - (uint64_t)y);
+    case GDX_OP_MUL: return (int64_t)((uint64_t)x * (uint64_t)y);
+    case GDX_OP_DIV:
+    case GDX_OP_MOD:
+        if (y == 0) {
+            gdx_fail("division by zero");
+        }
+        if (y == -1) {
+            return op == GDX_OP_DIV ? (int64_t)(0 - (uint64_t)x) : 0;
+        }
+        return op == GDX_OP_DIV ? x / y : x % y;
+    case GDX_OP_SHL:
+    case GDX_OP_SHR:
+        if (y < 0 || y > 63) {
+            gdx_fail("invalid shift amount %lld", (long long)y);
+        }
+        return op == GDX_OP_SHL ? (int64_t)((uint64_t)x << y) : x >> y;
+    case GDX_OP_POW: return gdx_int_pow(x, y);
+    case GDX_OP_BIT_AND: return x & y;
+    case GDX_OP_BIT_XOR: return x ^ y;
+    case GDX_OP_BIT_OR: return x | y;
+    case GDX_OP_NEG: return (int64_t)(0 - (uint64_t)x);
+    default:
+        gdx_fail("invalid operands `int` and `int` for `%s`", gdx_op_names[op]);
+        return 0;
+    }
+}
+
+/* Whether `a in b`. Strings contain their substrings; nothing else is a container yet. */
+static bool gdx_variant_in(gdx_variant a, gdx_variant b) {
+    if (a.tag == GDX_STRING && b.tag == GDX_STRING) {
+        return strstr(b.as.s, a.as.s) != NULL;
+    }
+    gdx_fail("invalid operands `%s` and `%s` for `in`", gdx_tag_names[a.tag], gdx_tag_names[b.tag]);
+    return false;
+}
+
+static gdx_variant gdx_variant_op(gdx_op op, gdx_variant a, gdx_variant b) {
+    if (op == GDX_OP_EQ || op == GDX_OP_NE) {
+        return gdx_variant_from_bool(gdx_variant_eq(a, b) == (op == GDX_OP_EQ));
+    }
+    if (op == GDX_OP_IN) {
+        return gdx_variant_from_bool(gdx_variant_in(a, b));
+    }
+    if (a.tag == GDX_INT && b.tag == GDX_INT) {
+        int64_t x = a.as.i, y = b.as.i;
+        switch (op) {
+        case GDX_OP_LT: return gdx_variant_from_bool(x < y);
+        case GDX_OP_LE: return gdx_variant_from_bool(x <= y);
+        case GDX_OP_GT: return gdx_variant_from_bool(x > y);
+        case GDX_OP_GE: return gdx_variant_from_bool(x >= y);
+        default: return gdx_variant_from_int(gdx_int_op(op, x, y));
+        }
+    } else if (gdx_variant_is_numeric(a) && gdx_variant_is_numeric(b)) {
+        double x = gdx_variant_to_float(a), y = gdx_variant_to_float(b);
+        switch (op) {
+        case GDX_OP_ADD: return gdx_variant_from_float(x + y);
+        case GDX_OP_SUB: return gdx_variant_from_float(x - y);
+        case GDX_OP_MUL: return gdx_variant_from_float(x * y);
+        case GDX_OP_DIV: return gdx_variant_from_float(x / y);
+        case GDX_OP_POW: return gdx_variant_from_float(pow(x, y));
+        case GDX_OP_LT: return gdx_variant_from_bool(x < y);
+        case GDX_OP_LE: return gdx_variant_from_bool(x <= y);
+        case GDX_OP_GT: return gdx_variant_from_bool(x > y);
+        case GDX_OP_GE: return gdx_variant_from_bool(x >= y);
+        default: break;
+        }
+    } else if (a.tag == GDX_STRING && b.tag == GDX_STRING) {
+        int cmp = strcmp(a.as.s, b.as.s);
+        switch (op) {
+        case GDX_OP_ADD: return gdx_variant_from_string(gdx_string_concat(a.as.s, b.as.s));
+        case GDX_OP_LT: return gdx_variant_from_bool(cmp < 0);
+        case GDX_OP_LE: return gdx_variant_from_bool(cmp <= 0);
+        case GDX_OP_GT: return gdx_variant_from_bool(cmp > 0);
+        case GDX_OP_GE: return gdx_variant_from_bool(cmp >= 0);
+        default: break;
+        }
+    }
+    gdx_fail("invalid operands `%s` and `%s` for `%s`", gdx_tag_names[a.tag], gdx_tag_names[b.tag], gdx_op_names[op]);
+    return (gdx_variant){ GDX_NIL };
+}
+
+static gdx_variant gdx_variant_unary(gdx_op op, gdx_variant v) {
+    if (v.tag == GDX_INT) {
+        switch (op) {
+        case GDX_OP_NEG: return gdx_variant_from_int(gdx_int_op(GDX_OP_NEG, v.as.i, 0));
+        case GDX_OP_POS: return v;
+        case GDX_OP_BIT_NOT: return gdx_variant_from_int(~v.as.i);
+        default: break;
+        }
+    } else if (v.tag == GDX_FLOAT) {
+        switch (op) {
+        case GDX_OP_NEG: return gdx_variant_from_float(-v.as.f);
+        case GDX_OP_POS: return v;
+        default: break;
+        }
+    }
+    gdx_fail("invalid operand `%s` for unary `%s`", gdx_tag_names[v.tag], gdx_op_names[op]);
+    return (gdx_variant){ GDX_NIL };
+}
+
+static gdx_variant gdx_call(gdx_closure closure, const gdx_variant *args) {
+    if (closure.fn == NULL) {
+        gdx_fail("calling a null Callable");
+    }
+    return closure.fn(closure.env, args);
+}
+
+/* Converts a value to a string the way `str` does. Whole floats keep a decimal point. */
+static gdx_string gdx_variant_str(gdx_variant v) {
+    switch (v.tag) {
+    case GDX_NIL: return "<null>";
+    case GDX_BOOL: return v.as.b ? "true" : "false";
+    case GDX_INT: return gdx_string_format("%lld", (long long)v.as.i);
+    case GDX_FLOAT: {
+        if (isnan(v.as.f)) {
+            return "nan";
+        }
+        if (isinf(v.as.f)) {
+            return v.as.f < 0 ? "-inf" : "inf";
+        }
+        gdx_string s = gdx_string_format("%.14g", v.as.f);
+        return strspn(s, "-0123456789") == strlen(s) ? gdx_string_concat(s, ".0") : s;
+    }
+    case GDX_STRING: return v.as.s;
+    case GDX_CALLABLE: return "<Callable>";
+    }
+    return "";
+}
+
+static gdx_string gdx_str(int64_t argc, const gdx_variant *args) {
+    gdx_string s = "";
+    for (int64_t i = 0; i < argc; i++) {
+        s = gdx_string_concat(s, gdx_variant_str(args[i]));
+    }
+    return s;
+}
+
+static gdx_variant gdx_print(int64_t argc, const gdx_variant *args) {
+    puts(gdx_str(argc, args));
+    return (gdx_variant){ GDX_NIL };
+}
@@ -1,10 +1,19 @@
-use crate::{ident::{IdentCache, IdentName}};
+use std::cell::{Ref, RefCell};
+
+use crate::{ident::{IdentCache, IdentName}, source_map::{FileId, SourceMap}};
 
 
 
 pub struct Ctx {
     arena: bumpalo::Bump,
     ident_cache: IdentCache,
+    source_map: RefCell<SourceMap>,
+}
+
+impl Default for Ctx {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Ctx {
@@ -12,6 +21,7 @@ impl Ctx {
         Self {
             arena: bumpalo::Bump::new(),
             ident_cache: IdentCache::new(),
+            source_map: RefCell::new(SourceMap::new()),
         }
     }
 
@@ -23,7 +33,20 @@ impl Ctx {
         self.arena.alloc_slice_copy(src)
     }
 
-    pub fn new_ident_name(&self, s: &str) -> IdentName {
+    pub fn alloc_str(&self, src: &str) -> &str {
+        self.arena.alloc_str(src)
+    }
+
+    pub fn new_ident_name(&self, s: &str) -> IdentName<'_> {
         self.ident_cache.new_ident_name(s)
     }
+
+    /// Registers a file with the source map.
+    pub fn add_file(&self, name: &str, src: String) -> FileId {
+        self.source_map.borrow_mut().add_file(name, src)
+    }
+
+    pub fn source_map(&self) -> Ref<'_, SourceMap> {
+        self.source_map.borrow()
+    }
 }
@@ -0,0 +1,595 @@
+use std::fmt::Write;
+
+use internment::Arena;
+use lalrpop_util::ParseError;
+
+use crate::{
+    annotation::AnnotationTarget,
+    check::{self, CheckError, CheckErrorKind},
+    context::Ctx,
+    lexer::{self, LexError, LexErrorKind, Span, TokenKind},
+    parser::{self, SyntaxError, UserErrorKind},
+    resolve::{self, ResolveError, ResolveErrorKind},
+    source_map::{FileId, SourceMap},
+    thir::{self, consts::{ConstError, ConstErrorKind}, lower::{LowerError, LowerErrorKind}},
+    typeck::{self, TypeError, TypeErrorKind},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A message about a span of source code, ready to be shown to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// A stable code such as `L001`, identifying the kind of problem.
+    pub code: &'static str,
+    pub message: String,
+    /// Underlined with `^`. Its message may be empty.
+    pub primary: Label,
+    /// Related places, underlined with `-`.
+    pub secondary: Vec<Label>,
+    pub help: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &'static str, message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity,
+            code,
+            message: message.into(),
+            primary: Label { span, message: String::new() },
+            secondary: Vec::new(),
+            help: Vec::new(),
+        }
+    }
+
+    pub fn error(code: &'static str, message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Error, code, message, span)
+    }
+
+    pub fn warning(code: &'static str, message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Warning, code, message, span)
+    }
+
+    /// Sets the message under the primary span.
+    pub fn with_label(mut self, message: impl Into<String>) -> Self {
+        self.primary.message = message.into();
+        self
+    }
+
+    pub fn with_secondary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.secondary.push(Label { span, message: message.into() });
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    /// Renders the diagnostic with the source lines it refers to, like:
+    ///
+    /// ```text
+    /// error[P003]: unexpected `)`
+    ///  --> a.gd:2:9
+    ///   |
+    /// 2 |     x = )
+    ///   |         ^ expected an expression
+    /// ```
+    pub fn render(&self, source_map: &SourceMap) -> String {
+        let mut out = String::new();
+        let start = source_map.location(self.primary.span.start);
+        let file = source_map.file(start.file);
+        let mut labels: Vec<(&Label, bool)> = vec![(&self.primary, true)];
+        labels.extend(self.secondary.iter().map(|label| (label, false)));
+        labels.sort_by_key(|(label, _)| label.span.start);
+        let width = labels.iter()
+            .map(|(label, _)| source_map.location(label.span.start).line.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let pad = " ".repeat(width);
+
+        writeln!(out, "{}[{}]: {}", self.severity.as_str(), self.code, self.message).unwrap();
+        writeln!(out, "{pad}--> {}:{}:{}", file.name, start.line, start.col).unwrap();
+        writeln!(out, "{pad} |").unwrap();
+        let mut prev = None;
+        for (label, primary) in labels {
+            let loc = source_map.location(label.span.start);
+            let label_file = source_map.file(loc.file);
+            if label_file.id != file.id {
+                writeln!(out, "{pad}::: {}", label_file.name).unwrap();
+            }
+            let line = label_file.line(loc.line);
+            if prev != Some((loc.file, loc.line)) {
+                writeln!(out, "{:>width$} | {}", loc.line, expand_tabs(line)).unwrap();
+            }
+            prev = Some((loc.file, loc.line));
+            // Spans running past the end of the line are underlined up to it.
+            let line_start = label_file.start + label_file.line_start(loc.line);
+            let before = &line[..((label.span.start - line_start) as usize).min(line.len())];
+            let end = (label.span.end.saturating_sub(line_start) as usize).clamp(before.len(), line.len());
+            let marked = expand_tabs(&line[before.len()..end]).chars().count().max(1);
+            let mark = if primary { "^" } else { "-" };
+            let text = format!("{}{} {}", " ".repeat(expand_tabs(before).chars().count()), mark.repeat(marked), label.message);
+            writeln!(out, "{pad} | {}", text.trim_end()).unwrap();
+        }
+        for help in &self.help {
+            writeln!(out, "{pad} = help: {help}").unwrap();
+        }
+        out
+    }
+
+    /// Renders the diagnostic as a JSON object on one line, with 1-based lines and columns.
+    /// `column_utf16` and `end_column_utf16` count UTF-16 code units instead of bytes.
+    pub fn to_json(&self, source_map: &SourceMap) -> String {
+        let mut out = String::new();
+        write!(out, "{{\"severity\":\"{}\",\"code\":\"{}\",\"message\":{}", self.severity.as_str(), self.code, json_string(&self.message)).unwrap();
+        write!(out, ",{}", json_span(self.primary.span, source_map)).unwrap();
+        let labels: Vec<_> = std::iter::once((&self.primary, true))
+            .chain(self.secondary.iter().map(|label| (label, false)))
+            .map(|(label, primary)| format!(
+                "{{{},\"primary\":{primary},\"message\":{}}}",
+                json_span(label.span, source_map),
+                json_string(&label.message),
+            ))
+            .collect();
+        write!(out, ",\"labels\":[{}]", labels.join(",")).unwrap();
+        let help: Vec<_> = self.help.iter().map(|help| json_string(help)).collect();
+        write!(out, ",\"help\":[{}]}}", help.join(",")).unwrap();
+        out
+    }
+}
+
+/// Lexes, parses and checks a file of the source map, collecting every problem found.
+pub fn check_file(ctx: &Ctx, file: FileId) -> Vec<Diagnostic> {
+    let source_map = ctx.source_map();
+    let file = source_map.file(file);
+    let tokens = lexer::tokenize_file(file);
+    let mut diagnostics: Vec<_> = tokens.errors.iter().map(|error| error.to_diagnostic(&source_map)).collect();
+    let (class, errors) = parser::parse_recovering(&file.src, &tokens, ctx);
+    diagnostics.extend(errors.iter().map(|error| error.to_diagnostic(&source_map)));
+    if let Some(class) = class {
+        diagnostics.extend(check::check(class).iter().map(|error| error.to_diagnostic(&source_map)));
+        let (resolution, errors) = resolve::resolve(class, &[]);
+        diagnostics.extend(errors.iter().map(|error| error.to_diagnostic(&source_map)));
+        // The arena borrows from the AST, so it lives in `ctx` too.
+        let tys = ctx.alloc(Arena::new());
+        let (_, errors) = thir::ty::Class::new(class, None, tys);
+        diagnostics.extend(errors.iter().map(|error| error.to_diagnostic(&source_map)));
+        let errors = typeck::check(class, &resolution, tys);
+        diagnostics.extend(errors.iter().map(|error| error.to_diagnostic(&source_map)));
+    }
+    diagnostics
+}
+
+/// Renders diagnostics separated by blank lines.
+pub fn render_all(diagnostics: &[Diagnostic], source_map: &SourceMap) -> String {
+    diagnostics.iter().map(|diagnostic| diagnostic.render(source_map)).collect::<Vec<_>>().join("\n")
+}
+
+/// Renders diagnostics as a JSON array with one diagnostic per line.
+pub fn to_json_all(diagnostics: &[Diagnostic], source_map: &SourceMap) -> String {
+    let items: Vec<_> = diagnostics.iter().map(|diagnostic| format!("  {}", diagnostic.to_json(source_map))).collect();
+    if items.is_empty() {
+        "[]\n".to_owned()
+    } else {
+        format!("[\n{}\n]\n", items.join(",\n"))
+    }
+}
+
+fn json_span(span: Span, source_map: &SourceMap) -> String {
+    let start = source_map.location(span.start);
+    let end = source_map.location(span.end);
+    format!(
+        "\"file\":{},\"line\":{},\"column\":{},\"column_utf16\":{},\"end_line\":{},\"end_column\":{},\"end_column_utf16\":{}",
+        json_string(&source_map.file(start.file).name), start.line, start.col, start.col_utf16, end.line, end.col, end.col_utf16,
+    )
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn expand_tabs(s: &str) -> String {
+    s.replace('\t', "    ")
+}
+
+/// Converts an error of one of the compiler passes into a diagnostic.
+pub trait ToDiagnostic {
+    fn to_diagnostic(&self, source_map: &SourceMap) -> Diagnostic;
+}
+
+impl ToDiagnostic for LexError {
+    fn to_diagnostic(&self, _source_map: &SourceMap) -> Diagnostic {
+        let code = self.kind.code();
+        let error = |message: String| Diagnostic::error(code, message, self.span);
+        match self.kind {
+            LexErrorKind::UnexpectedChar(c) => error(format!("unexpected character `{}`", c.escape_debug())),
+            LexErrorKind::LoneCarriageReturn => error("carriage return without a line feed".into())
+                .with_help("end lines with `\\n` or `\\r\\n`"),
+            LexErrorKind::OddIndentation => error("unindent doesn't match any outer indentation level".into()),
+            LexErrorKind::InconsistentIndentation => error("indentation mixes tabs and spaces differently from the lines above".into())
+                .with_help("indent with either tabs or spaces throughout"),
+            LexErrorKind::UnterminatedString => error("unterminated string".into()).with_label("string starts here"),
+            LexErrorKind::InvalidEscape(c) => error(format!("invalid escape sequence `\\{}`", c.escape_debug())),
+            LexErrorKind::InvalidUnicodeEscape => error("invalid unicode escape".into())
+                .with_help("`\\u` takes 4 hex digits and `\\U` 6, forming a valid code point"),
+            LexErrorKind::InvalidDigit(c) => error(format!("invalid digit `{}` in number", c.escape_debug())),
+            LexErrorKind::MissingDigits => error("number without digits".into()),
+            LexErrorKind::IntegerOutOfRange => error("integer out of range".into())
+                .with_help("integers are 64-bit"),
+        }
+    }
+}
+
+impl ToDiagnostic for SyntaxError {
+    fn to_diagnostic(&self, source_map: &SourceMap) -> Diagnostic {
+        match self {
+            ParseError::InvalidToken { location } => {
+                Diagnostic::error("P001", "invalid token", Span::new(*location, *location))
+            }
+            ParseError::UnrecognizedEof { location, expected } => {
+                Diagnostic::error("P002", "unexpected end of file", Span::new(*location, *location))
+                    .with_label(expected_message(expected))
+            }
+            ParseError::UnrecognizedToken { token: (start, kind, end), expected } => {
+                let span = Span::new(*start, *end);
+                Diagnostic::error("P003", format!("unexpected {}", describe_token(*kind, span, source_map)), span)
+                    .with_label(expected_message(expected))
+            }
+            ParseError::ExtraToken { token: (start, kind, end) } => {
+                let span = Span::new(*start, *end);
+                Diagnostic::error("P004", format!("unexpected {} after the end of the script", describe_token(*kind, span, source_map)), span)
+            }
+            ParseError::User { error } => match error.kind {
+                UserErrorKind::UnknownAccessor => Diagnostic::error("P005", "unknown property accessor", error.span)
+                    .with_help("properties only have `get` and `set` accessors"),
+                UserErrorKind::DuplicateAccessor => Diagnostic::error("P006", "property accessor defined twice", error.span),
+                UserErrorKind::AccessorParam => Diagnostic::error("P007", "wrong number of accessor parameters", error.span)
+                    .with_help("getters take no parameter and setters take one"),
+                UserErrorKind::DuplicateClassName => Diagnostic::error("P008", "`class_name` given twice", error.span),
+                UserErrorKind::DuplicateExtends => Diagnostic::error("P009", "`extends` given twice", error.span),
+                UserErrorKind::MisplacedClassHeader => {
+                    Diagnostic::error("P010", "`class_name` and `extends` must come first", error.span)
+                        .with_help("only class annotations like `@tool` can come before them")
+                }
+            },
+        }
+    }
+}
+
+fn describe_token(kind: TokenKind, span: Span, source_map: &SourceMap) -> String {
+    match kind {
+        TokenKind::Newline => "end of line".to_owned(),
+        TokenKind::Indent => "indent".to_owned(),
+        TokenKind::Dedent => "dedent".to_owned(),
+        TokenKind::Eof => "end of file".to_owned(),
+        _ => format!("`{}`", source_map.span_text(span)),
+    }
+}
+
+/// Describes the tokens LALRPOP expected, which it names after the grammar's terminals.
+fn expected_message(expected: &[String]) -> String {
+    let mut names: Vec<String> = expected.iter().map(|terminal| match terminal.as_str() {
+        "IdentTok" => "identifier".to_owned(),
+        "IntLitTok" | "FloatLitTok" => "number".to_owned(),
+        "StrLitTok" => "string".to_owned(),
+        "StringNameLitTok" => "string name".to_owned(),
+        "NodePathLitTok" => "node path".to_owned(),
+        "GetNodeTok" => "`$` node path".to_owned(),
+        "AnnotationTok" => "annotation".to_owned(),
+        "IndentTok" => "indented block".to_owned(),
+        "DedentTok" => "dedent".to_owned(),
+        "EofTok" => "end of file".to_owned(),
+        "Lf" => "end of line".to_owned(),
+        quoted => format!("`{}`", quoted.trim_matches('"')),
+    }).collect();
+    names.dedup();
+    match names.len() {
+        0 => String::new(),
+        1 => format!("expected {}", names[0]),
+        n if n > 8 => format!("expected one of {}, ...", names[..8].join(", ")),
+        _ => format!("expected one of {}", names.join(", ")),
+    }
+}
+
+impl ToDiagnostic for CheckError {
+    fn to_diagnostic(&self, _source_map: &SourceMap) -> Diagnostic {
+        let span = self.span;
+        match &self.kind {
+            CheckErrorKind::UnknownAnnotation => Diagnostic::error("C001", "unknown annotation", span),
+            CheckErrorKind::AnnotationNotAllowed(target) => {
+                let target = match target {
+                    AnnotationTarget::Class => "a class",
+                    AnnotationTarget::Var => "a member variable",
+                    AnnotationTarget::Func => "a function",
+                    AnnotationTarget::Standalone => "its own line",
+                    AnnotationTarget::Stmt => "a statement",
+                };
+                Diagnostic::error("C002", format!("annotation not allowed on {target}"), span)
+            }
+            CheckErrorKind::AnnotationArgCount { min, max, found } => {
+                let expected = match max {
+                    Some(max) if max == min => format!("{min}"),
+                    Some(max) => format!("{min} to {max}"),
+                    None => format!("at least {min}"),
+                };
+                Diagnostic::error("C003", format!("annotation takes {expected} arguments but {found} were given"), span)
+            }
+            CheckErrorKind::DuplicateAnnotation => Diagnostic::error("C004", "duplicate annotation", span),
+            CheckErrorKind::UnreachableMatchBranch => Diagnostic::warning("C005", "unreachable match branch", span)
+                .with_label("an earlier branch matches everything"),
+            CheckErrorKind::DuplicateMatchPattern => Diagnostic::warning("C006", "pattern already matched by an earlier pattern", span),
+        }
+    }
+}
+
+impl ToDiagnostic for ResolveError {
+    fn to_diagnostic(&self, source_map: &SourceMap) -> Diagnostic {
+        let name = source_map.span_text(self.span);
+        match self.kind {
+            ResolveErrorKind::Undefined => Diagnostic::error("R001", format!("cannot find `{name}` in this scope"), self.span)
+                .with_label("not found"),
+            ResolveErrorKind::Duplicate { previous } => Diagnostic::error("R002", format!("`{name}` is defined twice"), self.span)
+                .with_label("redefined here")
+                .with_secondary(previous, "first defined here"),
+            ResolveErrorKind::UseBeforeDeclaration { decl } => {
+                Diagnostic::error("R003", format!("`{name}` is used before its declaration"), self.span)
+                    .with_secondary(decl, "declared here")
+            }
+            ResolveErrorKind::SelfInStaticFunc => Diagnostic::error("R004", "`self` used in a static function", self.span),
+            ResolveErrorKind::InstanceMemberInStaticFunc => {
+                Diagnostic::error("R005", "instance member used in a static function", self.span)
+                    .with_help("static functions can only use static members")
+            }
+        }
+    }
+}
+
+impl ToDiagnostic for ConstError {
+    fn to_diagnostic(&self, _source_map: &SourceMap) -> Diagnostic {
+        let span = self.span;
+        match self.kind {
+            ConstErrorKind::NotConstant => Diagnostic::error("T001", "expression isn't constant", span),
+            ConstErrorKind::InvalidOperands => Diagnostic::error("T002", "invalid operands in constant expression", span),
+            ConstErrorKind::DivisionByZero => Diagnostic::error("T003", "division by zero in constant expression", span),
+            ConstErrorKind::Overflow => Diagnostic::error("T004", "constant expression overflows", span)
+                .with_help("integers are 64-bit"),
+            ConstErrorKind::EnumValueNotInt => Diagnostic::error("T005", "enum value isn't an integer", span),
+            ConstErrorKind::Invalid => Diagnostic::error("T006", "invalid constant expression", span),
+            ConstErrorKind::Cycle => Diagnostic::error("T016", "constant expression depends on itself", span),
+        }
+    }
+}
+
+impl ToDiagnostic for TypeError<'_> {
+    fn to_diagnostic(&self, source_map: &SourceMap) -> Diagnostic {
+        let span = self.span;
+        match &self.kind {
+            TypeErrorKind::Mismatch { expected, found } => Diagnostic::error("T007", "mismatched types", span)
+                .with_label(format!("expected `{expected}`, found `{found}`")),
+            TypeErrorKind::CannotInfer { found } => {
+                let name = source_map.span_text(span);
+                Diagnostic::error("T008", format!("cannot infer the type of `{name}`"), span)
+                    .with_label(format!("the value is `{found}`"))
+                    .with_help("add a type annotation, or use `=` to keep it untyped")
+            }
+            TypeErrorKind::ArgCount { min, max, found, def } => {
+                let expected = match max {
+                    Some(max) if max == min => min.to_string(),
+                    Some(max) => format!("{min} to {max}"),
+                    None => format!("at least {min}"),
+                };
+                let plural = if max.unwrap_or(*min) == 1 { "" } else { "s" };
+                let diagnostic = Diagnostic::error("T009", format!("function takes {expected} argument{plural} but {found} were given"), span);
+                match def {
+                    Some(def) => diagnostic.with_secondary(*def, "defined here"),
+                    None => diagnostic,
+                }
+            }
+            TypeErrorKind::InvalidOperands { op, lhs, rhs } => {
+                Diagnostic::error("T010", format!("invalid operands `{lhs}` and `{rhs}` for `{}`", op.as_str()), span)
+            }
+            TypeErrorKind::ReturnValueInVoid => Diagnostic::error("T011", "void function returns a value", span),
+            TypeErrorKind::MissingReturnValue { expected } => Diagnostic::error("T012", "missing return value", span)
+                .with_label(format!("expected a `{expected}`")),
+            TypeErrorKind::AssignToConst { def } => {
+                let name = source_map.span_text(span);
+                Diagnostic::error("T013", format!("cannot assign to constant `{name}`"), span)
+                    .with_secondary(*def, "declared here")
+            }
+            TypeErrorKind::NoConstructor { ty, args } => {
+                let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+                Diagnostic::error("T014", format!("no constructor of `{ty}` takes ({})", args.join(", ")), span)
+            }
+            TypeErrorKind::InvalidOperand { op, ty } => {
+                Diagnostic::error("T015", format!("invalid operand `{ty}` for unary `{}`", op.as_str()), span)
+            }
+        }
+    }
+}
+
+impl ToDiagnostic for LowerError {
+    fn to_diagnostic(&self, _source_map: &SourceMap) -> Diagnostic {
+        match self.kind {
+            LowerErrorKind::Unsupported(what) => {
+                Diagnostic::error("G001", format!("code generation doesn't support {what} yet"), self.span)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+
+    /// Checks `src` as the second file of a source map, so that its spans don't start at zero.
+    fn check(src: &str) -> (Ctx, Vec<Diagnostic>) {
+        let ctx = Ctx::new();
+        ctx.add_file("a.gd", "var a = 1\n".to_owned());
+        let file = ctx.add_file("b.gd", src.to_owned());
+        let diagnostics = check_file(&ctx, file);
+        (ctx, diagnostics)
+    }
+
+    #[test]
+    fn collects_all_stages() {
+        let (ctx, diagnostics) = check(indoc! {"
+            @bogus
+            var x = )
+            const C = 1 / 0
+            var s = \"abc
+        "});
+        let codes: Vec<_> = diagnostics.iter().map(|diagnostic| diagnostic.code).collect();
+        assert_eq!(codes, ["L005", "P003", "C001", "T003"]);
+        let source_map = ctx.source_map();
+        assert_eq!(diagnostics[1].render(&source_map), indoc! {"
+            error[P003]: unexpected `)`
+             --> b.gd:2:9
+              |
+            2 | var x = )
+              |         ^ expected one of `!`, `(`, `+`, `-`, `[`, `await`, `func`, `not`, ...
+        "});
+        let json = to_json_all(&diagnostics, &source_map);
+        assert_eq!(json.lines().count(), diagnostics.len() + 2);
+        assert!(json.contains(r#""code":"T003","message":"division by zero in constant expression","file":"b.gd","line":3,"column":11"#));
+    }
+
+    #[test]
+    fn resolve_errors() {
+        let (ctx, diagnostics) = check(indoc! {"
+            func f(x):
+                var x = 1
+                return y
+        "});
+        let source_map = ctx.source_map();
+        let rendered: Vec<_> = diagnostics.iter().map(|diagnostic| diagnostic.render(&source_map)).collect();
+        assert_eq!(rendered, [
+            indoc! {"
+                error[R002]: `x` is defined twice
+                 --> b.gd:2:9
+                  |
+                1 | func f(x):
+                  |        - first defined here
+                2 |     var x = 1
+                  |         ^ redefined here
+            "},
+            indoc! {"
+                error[R001]: cannot find `y` in this scope
+                 --> b.gd:3:12
+                  |
+                3 |     return y
+                  |            ^ not found
+            "},
+        ]);
+    }
+
+    #[test]
+    fn type_errors() {
+        let (ctx, diagnostics) = check(indoc! {"
+            const MAX = 3
+            func f(x: int) -> int:
+                MAX = x
+                return f(1, 2)
+        "});
+        let source_map = ctx.source_map();
+        let rendered: Vec<_> = diagnostics.iter().map(|diagnostic| diagnostic.render(&source_map)).collect();
+        assert_eq!(rendered, [
+            indoc! {"
+                error[T013]: cannot assign to constant `MAX`
+                 --> b.gd:3:5
+                  |
+                1 | const MAX = 3
+                  |       --- declared here
+                3 |     MAX = x
+                  |     ^^^
+            "},
+            indoc! {"
+                error[T009]: function takes 1 argument but 2 were given
+                 --> b.gd:4:12
+                  |
+                2 | func f(x: int) -> int:
+                  |      - defined here
+                4 |     return f(1, 2)
+                  |            ^^^^^^^
+            "},
+        ]);
+
+        let (_, diagnostics) = check(indoc! {"
+            var s := \"a\"
+            var x = s - 1
+            var y = -s
+            var v = Vector2(s)
+        "});
+        let codes: Vec<_> = diagnostics.iter().map(|diagnostic| diagnostic.code).collect();
+        assert_eq!(codes, ["T010", "T015", "T014"]);
+    }
+
+    #[test]
+    fn render() {
+        let (ctx, _) = check(indoc! {"
+            func f():
+            \tvar x = 1
+            \treturn x
+        "});
+        let source_map = ctx.source_map();
+        let start = source_map.files()[1].start;
+        let diagnostic = Diagnostic::error("X001", "mismatched types", Span::new(start + 29, start + 30))
+            .with_label("this is an int")
+            .with_secondary(Span::new(start + 15, start + 16), "declared here")
+            .with_help("convert it first");
+        assert_eq!(diagnostic.render(&source_map), indoc! {"
+            error[X001]: mismatched types
+             --> b.gd:3:9
+              |
+            2 |     var x = 1
+              |         - declared here
+            3 |     return x
+              |            ^ this is an int
+              = help: convert it first
+        "});
+        assert_eq!(diagnostic.to_json(&source_map), concat!(
+            r#"{"severity":"error","code":"X001","message":"mismatched types","#,
+            r#""file":"b.gd","line":3,"column":9,"column_utf16":9,"end_line":3,"end_column":10,"end_column_utf16":10,"#,
+            r#""labels":[{"file":"b.gd","line":3,"column":9,"column_utf16":9,"end_line":3,"end_column":10,"end_column_utf16":10,"#,
+            r#""primary":true,"message":"this is an int"},"#,
+            r#"{"file":"b.gd","line":2,"column":6,"column_utf16":6,"end_line":2,"end_column":7,"end_column_utf16":7,"#,
+            r#""primary":false,"message":"declared here"}],"help":["convert it first"]}"#,
+        ));
+    }
+}
@@ -2,11 +2,15 @@ use std::{path::Path, process::Command};
 
 
 pub fn compile(src_path: &Path, dst_path: &Path) -> Result<(), std::io::Error> {
-    Command::new("cc")
+    let output = Command::new("cc")
         .arg("-o")
         .arg(dst_path)
         .arg(src_path)
+        .arg("-lm")
         .output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(String::from_utf8_lossy(&output.stderr).into_owned()));
+    }
     Ok(())
 }
 
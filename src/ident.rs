@@ -8,12 +8,24 @@ pub struct IdentCache {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct IdentName<'a>(ArenaIntern<'a, str>);
 
+impl<'a> IdentName<'a> {
+    pub fn as_str(self) -> &'a str {
+        self.0.into_ref()
+    }
+}
+
+impl Default for IdentCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl IdentCache {
     pub fn new() -> IdentCache {
         IdentCache { arena: Arena::new() }
     }
 
-    pub fn new_ident_name(&self, ident: &str) -> IdentName {
+    pub fn new_ident_name(&self, ident: &str) -> IdentName<'_> {
         IdentName(self.arena.intern(ident))
     }
 }
@@ -1,6 +1,8 @@
 use std::str::CharIndices;
 use unicode_ident::{is_xid_start, is_xid_continue};
 
+use crate::source_map::SourceFile;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Span {
     pub start: u32,
@@ -36,28 +38,87 @@ impl std::fmt::Debug for Token {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LexError {
-    span: Span,
-    kind: LexErrorKind,
+    pub span: Span,
+    pub kind: LexErrorKind,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LexErrorKind {
+    /// A run of characters that can't start a token, reported by its first character.
     UnexpectedChar(char),
-    UnexpectedEof,
+    /// A `\r` that isn't followed by `\n`. It still ends the line.
+    LoneCarriageReturn,
+    /// A dedent to a column between two indentation levels.
     OddIndentation,
+    /// Indentation mixing tabs and spaces differently from the lines above.
     InconsistentIndentation,
+    UnterminatedString,
+    InvalidEscape(char),
+    InvalidUnicodeEscape,
+    InvalidDigit(char),
+    MissingDigits,
+    IntegerOutOfRange,
+}
+
+impl LexErrorKind {
+    /// A code identifying the kind of error, which never changes meaning once assigned.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexErrorKind::UnexpectedChar(_) => "L001",
+            LexErrorKind::LoneCarriageReturn => "L002",
+            LexErrorKind::OddIndentation => "L003",
+            LexErrorKind::InconsistentIndentation => "L004",
+            LexErrorKind::UnterminatedString => "L005",
+            LexErrorKind::InvalidEscape(_) => "L006",
+            LexErrorKind::InvalidUnicodeEscape => "L007",
+            LexErrorKind::InvalidDigit(_) => "L008",
+            LexErrorKind::MissingDigits => "L009",
+            LexErrorKind::IntegerOutOfRange => "L010",
+        }
+    }
+}
+
+/// Source text that doesn't produce tokens but is kept for tooling and doc comments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriviaKind {
+    /// `# ...`
+    Comment,
+    /// `## ...`, documenting the declaration below it.
+    DocComment,
+    /// A `\` joining two physical lines.
+    LineContinuation,
 }
 
 #[derive(Debug, PartialEq, Eq, Default)]
 pub struct TokenStorage {
     pub tokens: Vec<Token>,
     pub errors: Vec<LexError>,
+    pub trivia: Vec<Trivia>,
+    /// The offset of the start of the source, which spans are relative to.
+    pub start: u32,
 }
 
-pub fn tokenize(source: &str) -> (Vec<Token>, Vec<LexError>) {
+pub fn tokenize(source: &str) -> TokenStorage {
     let mut lex = Lexer::new(source);
     lex.tokenize();
-    (lex.tokens, lex.errors)
+    TokenStorage { tokens: lex.tokens, errors: lex.errors, trivia: lex.trivia, start: 0 }
+}
+
+/// Tokenizes a file of a source map, giving spans in the offsets of the source map.
+pub fn tokenize_file(file: &SourceFile) -> TokenStorage {
+    let mut storage = tokenize(&file.src);
+    let shift = |span: &mut Span| *span = Span::new(span.start + file.start, span.end + file.start);
+    storage.tokens.iter_mut().for_each(|token| shift(&mut token.span));
+    storage.errors.iter_mut().for_each(|error| shift(&mut error.span));
+    storage.trivia.iter_mut().for_each(|trivia| shift(&mut trivia.span));
+    storage.start = file.start;
+    storage
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -66,6 +127,16 @@ enum IndentKind {
     Space
 }
 
+/// A lambda whose `func` keyword has been lexed.
+#[derive(Debug)]
+struct Lambda {
+    /// The bracket nesting around the lambda.
+    nesting: u32,
+    /// Once the colon is followed by a line break, the indentation depth the
+    /// lambda started at. The body ends when the indentation returns there.
+    depth: Option<usize>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 struct Indentation {
     elements: Vec<IndentKind>,
@@ -84,25 +155,29 @@ pub struct Lexer<'a> {
     chars: CharIndices<'a>,
     tokens: Vec<Token>,
     errors: Vec<LexError>,
+    trivia: Vec<Trivia>,
     current_pos: u32,
     current_char: Option<char>,
     token_start: Option<u32>,
     indent_stack: Vec<Indentation>,
     nesting: u32,
+    lambdas: Vec<Lambda>,
 }
 
 impl <'a> Lexer<'a> {
-    pub fn new(source: &str) -> Lexer {
+    pub fn new(source: &str) -> Lexer<'_> {
         Lexer {
             source,
             chars: source.char_indices(),
             tokens: Vec::new(),
             errors: Vec::new(),
+            trivia: Vec::new(),
             current_pos: 0,
             current_char: None,
             token_start: None,
             indent_stack: Vec::new(),
             nesting: 0,
+            lambdas: Vec::new(),
         }
     }
 
@@ -118,10 +193,6 @@ impl <'a> Lexer<'a> {
         self.tokens.last()
     }
 
-    fn prev_error_mut(&mut self) -> Option<&mut LexError> {
-        self.errors.last_mut()
-    }
-
     fn current(&mut self) -> char {
         self.current_char.unwrap()
     }
@@ -131,11 +202,11 @@ impl <'a> Lexer<'a> {
         iter.next().map(|(_, c)| c)
     }
 
-    // fn second(&self) -> Option<char> {
-    //     let mut iter = self.chars.clone();
-    //     iter.next();
-    //     iter.next().map(|(_, c)| c)
-    // }
+    fn second(&self) -> Option<char> {
+        let mut iter = self.chars.clone();
+        iter.next();
+        iter.next().map(|(_, c)| c)
+    }
 
     // fn third(&self) -> Option<char> {
     //     let mut iter = self.chars.clone();
@@ -163,8 +234,12 @@ impl <'a> Lexer<'a> {
         self.token_start = Some(self.current_pos);
     }
 
+    fn current_end(&self) -> u32 {
+        self.current_pos + self.current_char.map_or(1, char::len_utf8) as u32
+    }
+
     fn end_token_incl(&mut self, kind: TokenKind) {
-        let span = Span::new(self.token_start.unwrap(), self.current_pos + 1);
+        let span = Span::new(self.token_start.unwrap(), self.current_end());
         let token = Token::new(kind, span);
         self.tokens.push(token);
     }
@@ -182,7 +257,7 @@ impl <'a> Lexer<'a> {
     // }
 
     fn token_str_incl(&self) -> &str {
-        &self.source[(self.token_start.unwrap() as usize) ..= (self.current_pos as usize)]
+        &self.source[(self.token_start.unwrap() as usize) .. (self.current_end() as usize)]
     }
 
     fn single_char_token(&mut self, kind: TokenKind) {
@@ -191,7 +266,11 @@ impl <'a> Lexer<'a> {
     }
 
     fn error(&mut self, kind: LexErrorKind) {
-        self.errors.push(LexError { span: Span::new(self.current_pos, self.current_pos + 1), kind });
+        self.errors.push(LexError { span: Span::new(self.current_pos, self.current_end()), kind });
+    }
+
+    fn error_span(&mut self, span: Span, kind: LexErrorKind) {
+        self.errors.push(LexError { span, kind });
     }
 
     fn nest(&mut self) {
@@ -209,7 +288,12 @@ pub enum TokenKind {
     Annotation,
     Ident,
     IntLit,
+    FloatLit,
     StrLit,
+    StringNameLit,
+    NodePathLit,
+    /// `$Path/To/Node`, `$"Quoted"` or `%UniqueName`.
+    GetNode,
 
     // Comparison
     Less,
@@ -364,17 +448,41 @@ impl <'a> Lexer<'a> {
                     Some(x) if x.kind != Dedent && x.kind != Newline => self.zero_length_token(Newline, 1),
                     _ => (),
                 }
-                while let Some(_) = self.indent_stack.pop() {
+                while self.indent_stack.pop().is_some() {
                     self.zero_length_token(Dedent, 1);
+                    self.end_lambdas(1);
                 }
                 self.zero_length_token(Eof, 1);
                 return;
             };
             match current_char {
                 ' ' | '\t' => (),
-                c if is_xid_start(c) => {
+                '"' | '\'' => {
+                    self.begin_token();
+                    self.string(false, StrLit);
+                }
+                'r' if matches!(self.first(), Some('"' | '\'')) => {
+                    self.begin_token();
+                    self.bump();
+                    self.string(true, StrLit);
+                }
+                '&' | '^' if matches!(self.first(), Some('"' | '\'')) => {
+                    let kind = if current_char == '&' { StringNameLit } else { NodePathLit };
+                    self.begin_token();
+                    self.bump();
+                    self.string(false, kind);
+                }
+                '$' => {
                     self.begin_token();
-                    self.bump_while(|c| is_xid_continue(c));
+                    self.get_node();
+                }
+                '%' if !self.prev_is_operand() && self.first().is_some_and(|c| matches!(c, '_' | '"' | '\'') || is_xid_start(c)) => {
+                    self.begin_token();
+                    self.get_node();
+                }
+                c if c == '_' || is_xid_start(c) => {
+                    self.begin_token();
+                    self.bump_while(is_xid_continue);
                     let kind = match self.token_str_incl() {
                         "if" => If,
                         "elif" => Elif,
@@ -409,93 +517,98 @@ impl <'a> Lexer<'a> {
                         "var" => Var,
                         "void" => Void,
                         "yield" => Yield,
+                        "and" => And,
+                        "or" => Or,
+                        "not" => Not,
+                        "_" => Underscore,
+                        "INF" | "NAN" => FloatLit,
                         _ => Ident,
                     };
                     self.end_token_incl(kind);
-                }
-                '0' => {
-                    self.begin_token();
-                    match self.bump() {
-                        Some(_) | None => {
-                            self.end_token_incl(IntLit);
-                        }
-                    }
-                }
-                '1'..='9' => {
-                    self.begin_token();
-                    self.bump_while(|c| ('0'..'9').contains(&c));
-                    match i128::from_str_radix(self.token_str_incl(), 10) {
-                        Ok(_) => {
-                            self.end_token_incl(IntLit);
-                        }
-                        Err(_) => {
-
-                        }
+                    if kind == Func {
+                        self.func();
                     }
                 }
-                '+' => {
-                    self.begin_token();
-                    if let Some('=') = self.first() {
-                        self.bump();
-                        self.end_token_incl(PlusEqual);
-                    } else {
-                        self.end_token_incl(Plus);
-                    }
+                '0'..='9' => self.number(),
+                '.' if self.first().is_some_and(|c| c.is_ascii_digit()) => self.number(),
+                '+' => self.longest_match(&[("+", Plus), ("+=", PlusEqual)]),
+                '-' => self.longest_match(&[("-", Minus), ("-=", MinusEqual), ("->", ForwardArrow)]),
+                '*' => self.longest_match(&[("*", Star), ("*=", StarEqual), ("**", StarStar), ("**=", StarStarEqual)]),
+                '/' => self.longest_match(&[("/", Slash), ("/=", SlashEqual)]),
+                '%' => self.longest_match(&[("%", Percent), ("%=", PercentEqual)]),
+                '=' => self.longest_match(&[("=", Equal), ("==", EqualEqual)]),
+                '<' => self.longest_match(&[("<", Less), ("<=", LessEqual), ("<<", LessLess), ("<<=", LessLessEqual)]),
+                '>' => self.longest_match(&[(">", Greater), (">=", GreaterEqual), (">>", GreaterGreater), (">>=", GreaterGreaterEqual)]),
+                '!' => self.longest_match(&[("!", Bang), ("!=", BangEqual)]),
+                '&' => self.longest_match(&[("&", Amp), ("&&", AmpAmp), ("&=", AmpEqual)]),
+                '|' => self.longest_match(&[("|", Pipe), ("||", PipePipe), ("|=", PipeEqual)]),
+                '^' => self.longest_match(&[("^", Caret), ("^=", CaretEqual)]),
+                '.' => self.longest_match(&[(".", Period), ("..", PeriodPeriod)]),
+                '~' => self.single_char_token(Tilde),
+                ',' => self.single_char_token(Comma),
+                ';' => self.single_char_token(SemiColon),
+                ':' => {
+                    self.single_char_token(Colon);
+                    self.colon();
                 }
-                '-' => {
+                '(' => self.bracket_open(ParenthesisOpen),
+                ')' => self.bracket_close(ParenthesisClose),
+                '[' => self.bracket_open(BracketOpen),
+                ']' => self.bracket_close(BracketClose),
+                '{' => self.bracket_open(BraceOpen),
+                '}' => self.bracket_close(BraceClose),
+                '#' => self.comment(),
+                '@' => {
                     self.begin_token();
                     match self.first() {
-                        Some('=') => {
-                            self.bump();
-                            self.end_token_incl(MinusEqual);
+                        Some(c) if c == '_' || is_xid_start(c) => {
+                            self.bump_while(is_xid_continue);
+                            self.end_token_incl(Annotation);
                         }
-                        Some('>') => {
-                            self.bump();
-                            self.end_token_incl(ForwardArrow);
-                        }
-                        _ => self.end_token_incl(Minus),
-                    }
-                }
-                '*' => {
-                    self.begin_token();
-                    if let Some('=') = self.first() {
-                        self.bump();
-                        self.end_token_incl(StarEqual);
-                    } else {
-                        self.end_token_incl(Star);
+                        _ => self.error(LexErrorKind::UnexpectedChar('@')),
                     }
                 }
-                '/' => {
-                    self.begin_token();
-                    if let Some('=') = self.first() {
-                        self.bump();
-                        self.end_token_incl(SlashEqual);
+                '\\' => {
+                    let len = match (self.first(), self.second()) {
+                        (Some('\n'), _) => 1,
+                        (Some('\r'), Some('\n')) => 2,
+                        _ => 0,
+                    };
+                    if len == 0 {
+                        self.error(LexErrorKind::UnexpectedChar('\\'));
                     } else {
-                        self.end_token_incl(Slash);
-                    }
-                }
-                '=' => {
-                    self.begin_token();
-                    let kind = match self.first() {
-                        Some('=') => {
+                        self.begin_token();
+                        for _ in 0..len {
                             self.bump();
-                            EqualEqual
-                        },
-                        _ => Equal,
-                    };
-                    self.end_token_incl(kind);
+                        }
+                        self.push_trivia(TriviaKind::LineContinuation);
+                    }
                 }
-                '(' => self.bracket_open(ParenthesisOpen),
-                ')' => self.bracket_close(ParenthesisClose),
-                ':' => self.single_char_token(Colon),
                 '\n' | '\r' => self.newline(),
                 c => {
-                    self.error(LexErrorKind::UnexpectedChar(c));
+                    self.begin_token();
+                    self.bump_while(is_stray);
+                    self.error_span(Span::new(self.token_start.unwrap(), self.current_end()), LexErrorKind::UnexpectedChar(c));
                 }
             }
         }
     }
 
+    /// Emits the longest of `candidates` that matches at the current position.
+    fn longest_match(&mut self, candidates: &[(&str, TokenKind)]) {
+        self.begin_token();
+        let rest = &self.source[self.current_pos as usize..];
+        let (op, kind) = candidates
+            .iter()
+            .filter(|(op, _)| rest.starts_with(op))
+            .max_by_key(|(op, _)| op.len())
+            .unwrap();
+        for _ in 1..op.len() {
+            self.bump();
+        }
+        self.end_token_incl(*kind);
+    }
+
     fn bracket_open(&mut self, kind: TokenKind) {
         self.single_char_token(kind);
         self.nest();
@@ -506,105 +619,486 @@ impl <'a> Lexer<'a> {
         self.unnest();
     }
 
-    fn newline(&mut self) {
-        if self.nesting > 0 {
+    fn number(&mut self) {
+        self.begin_token();
+        let radix = match (self.current(), self.first()) {
+            ('0', Some('x' | 'X')) => 16,
+            ('0', Some('b' | 'B')) => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.bump();
+            let (value, count) = self.digits(radix, Some(0));
+            if count == 0 {
+                self.error_span(Span::new(self.token_start.unwrap(), self.current_end()), LexErrorKind::MissingDigits);
+            }
+            self.invalid_digits();
+            self.int_token(value);
             return;
         }
-        self.begin_token();
+        let mut is_float = self.current() == '.';
+        let value = if is_float {
+            self.digits(10, None);
+            None
+        } else {
+            let first_digit = self.current().to_digit(10).map(i64::from);
+            self.digits(10, first_digit).0
+        };
+        if !is_float && self.first() == Some('.') {
+            // `1.` is a float too, but `1.foo` and `1..` are not.
+            let fraction = match self.second() {
+                Some(c) => c.is_ascii_digit() || !(is_xid_start(c) || c == '_' || c == '.'),
+                None => true,
+            };
+            if fraction {
+                self.bump();
+                self.digits(10, None);
+                is_float = true;
+            }
+        }
+        if let Some('e' | 'E') = self.first() {
+            self.bump();
+            if let Some('+' | '-') = self.first() {
+                self.bump();
+            }
+            if self.digits(10, None).1 == 0 {
+                self.error_span(Span::new(self.token_start.unwrap(), self.current_end()), LexErrorKind::MissingDigits);
+            }
+            is_float = true;
+        }
+        self.invalid_digits();
+        if is_float {
+            self.end_token_incl(TokenKind::FloatLit);
+        } else {
+            self.int_token(value);
+        }
+    }
+
+    /// Consumes digits of the given radix and `_` separators, accumulating
+    /// them onto `value`, which becomes `None` once it overflows `i64`.
+    fn digits(&mut self, radix: u32, mut value: Option<i64>) -> (Option<i64>, usize) {
+        let mut count = 0;
+        while let Some(c) = self.first() {
+            if c != '_' {
+                let Some(digit) = c.to_digit(radix) else { break };
+                value = value.and_then(|v| v.checked_mul(radix as i64)?.checked_add(digit as i64));
+                count += 1;
+            }
+            self.bump();
+        }
+        (value, count)
+    }
+
+    /// Reports and consumes identifier characters glued to the end of a number, as in `0b102` or `12px`.
+    fn invalid_digits(&mut self) {
+        let Some(c) = self.first().filter(|&c| is_xid_continue(c)) else { return };
+        self.bump();
+        let start = self.current_pos;
+        self.bump_while(is_xid_continue);
+        self.error_span(Span::new(start, self.current_end()), LexErrorKind::InvalidDigit(c));
+    }
+
+    fn int_token(&mut self, value: Option<i64>) {
+        self.end_token_incl(TokenKind::IntLit);
+        if value.is_none() {
+            let span = self.tokens.last().unwrap().span;
+            self.error_span(span, LexErrorKind::IntegerOutOfRange);
+        }
+    }
+
+    /// Lexes the path after `$` or `%`, either quoted or as `/`-separated node names.
+    fn get_node(&mut self) {
+        if let Some('"' | '\'') = self.first() {
+            self.bump();
+            self.string(false, TokenKind::GetNode);
+            return;
+        }
+        let mut segments = 0;
         loop {
-            match (self.current(), self.first()) {
-                ('\n', Some('\n')) => { self.bump(); }
-                ('\n', Some('\r')) => { self.bump(); }
-                ('\r', Some('\n')) => { self.bump(); }
-                ('\r', Some(c)) => {
-                    self.error(LexErrorKind::UnexpectedChar(c));
-                    return;
+            if self.first() == Some('%') {
+                self.bump();
+            }
+            match (self.first(), self.second()) {
+                (Some('.'), Some('.')) => {
+                    self.bump();
+                    self.bump();
                 }
-                ('\r', None) => {
-                    self.error(LexErrorKind::UnexpectedEof);
-                    return;
+                (Some('.'), _) => { self.bump(); }
+                (Some(c), _) if c == '_' || is_xid_start(c) => {
+                    self.bump();
+                    self.bump_while(is_xid_continue);
                 }
                 _ => break,
             }
+            segments += 1;
+            match (self.first(), self.second()) {
+                (Some('/'), Some(c)) if matches!(c, '%' | '.' | '_') || is_xid_start(c) => { self.bump(); }
+                _ => break,
+            }
+        }
+        // A lone `$` is left for the parser to reject.
+        self.end_token_incl(if segments == 0 { TokenKind::Dollar } else { TokenKind::GetNode });
+    }
+
+    /// Whether the previous token ends an operand, making a following `%` the modulo operator.
+    fn prev_is_operand(&mut self) -> bool {
+        use TokenKind::*;
+        matches!(
+            self.prev_token().map(|t| t.kind),
+            Some(Ident | IntLit | FloatLit | StrLit | StringNameLit | NodePathLit | GetNode
+                | ParenthesisClose | BracketClose | BraceClose | Self_)
+        )
+    }
+
+    fn string(&mut self, raw: bool, kind: TokenKind) {
+        let quote = self.current();
+        let triple = self.first() == Some(quote) && self.second() == Some(quote);
+        if triple {
+            self.bump();
+            self.bump();
         }
-        self.end_token_incl(TokenKind::Newline);
-        
-        let mut current_ind = Vec::new();
+        let open = Span::new(self.token_start.unwrap(), self.current_end());
         loop {
-            current_ind.push(match self.first() {
-                Some(' ') => IndentKind::Space,
-                Some('\t') => IndentKind::Tab,
-                _ => break,
-            });
+            match self.first() {
+                None => {
+                    self.error_span(open, LexErrorKind::UnterminatedString);
+                    break;
+                }
+                Some('\n' | '\r') if !triple => {
+                    self.error_span(open, LexErrorKind::UnterminatedString);
+                    break;
+                }
+                Some('\\') => {
+                    self.bump();
+                    match self.first() {
+                        Some(c) if raw && (c == quote || c == '\\') => { self.bump(); }
+                        Some(_) if !raw => self.escape(),
+                        _ => (),
+                    }
+                }
+                Some(c) if c == quote => {
+                    self.bump();
+                    if !triple {
+                        break;
+                    }
+                    if self.first() == Some(quote) && self.second() == Some(quote) {
+                        self.bump();
+                        self.bump();
+                        break;
+                    }
+                }
+                Some(_) => { self.bump(); }
+            }
+        }
+        self.end_token_incl(kind);
+    }
+
+    /// Consumes and validates the escape sequence after the `\` at the current position.
+    fn escape(&mut self) {
+        let start = self.current_pos;
+        let (res, len) = decode_escape(&self.source[start as usize + 1..]);
+        let end = start + 1 + len as u32;
+        while self.first().is_some() && self.current_end() < end {
             self.bump();
         }
-        let mut dedent_count = 0;
-        let mut cur_iter = current_ind.into_iter().peekable();
-        for prev in self.indent_stack.clone().iter() {
-            if cur_iter.peek() == None {
-                dedent_count += 1;
-                continue;
+        if let Err(kind) = res {
+            self.error_span(Span::new(start, end), kind);
+        }
+    }
+
+    fn comment(&mut self) {
+        self.begin_token();
+        let kind = if self.first() == Some('#') { TriviaKind::DocComment } else { TriviaKind::Comment };
+        self.bump_while(|c| c != '\n' && c != '\r');
+        self.push_trivia(kind);
+    }
+
+    fn push_trivia(&mut self, kind: TriviaKind) {
+        let span = Span::new(self.token_start.unwrap(), self.current_end());
+        self.trivia.push(Trivia { kind, span });
+    }
+
+    /// Starts tracking a lambda. Function declarations always have a name
+    /// before the parenthesis, while lambdas don't.
+    fn func(&mut self) {
+        let rest = &self.source[self.current_end() as usize..];
+        if rest.trim_start_matches([' ', '\t']).starts_with('(') {
+            self.lambdas.push(Lambda { nesting: self.nesting, depth: None });
+        }
+    }
+
+    /// Handles the colon ending a lambda's signature. When the body starts on
+    /// the next line, newlines and indentation matter again even inside brackets,
+    /// until the indentation goes back to where the lambda started.
+    fn colon(&mut self) {
+        let rest = &self.source[self.current_end() as usize..];
+        let rest = rest.trim_start_matches([' ', '\t']);
+        let multiline = rest.is_empty() || rest.starts_with(['\n', '\r', '#']);
+        let Some(lambda) = self.lambdas.last_mut() else { return };
+        if lambda.depth.is_some() || lambda.nesting != self.nesting {
+            return;
+        }
+        if multiline {
+            lambda.depth = Some(self.indent_stack.len());
+            self.nesting = 0;
+        } else {
+            self.lambdas.pop();
+        }
+    }
+
+    /// Ends the multi-line lambdas whose indentation has been closed, restoring
+    /// the bracket nesting around them. A lambda outside brackets is part of a
+    /// statement that still needs its newline.
+    fn end_lambdas(&mut self, offset: i64) {
+        while let Some(&Lambda { nesting, depth: Some(depth) }) = self.lambdas.last() {
+            if self.indent_stack.len() > depth {
+                break;
             }
-            for e in prev.elements.iter() {
-                match (e, cur_iter.next()) {
-                    (a, Some(b)) if *a == b => (),
-                    (_, None) => {
-                        // odd dedentation
-                        self.error(LexErrorKind::OddIndentation);
-                        return;
-                    },
-                    _ => {
-                        // indent element mismatch
-                        self.error(LexErrorKind::InconsistentIndentation);
-                        return;
+            self.lambdas.pop();
+            self.nesting = nesting;
+            if nesting == 0 {
+                self.zero_length_token(TokenKind::Newline, offset);
+            }
+        }
+    }
+
+    fn newline(&mut self) {
+        if self.nesting > 0 {
+            self.lone_carriage_return();
+            return;
+        }
+        let start = self.current_pos;
+        let mut current_ind;
+        let mut end;
+        loop {
+            loop {
+                match (self.current(), self.first()) {
+                    ('\n', Some('\n')) => { self.bump(); }
+                    ('\n', Some('\r')) => { self.bump(); }
+                    ('\r', Some('\n')) => { self.bump(); }
+                    ('\r', _) => {
+                        self.lone_carriage_return();
+                        match self.first() {
+                            Some('\n' | '\r') => { self.bump(); }
+                            _ => break,
+                        }
                     }
+                    _ => break,
                 }
             }
+            end = self.current_end();
+
+            current_ind = Vec::new();
+            loop {
+                current_ind.push(match self.first() {
+                    Some(' ') => IndentKind::Space,
+                    Some('\t') => IndentKind::Tab,
+                    _ => break,
+                });
+                self.bump();
+            }
+            // Blank and comment-only lines don't take part in indentation.
+            match self.first() {
+                Some('#') => {
+                    self.bump();
+                    self.comment();
+                    if self.bump().is_none() {
+                        break;
+                    }
+                }
+                Some('\n' | '\r') => { self.bump(); }
+                _ => break,
+            }
+        }
+        self.tokens.push(Token::new(TokenKind::Newline, Span::new(start, end)));
+        if self.first().is_none() {
+            return;
+        }
+
+        // The number of levels the line stays in, and how much of its indentation they take up.
+        let mut depth = 0;
+        let mut width = 0;
+        let mut error = None;
+        for prev in self.indent_stack.iter() {
+            let rest = &current_ind[width..];
+            if rest.is_empty() {
+                break;
+            }
+            let len = prev.elements.len();
+            if rest.len() < len {
+                let odd = rest == &prev.elements[..rest.len()];
+                error = error.or(Some(if odd { LexErrorKind::OddIndentation } else { LexErrorKind::InconsistentIndentation }));
+                break;
+            }
+            // Going by width alone, so that the rest of the file lines up as intended.
+            if rest[..len] != prev.elements[..] {
+                error = error.or(Some(LexErrorKind::InconsistentIndentation));
+            }
+            depth += 1;
+            width += len;
         }
-        for _ in 0..dedent_count {
+        if let Some(kind) = error {
+            self.error_span(Span::new(end, self.current_end()), kind);
+        }
+        // Indentation left over after an odd dedent doesn't start a new level.
+        let new_ind = if depth == self.indent_stack.len() { current_ind.split_off(width) } else { Vec::new() };
+        for _ in depth..self.indent_stack.len() {
             self.indent_stack.pop();
             self.zero_length_token(TokenKind::Dedent, 1);
+            self.end_lambdas(1);
         }
-        let new_ind: Vec<_> = cur_iter.collect();
-        if new_ind.len() != 0 {
+        if !new_ind.is_empty() {
             self.indent_stack.push(Indentation::new(new_ind));
             self.zero_length_token(TokenKind::Indent, 0);
         }
     }
+
+    /// Reports the current character if it's a `\r` without a `\n` after it.
+    fn lone_carriage_return(&mut self) {
+        if self.current() == '\r' && self.first() != Some('\n') {
+            self.error(LexErrorKind::LoneCarriageReturn);
+        }
+    }
+}
+
+/// Whether `c` can't start any token, so that a run of them is reported as one error.
+fn is_stray(c: char) -> bool {
+    matches!(c, '?' | '`') || (c.is_ascii_control() && !matches!(c, '\t' | '\n' | '\r')) || (!c.is_ascii() && !is_xid_start(c))
+}
+
+/// Returns the value of a string literal given the source text of its
+/// `StrLit` token, prefix and quotes included. Malformed escapes are dropped,
+/// since the lexer has already reported them.
+pub fn unescape(text: &str) -> String {
+    let (raw, text) = match text.strip_prefix('r') {
+        Some(text) => (true, text),
+        None => (false, text),
+    };
+    let quote = &text[..1];
+    let delim = if text[1..].starts_with(quote) && text[2..].starts_with(quote) { &text[..3] } else { quote };
+    let body = &text[delim.len()..];
+    let body = body.strip_suffix(delim).unwrap_or(body);
+    if raw {
+        return body.to_owned();
+    }
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(i) = rest.find('\\') {
+        out.push_str(&rest[..i]);
+        let (res, len) = decode_escape(&rest[i + 1..]);
+        if let Ok(Some(c)) = res {
+            out.push(c);
+        }
+        rest = &rest[i + 1 + len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Returns the node path of a `GetNode` token given its source text, keeping
+/// the `%` of unique names.
+pub fn node_path(text: &str) -> String {
+    let (unique, path) = match text.strip_prefix('%') {
+        Some(path) => ("%", path),
+        None => ("", &text[1..]),
+    };
+    if path.starts_with(['"', '\'']) {
+        format!("{unique}{}", unescape(path))
+    } else {
+        format!("{unique}{path}")
+    }
+}
+
+/// Decodes the escape sequence that follows a `\` in a string literal.
+///
+/// Returns the decoded character, or `None` for a line continuation, together
+/// with the number of bytes of `rest` the sequence occupies.
+fn decode_escape(rest: &str) -> (Result<Option<char>, LexErrorKind>, usize) {
+    let Some(c) = rest.chars().next() else {
+        return (Err(LexErrorKind::UnterminatedString), 0);
+    };
+    let ch = match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        'a' => '\x07',
+        'b' => '\x08',
+        'f' => '\x0c',
+        'v' => '\x0b',
+        '"' | '\'' | '\\' => c,
+        '\n' => return (Ok(None), 1),
+        '\r' => return (Ok(None), if rest[1..].starts_with('\n') { 2 } else { 1 }),
+        'u' => return decode_unicode_escape(rest, 4),
+        'U' => return decode_unicode_escape(rest, 6),
+        c => return (Err(LexErrorKind::InvalidEscape(c)), c.len_utf8()),
+    };
+    (Ok(Some(ch)), 1)
+}
+
+/// Decodes `\uXXXX` or `\UXXXXXX`, where `rest` starts at the `u`. A high
+/// surrogate must be directly followed by a `\u` low surrogate.
+fn decode_unicode_escape(rest: &str, digits: usize) -> (Result<Option<char>, LexErrorKind>, usize) {
+    fn hex(s: &str, digits: usize) -> Result<u32, usize> {
+        let len = s.chars().take(digits).take_while(char::is_ascii_hexdigit).count();
+        if len < digits {
+            return Err(len);
+        }
+        Ok(u32::from_str_radix(&s[..len], 16).unwrap())
+    }
+    let mut len = 1 + digits;
+    let mut code = match hex(&rest[1..], digits) {
+        Ok(code) => code,
+        Err(n) => return (Err(LexErrorKind::InvalidUnicodeEscape), 1 + n),
+    };
+    if digits == 4 && (0xd800..0xdc00).contains(&code) {
+        let low = rest[len..].strip_prefix("\\u").map(|s| hex(s, 4));
+        match low {
+            Some(Ok(low @ 0xdc00..=0xdfff)) => {
+                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                len += 6;
+            }
+            _ => return (Err(LexErrorKind::InvalidUnicodeEscape), len),
+        }
+    }
+    match char::from_u32(code) {
+        Some(c) => (Ok(Some(c)), len),
+        None => (Err(LexErrorKind::InvalidUnicodeEscape), len),
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    macro_rules! test {
+        ($src:expr; $($tok:expr, $start:expr, $end:expr);* $(;)?) => {
+            {
+                let TokenStorage { tokens, errors, .. } = tokenize($src);
+                assert_eq!(tokens, vec![
+                    $(
+                        Token::new($tok, Span::new($start, $end)),
+                    )*
+                ]);
+                assert_eq!(errors, vec![]);
+            }
+        };
+    }
+    macro_rules! test_err {
+        ($src:expr; $($tok:expr, $start:expr, $end:expr);* $(;)?) => {
+            {
+                let TokenStorage { tokens, errors, .. } = tokenize($src);
+                assert_eq!(tokens, vec![
+                    $(
+                        Token::new($tok, Span::new($start, $end)),
+                    )*
+                ]);
+                assert_ne!(errors, vec![]);
+            }
+        };
+    }
+
     #[test]
     fn basics() {
-        macro_rules! test {
-            ($src:expr; $($tok:expr, $start:expr, $end:expr);* $(;)?) => {
-                {
-                    let (tokens, errors) = tokenize($src);
-                    assert_eq!(tokens, vec![
-                        $(
-                            Token::new($tok, Span::new($start, $end)),
-                        )*
-                    ]);
-                    assert_eq!(errors, vec![]);
-                }
-            };
-        }
-        macro_rules! test_err {
-            ($src:expr; $($tok:expr, $start:expr, $end:expr);* $(;)?) => {
-                {
-                    let (tokens, errors) = tokenize($src);
-                    assert_eq!(tokens, vec![
-                        $(
-                            Token::new($tok, Span::new($start, $end)),
-                        )*
-                    ]);
-                    assert_ne!(errors, vec![]);
-                }
-            };
-        }
         use TokenKind::*;
         test!(
             "";
@@ -679,6 +1173,7 @@ mod test {
         );
         test_err!(
             "\r";
+            Newline, 0, 1;
             Eof, 1, 1;
         );
         test!(
@@ -702,4 +1197,367 @@ mod test {
             Eof, 11, 11;
         )
     }
+
+    #[test]
+    fn strings() {
+        use TokenKind::*;
+        test!(
+            "\"hello\" 'world'";
+            StrLit, 0, 7;
+            StrLit, 8, 15;
+            Newline, 15, 15;
+            Eof, 15, 15;
+        );
+        test!(
+            "\"\"\"a\n\"b\"\n\"\"\"";
+            StrLit, 0, 12;
+            Newline, 12, 12;
+            Eof, 12, 12;
+        );
+        test!(
+            "r\"\\d\\\"\"";
+            StrLit, 0, 7;
+            Newline, 7, 7;
+            Eof, 7, 7;
+        );
+        test!(
+            "\"\\u00e9\\U01F600\\\n\"";
+            StrLit, 0, 18;
+            Newline, 18, 18;
+            Eof, 18, 18;
+        );
+
+        let errors = tokenize("\"abc\nx").errors;
+        assert_eq!(errors, vec![LexError { span: Span::new(0, 1), kind: LexErrorKind::UnterminatedString }]);
+        let errors = tokenize("'''abc").errors;
+        assert_eq!(errors, vec![LexError { span: Span::new(0, 3), kind: LexErrorKind::UnterminatedString }]);
+        let errors = tokenize("\"a\\qb\"").errors;
+        assert_eq!(errors, vec![LexError { span: Span::new(2, 4), kind: LexErrorKind::InvalidEscape('q') }]);
+        let errors = tokenize("\"\\u12x\"").errors;
+        assert_eq!(errors, vec![LexError { span: Span::new(1, 5), kind: LexErrorKind::InvalidUnicodeEscape }]);
+        let errors = tokenize("\"\\ud800\"").errors;
+        assert_eq!(errors, vec![LexError { span: Span::new(1, 7), kind: LexErrorKind::InvalidUnicodeEscape }]);
+    }
+
+    #[test]
+    fn operators() {
+        use TokenKind::*;
+        test!(
+            "a<<=b**=c>=d->e";
+            Ident, 0, 1;
+            LessLessEqual, 1, 4;
+            Ident, 4, 5;
+            StarStarEqual, 5, 8;
+            Ident, 8, 9;
+            GreaterEqual, 9, 11;
+            Ident, 11, 12;
+            ForwardArrow, 12, 14;
+            Ident, 14, 15;
+            Newline, 15, 15;
+            Eof, 15, 15;
+        );
+        test!(
+            "< <= << > >> >>= ** * % %= && & &= || | |= ! != ^ ^= ~";
+            Less, 0, 1;
+            LessEqual, 2, 4;
+            LessLess, 5, 7;
+            Greater, 8, 9;
+            GreaterGreater, 10, 12;
+            GreaterGreaterEqual, 13, 16;
+            StarStar, 17, 19;
+            Star, 20, 21;
+            Percent, 22, 23;
+            PercentEqual, 24, 26;
+            AmpAmp, 27, 29;
+            Amp, 30, 31;
+            AmpEqual, 32, 34;
+            PipePipe, 35, 37;
+            Pipe, 38, 39;
+            PipeEqual, 40, 42;
+            Bang, 43, 44;
+            BangEqual, 45, 47;
+            Caret, 48, 49;
+            CaretEqual, 50, 52;
+            Tilde, 53, 54;
+            Newline, 54, 54;
+            Eof, 54, 54;
+        );
+        test!(
+            "a.b .. , ; $ _ _x and or not";
+            Ident, 0, 1;
+            Period, 1, 2;
+            Ident, 2, 3;
+            PeriodPeriod, 4, 6;
+            Comma, 7, 8;
+            SemiColon, 9, 10;
+            Dollar, 11, 12;
+            Underscore, 13, 14;
+            Ident, 15, 17;
+            And, 18, 21;
+            Or, 22, 24;
+            Not, 25, 28;
+            Newline, 28, 28;
+            Eof, 28, 28;
+        );
+        test!(
+            "[1,\n2]\n{\n}";
+            BracketOpen, 0, 1;
+            IntLit, 1, 2;
+            Comma, 2, 3;
+            IntLit, 4, 5;
+            BracketClose, 5, 6;
+            Newline, 6, 7;
+            BraceOpen, 7, 8;
+            BraceClose, 9, 10;
+            Newline, 10, 10;
+            Eof, 10, 10;
+        );
+    }
+
+    #[test]
+    fn annotations() {
+        use TokenKind::*;
+        test!(
+            "@export_range(0, 1) @onready var";
+            Annotation, 0, 13;
+            ParenthesisOpen, 13, 14;
+            IntLit, 14, 15;
+            Comma, 15, 16;
+            IntLit, 17, 18;
+            ParenthesisClose, 18, 19;
+            Annotation, 20, 28;
+            Var, 29, 32;
+            Newline, 32, 32;
+            Eof, 32, 32;
+        );
+        test_err!(
+            "@ x";
+            Ident, 2, 3;
+            Newline, 3, 3;
+            Eof, 3, 3;
+        );
+    }
+
+    #[test]
+    fn node_paths() {
+        use TokenKind::*;
+        test!(
+            "$A/%B/../C.d $'x y', %Name &\"sn\" ^\"a/b\" a % b a%b $ /";
+            GetNode, 0, 10;
+            Period, 10, 11;
+            Ident, 11, 12;
+            GetNode, 13, 19;
+            Comma, 19, 20;
+            GetNode, 21, 26;
+            StringNameLit, 27, 32;
+            NodePathLit, 33, 39;
+            Ident, 40, 41;
+            Percent, 42, 43;
+            Ident, 44, 45;
+            Ident, 46, 47;
+            Percent, 47, 48;
+            Ident, 48, 49;
+            Dollar, 50, 51;
+            Slash, 52, 53;
+            Newline, 53, 53;
+            Eof, 53, 53;
+        );
+        assert_eq!(node_path("$A/%B/../C"), "A/%B/../C");
+        assert_eq!(node_path("$'x\\ty'"), "x\ty");
+        assert_eq!(node_path("%Name"), "%Name");
+        assert_eq!(node_path("$%Name"), "%Name");
+        assert_eq!(node_path("%\"Name\""), "%Name");
+    }
+
+    #[test]
+    fn comments() {
+        use TokenKind::*;
+        test!(
+            "a # c\n";
+            Ident, 0, 1;
+            Newline, 5, 6;
+            Eof, 6, 6;
+        );
+        test!(
+            "\n    a\n\n  # c\n        \n    b\n";
+            Newline, 0, 1;
+            Indent, 4, 4;
+            Ident, 5, 6;
+            Newline, 6, 23;
+            Ident, 27, 28;
+            Newline, 28, 29;
+            Dedent, 29, 29;
+            Eof, 29, 29;
+        );
+        test!(
+            "a \\\n  + b\\\r\n";
+            Ident, 0, 1;
+            Plus, 6, 7;
+            Ident, 8, 9;
+            Newline, 12, 12;
+            Eof, 12, 12;
+        );
+        test_err!(
+            "\\ a";
+            Ident, 2, 3;
+            Newline, 3, 3;
+            Eof, 3, 3;
+        );
+
+        let trivia = tokenize("# a\n## b\nc \\\n").trivia;
+        assert_eq!(trivia, vec![
+            Trivia { kind: TriviaKind::Comment, span: Span::new(0, 3) },
+            Trivia { kind: TriviaKind::DocComment, span: Span::new(4, 8) },
+            Trivia { kind: TriviaKind::LineContinuation, span: Span::new(11, 13) },
+        ]);
+    }
+
+    #[test]
+    fn numbers() {
+        use TokenKind::*;
+        test!(
+            "0 9 0xFF 0b1010 1_000_000";
+            IntLit, 0, 1;
+            IntLit, 2, 3;
+            IntLit, 4, 8;
+            IntLit, 9, 15;
+            IntLit, 16, 25;
+            Newline, 25, 25;
+            Eof, 25, 25;
+        );
+        test!(
+            "3.14 1e-5 .5 1. 2E10 INF NAN";
+            FloatLit, 0, 4;
+            FloatLit, 5, 9;
+            FloatLit, 10, 12;
+            FloatLit, 13, 15;
+            FloatLit, 16, 20;
+            FloatLit, 21, 24;
+            FloatLit, 25, 28;
+            Newline, 28, 28;
+            Eof, 28, 28;
+        );
+        test!(
+            "0+1";
+            IntLit, 0, 1;
+            Plus, 1, 2;
+            IntLit, 2, 3;
+            Newline, 3, 3;
+            Eof, 3, 3;
+        );
+
+        let errors = tokenize("9223372036854775807").errors;
+        assert_eq!(errors, vec![]);
+        let errors = tokenize("9223372036854775808").errors;
+        assert_eq!(errors, vec![LexError { span: Span::new(0, 19), kind: LexErrorKind::IntegerOutOfRange }]);
+        let errors = tokenize("0x").errors;
+        assert_eq!(errors, vec![LexError { span: Span::new(0, 2), kind: LexErrorKind::MissingDigits }]);
+        let errors = tokenize("1e+").errors;
+        assert_eq!(errors, vec![LexError { span: Span::new(0, 3), kind: LexErrorKind::MissingDigits }]);
+        let TokenStorage { tokens, errors, .. } = tokenize("0b102");
+        assert_eq!(tokens[0], Token::new(IntLit, Span::new(0, 5)));
+        assert_eq!(errors, vec![LexError { span: Span::new(4, 5), kind: LexErrorKind::InvalidDigit('2') }]);
+    }
+
+    #[test]
+    fn unescape_strings() {
+        assert_eq!(unescape("\"a\\tb\\n\""), "a\tb\n");
+        assert_eq!(unescape("'\\'\\\"\\\\'"), "'\"\\");
+        assert_eq!(unescape("\"\\u00e9\\U01F600\""), "é😀");
+        assert_eq!(unescape("\"\\ud83d\\ude00\""), "😀");
+        assert_eq!(unescape("\"a\\\nb\""), "ab");
+        assert_eq!(unescape("\"\"\"x\ny\"\"\""), "x\ny");
+        assert_eq!(unescape("r'\\n\\''"), "\\n\\'");
+        assert_eq!(unescape("\"\""), "");
+        assert_eq!(unescape("\"open"), "open");
+    }
+
+    #[test]
+    fn lambdas() {
+        use TokenKind::*;
+        let kinds = |src| tokenize(src).tokens.into_iter().map(|tok| tok.kind).collect::<Vec<_>>();
+        assert_eq!(kinds("f(func(x):\n    return x\n)\n"), [
+            Ident, ParenthesisOpen, Func, ParenthesisOpen, Ident, ParenthesisClose, Colon,
+            Newline, Indent, Return, Ident, Newline, Dedent, ParenthesisClose, Newline, Eof,
+        ]);
+        assert_eq!(kinds("var g = func(): # comment\n    pass\nh()\n"), [
+            Var, Ident, Equal, Func, ParenthesisOpen, ParenthesisClose, Colon,
+            Newline, Indent, Pass, Newline, Dedent, Newline, Ident, ParenthesisOpen, ParenthesisClose, Newline, Eof,
+        ]);
+        assert_eq!(kinds("var g = func():\n    pass"), [
+            Var, Ident, Equal, Func, ParenthesisOpen, ParenthesisClose, Colon,
+            Newline, Indent, Pass, Newline, Dedent, Newline, Eof,
+        ]);
+        assert_eq!(kinds("f(func(x: int): return x,\n  1)\n"), [
+            Ident, ParenthesisOpen, Func, ParenthesisOpen, Ident, Colon, Ident, ParenthesisClose, Colon,
+            Return, Ident, Comma, IntLit, ParenthesisClose, Newline, Eof,
+        ]);
+        assert_eq!(kinds("func f():\n    pass\n"), [
+            Func, Ident, ParenthesisOpen, ParenthesisClose, Colon, Newline, Indent, Pass, Newline, Dedent, Eof,
+        ]);
+    }
+
+    #[test]
+    fn recovery() {
+        use TokenKind::*;
+        fn errors(source: &str) -> Vec<(LexErrorKind, &str)> {
+            tokenize(source).errors.into_iter().map(|e| (e.kind, &source[e.span.start as usize..e.span.end as usize])).collect()
+        }
+        assert_eq!(errors("a ?€` b\nc"), vec![(LexErrorKind::UnexpectedChar('?'), "?€`")]);
+        test_err!(
+            "a\rb";
+            Ident, 0, 1;
+            Newline, 1, 2;
+            Ident, 2, 3;
+            Newline, 3, 3;
+            Eof, 3, 3;
+        );
+        assert_eq!(errors("a\r\r\nb(\r)"), vec![(LexErrorKind::LoneCarriageReturn, "\r"), (LexErrorKind::LoneCarriageReturn, "\r")]);
+        assert_eq!(LexErrorKind::LoneCarriageReturn.code(), "L002");
+
+        // An odd dedent goes back to the enclosing level.
+        let source = "a\n    b\n  c\nd\n";
+        assert_eq!(errors(source), vec![(LexErrorKind::OddIndentation, "  ")]);
+        test_err!(
+            source;
+            Ident, 0, 1;
+            Newline, 1, 2;
+            Indent, 5, 5;
+            Ident, 6, 7;
+            Newline, 7, 8;
+            Dedent, 10, 10;
+            Ident, 10, 11;
+            Newline, 11, 12;
+            Ident, 12, 13;
+            Newline, 13, 14;
+            Eof, 14, 14;
+        );
+        // Mismatched tabs and spaces are matched by width, and still allow deeper indents.
+        let source = "a\n\tb\n    c\n c2\n  d\n";
+        assert_eq!(errors(source), vec![
+            (LexErrorKind::InconsistentIndentation, "    "),
+            (LexErrorKind::InconsistentIndentation, " "),
+            (LexErrorKind::InconsistentIndentation, "  "),
+        ]);
+        test_err!(
+            source;
+            Ident, 0, 1;
+            Newline, 1, 2;
+            Indent, 2, 2;
+            Ident, 3, 4;
+            Newline, 4, 5;
+            Indent, 8, 8;
+            Ident, 9, 10;
+            Newline, 10, 11;
+            Dedent, 12, 12;
+            Ident, 12, 14;
+            Newline, 14, 15;
+            Indent, 16, 16;
+            Ident, 17, 18;
+            Newline, 18, 19;
+            Dedent, 19, 19;
+            Dedent, 19, 19;
+            Eof, 19, 19;
+        );
+    }
 }
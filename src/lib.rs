@@ -1,38 +1,62 @@
-mod context;
-mod ast;
-mod codegen;
-mod extcc;
-mod thir;
-mod ident;
-mod lexer;
-mod parser;
+pub mod context;
+pub mod annotation;
+pub mod ast;
+pub mod check;
+pub mod codegen;
+pub mod diagnostics;
+pub mod extcc;
+pub mod thir;
+pub mod ident;
+pub mod lexer;
+pub mod parser;
+pub mod resolve;
+pub mod source_map;
+pub mod typeck;
 
 #[cfg(test)]
 mod test {
-    use std::{path::Path, process::Command};
+    use std::{process::{Command, Output}, sync::atomic::{AtomicUsize, Ordering}};
 
     use indoc::indoc;
     use internment::Arena;
 
-    use self::thir::ty::TyKind;
+    use self::{diagnostics::ToDiagnostic, thir::ty::TyKind};
 
     use super::*;
 
-    fn compile_and_run(src: &str) {
+    /// Returns the exit code, which a top-level `return` sets.
+    fn compile_and_run(src: &str) -> i32 {
+        run(src).status.code().unwrap()
+    }
+
+    fn run(src: &str) -> Output {
         let ctx = context::Ctx::new();
-        let (tokens, error) = lexer::tokenize(src);
-        assert_eq!(error, vec![]);
-        println!("{:?}", tokens);
-        let program = parser::parse(src, &tokens, &ctx).unwrap();
+        let file = ctx.add_file("test.gd", src.to_owned());
+        let diagnostics = diagnostics::check_file(&ctx, file);
+        assert!(diagnostics.is_empty(), "{}", diagnostics::render_all(&diagnostics, &ctx.source_map()));
+        let tokens = lexer::tokenize(src);
+        println!("{:?}", tokens.tokens);
+        let ast = parser::parse(src, &tokens, &ctx).unwrap();
+        let (resolution, _) = resolve::resolve(ast, &[]);
+        let program = thir::lower::lower(&ctx, ast, &resolution, ctx.alloc(Arena::new())).unwrap_or_else(|errors| {
+            let diagnostics: Vec<_> = errors.iter().map(|error| error.to_diagnostic(&ctx.source_map())).collect();
+            panic!("{}", diagnostics::render_all(&diagnostics, &ctx.source_map()))
+        });
         println!("{:?}", program);
-        let c_filename = Path::new("tmp.c");
-        let mut c_file = std::fs::File::create(c_filename).unwrap();
-        let mut cg = codegen::Codegen::new(&program, &mut c_file);
+        // Tests run in parallel, so each program gets its own files.
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!("gdx-test-{}-{}", std::process::id(), COUNT.fetch_add(1, Ordering::Relaxed));
+        let c_filename = std::env::temp_dir().join(format!("{name}.c"));
+        let out_filename = std::env::temp_dir().join(name);
+        let mut c_file = std::fs::File::create(&c_filename).unwrap();
+        let mut cg = codegen::Codegen::new(program, &mut c_file);
         cg.generate().unwrap();
-        let out_filename = Path::new("./tmp");
         extcc::compile(&c_filename, &out_filename).unwrap();
-        let out = Command::new(out_filename).output().unwrap();
+        let out = Command::new(&out_filename).output().unwrap();
+        std::fs::remove_file(c_filename).unwrap();
+        std::fs::remove_file(out_filename).unwrap();
         println!("{out:?}");
+        out
     }
 
     #[test]
@@ -43,6 +67,331 @@ mod test {
                 var x = 2147483647
         "});
 
-        let a: Arena<TyKind> = Arena::new();
+        let _a: Arena<TyKind> = Arena::new();
+    }
+
+    #[test]
+    fn lambdas() {
+        // Locals are captured by copy.
+        assert_eq!(compile_and_run(indoc! {"
+            static func make():
+                var k = 3
+                var triple = func(x): return x * k
+                k = 10
+                return triple.call(2)
+            return make()
+        "}), 6);
+        assert_eq!(compile_and_run(indoc! {"
+            static func make():
+                var base = 1
+                var add = func(a, b):
+                    var sum = a + b
+                    base += sum
+                    return base
+                var first = add.call(2, 3)
+                return first * 10 + add.call(1, 1) + base
+            return make()
+        "}), 64);
+        // Member variables aren't captured, so the lambda sees later changes.
+        assert_eq!(compile_and_run(indoc! {"
+            var k = 3
+            var triple = func(x): return x * k
+            k = 10
+            return triple.call(2)
+        "}), 20);
+        assert_eq!(compile_and_run(indoc! {"
+            var n = 4
+            var outer = func():
+                var inner = func(x): return x + n
+                return inner.call(1)
+            return outer.call()
+        "}), 5);
+    }
+
+    #[test]
+    fn static_members() {
+        assert_eq!(compile_and_run(indoc! {"
+            static var calls = 0
+            static var exit = 40
+            static func count(n):
+                calls += 1
+                return n + calls
+            static func twice(n):
+                return count(count(n))
+            var counter = func(): return count(0)
+            return twice(1) + counter.call() + exit
+        "}), 47);
+    }
+
+    #[test]
+    fn control_flow() {
+        assert_eq!(compile_and_run(indoc! {"
+            static func sign(n):
+                if n < 0:
+                    return -1
+                elif n == 0:
+                    return 0
+                else:
+                    return 1
+            var i = 0
+            var total = 0
+            while true:
+                i += 1
+                if i > 10:
+                    break
+                if i % 2 == 0:
+                    continue
+                total += i
+            return total + sign(-5) + sign(0) * 100 + sign(7) * 10
+        "}), 34);
+    }
+
+    #[test]
+    fn properties() {
+        assert_eq!(compile_and_run(indoc! {"
+            var hp: int = 5:
+                get:
+                    return hp * 2
+                set(value):
+                    if value > 50:
+                        value = 50
+                    hp = value
+            var mp = 3: set = _set_mp, get = _get_mp
+            func _get_mp():
+                return mp + 1
+            func _set_mp(value):
+                mp = value * 10
+            var first = hp
+            hp = 100
+            hp -= 30
+            mp = 2
+            return first * 10 + hp + mp
+        "}), 100 + 100 + 21);
+        // Methods go through the accessors too, and reach plain member variables directly.
+        assert_eq!(compile_and_run(indoc! {"
+            var hp: int = 5:
+                get:
+                    return hp * 2
+                set(value):
+                    hp = min(value, 50)
+            var plain = 1
+            func hit(damage):
+                plain += 1
+                hp -= damage
+                self.hp += 1
+                return hp + self.plain
+            static func min(a, b):
+                return a if a < b else b
+            var first = hit(2)
+            hp = 100
+            return first * 100 + hp + plain
+        "}), (36 * 100 + 100 + 2) % 256);
+    }
+
+    #[test]
+    fn members() {
+        assert_eq!(compile_and_run(indoc! {"
+            var hp = 1
+            var typed: int
+            func heal(amount = 5):
+                hp += amount
+                self.typed = hp * 2
+                return self.hp
+            heal()
+            heal(10)
+            return hp + typed
+        "}), 16 + 32);
+    }
+
+    #[test]
+    fn loops_and_match() {
+        assert_eq!(compile_and_run(indoc! {"
+            var total = 0
+            for i in 4:
+                total += i
+            for i in range(10, 0, -3):
+                if i == 7:
+                    continue
+                total += i * 100
+            var n = 3
+            for i: int in range(1, n):
+                total += i * 10000
+            return total % 256
+        "}), (6 + 1500 + 30000) % 256);
+        assert_eq!(compile_and_run(indoc! {"
+            enum Kind {SMALL, BIG}
+            static func classify(n):
+                match n:
+                    0, 1:
+                        return Kind.SMALL
+                    \"many\":
+                        return -1
+                    var x when x > 100:
+                        return Kind.BIG * 10
+                    _:
+                        return Kind.BIG
+            var sum = 0
+            for i in 3:
+                match i:
+                    1:
+                        continue
+                    _:
+                        sum += i
+            return classify(1) + classify(\"many\") + classify(500) + classify(50) * 100 + sum * 1000
+        "}), (-1 + 10 + 100 + 2000) % 256);
+    }
+
+    #[test]
+    fn strings() {
+        let out = run(indoc! {"
+            const GREETING = \"hello\"
+            var name: String = \"world\"
+            var s = GREETING + \", \" + name
+            name += \"!\"
+            print(s, \" \", name, 1, 2.0, 0.5, true, null)
+            print()
+            var same = \"a\" + \"b\" == \"ab\"
+            var label = str(7) + (\"x\" if same else \"y\")
+            print(label, \"abc\" < \"abd\", \"tab\\tquote\\\"\")
+            return 0
+        "});
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "hello, world world!12.00.5true<null>\n\n7xtruetab\tquote\"\n");
+    }
+
+    #[test]
+    fn runtime_names() {
+        // The runtime has `gdx_fail` and `gdx_call`, and accessors have generated names.
+        assert_eq!(compile_and_run(indoc! {"
+            var call = 1
+            func fail(n):
+                return n + call
+            var hp = 3:
+                get:
+                    return hp * 2
+            func hp__get():
+                return 10
+            return fail(2) + hp + hp__get()
+        "}), 3 + 6 + 10);
+    }
+
+    #[test]
+    fn truthiness() {
+        assert_eq!(compile_and_run(indoc! {"
+            var e := \"\"
+            var full := \"x\"
+            var none: Callable
+            var score = (1 if e else 0) + (10 if not e else 0)
+            if full and not none:
+                score += 100
+            while e:
+                score = 0
+            return score
+        "}), 110);
+    }
+
+    #[test]
+    fn casts_and_tests() {
+        assert_eq!(compile_and_run(indoc! {"
+            var v = 2.5
+            var w = \"s\"
+            var score = 0
+            if v is float:
+                score += 1
+            if w is String and not (w is int):
+                score += 10
+            if v is not int:
+                score += 100
+            var i = v as int
+            return score + i * 1000
+        "}), 2111 % 256);
+    }
+
+    #[test]
+    fn operators() {
+        assert_eq!(compile_and_run(indoc! {"
+            var x = 2 ** 3
+            var typed: int = 3
+            var big := typed ** 40
+            var root := 16 ** 0.5
+            var score = 0
+            if \"ell\" in \"hello\":
+                score += 1
+            var s = \"xyz\"
+            if \"a\" not in s:
+                score += 2
+            # 3 ** 40 wraps around.
+            if big == -6289078614652622815:
+                score += 4
+            return x * 10 + root + score
+        "}), 80 + 4 + 7);
+
+        assert_eq!(compile_and_run(indoc! {"
+            var min: int = -9223372036854775807 - 1
+            var max: int = 9223372036854775807
+            var f: float = 1.5
+            f **= 2
+            var score = 0
+            if min / -1 == min and min % -1 == 0 and -min == min:
+                score += 1
+            max += 1
+            if max == min and min - 1 == 9223372036854775807:
+                score += 2
+            if 1 << 63 == min and min >> 63 == -1:
+                score += 4
+            return score * 10 + f
+        "}), 72);
+        for op in ["/", "%"] {
+            let out = run(&format!("var zero: int = 0\nreturn 1 {op} zero\n"));
+            assert_eq!(String::from_utf8_lossy(&out.stderr), "runtime error: division by zero\n");
+        }
+        let out = run(indoc! {"
+            var n: int = 64
+            n <<= n
+            return n
+        "});
+        assert_eq!(String::from_utf8_lossy(&out.stderr), "runtime error: invalid shift amount 64\n");
+
+        let out = run(indoc! {"
+            var n = 1
+            return 2 in n
+        "});
+        assert_eq!(String::from_utf8_lossy(&out.stderr), "runtime error: invalid operands `int` and `int` for `in`\n");
+
+        let out = run(indoc! {"
+            var n = -1
+            return 2 ** n
+        "});
+        assert_eq!(String::from_utf8_lossy(&out.stderr), "runtime error: negative exponent -1\n");
+    }
+
+    #[test]
+    fn variants() {
+        assert_eq!(compile_and_run(indoc! {"
+            var x = 1
+            x = x + 0.5
+            var y: int = x * 2
+            var typed := 4
+            var mixed = typed / 2.0
+            var n = null
+            if not n and mixed == 2:
+                y += 10
+            var f: float = typed
+            var g = func(a: int) -> int: return a * 2
+            return y + f + g.call(x)
+        "}), 19);
+
+        let out = run(indoc! {"
+            var v = 1
+            var b: bool = v
+            return 5
+        "});
+        assert_eq!(out.status.code(), Some(1));
+        assert_eq!(String::from_utf8_lossy(&out.stderr), "runtime error: cannot convert a Variant holding `int` to `bool`\n");
+
+        let out = run(indoc! {"
+            var c = func(): return 1
+            return c + 1
+        "});
+        assert_eq!(String::from_utf8_lossy(&out.stderr), "runtime error: invalid operands `Callable` and `int` for `+`\n");
     }
 }
@@ -1,3 +1,46 @@
-fn main() {
-    println!("Hello, world!");
+use std::process::ExitCode;
+
+use gdx::{context, diagnostics::{self, Severity}};
+
+/// Checks the scripts given on the command line, printing a diagnostic for each problem.
+/// With `--json`, the diagnostics are printed as a JSON array for other tools to consume.
+fn main() -> ExitCode {
+    let mut json = false;
+    let mut paths = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("usage: gdx [--json] FILE...");
+        return ExitCode::from(2);
+    }
+
+    let ctx = context::Ctx::new();
+    let mut all = Vec::new();
+    for path in &paths {
+        let src = match std::fs::read_to_string(path) {
+            Ok(src) => src,
+            Err(err) => {
+                eprintln!("{path}: {err}");
+                return ExitCode::from(2);
+            }
+        };
+        let file = ctx.add_file(path, src);
+        all.extend(diagnostics::check_file(&ctx, file));
+    }
+
+    let source_map = ctx.source_map();
+    if json {
+        print!("{}", diagnostics::to_json_all(&all, &source_map));
+    } else {
+        eprint!("{}", diagnostics::render_all(&all, &source_map));
+    }
+    if all.iter().any(|diagnostic| diagnostic.severity == Severity::Error) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
 }
@@ -1,18 +1,62 @@
-use std::ops::Range;
+use std::{cell::RefCell, ops::Range};
 
-use lalrpop_util::{lalrpop_mod, ParseError};
+use lalrpop_util::{lalrpop_mod, ErrorRecovery, ParseError};
 
-use crate::{ast::Class, context, ident::{IdentCache, IdentName}, lexer::{self, Span, Token, TokenKind}};
-lalrpop_mod!(gdx);
+use crate::{ast::{Accessor, AccessorKind, Annotation, Attr, Extends, BinOp, BinOpKind, Class, Expr, ExprKind, FuncDef, Ident, Index, Stmt, StmtKind, StmtList, UnaryOp, UnaryOpKind, VarDef}, annotation::{annotation_targets, AnnotationTarget}, context, ident::IdentName, lexer::{Span, TokenKind, TokenStorage, Trivia, TriviaKind}};
+lalrpop_mod!(#[allow(clippy::all)] gdx);
+
+pub type SyntaxError = ParseError<u32, TokenKind, UserError>;
+
+/// An error found while building the AST rather than by the grammar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserError {
+    pub span: Span,
+    pub kind: UserErrorKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserErrorKind {
+    /// A property accessor named something other than `get` or `set`.
+    UnknownAccessor,
+    DuplicateAccessor,
+    /// A getter with a parameter, or a setter without one.
+    AccessorParam,
+    /// A second `class_name` in a script.
+    DuplicateClassName,
+    /// A second `extends` in a script.
+    DuplicateExtends,
+    /// A `class_name` or `extends` line below a member or statement.
+    MisplacedClassHeader,
+}
+
+/// A statement, or a line of annotations waiting for the declaration below it.
+enum Item<'a> {
+    Stmt(&'a Stmt<'a>),
+    Annotations(Vec<&'a Annotation<'a>>),
+    /// A `class_name` or `extends` line, only found at the top of a script.
+    Header(ClassHeader<'a>),
+}
+
+#[derive(Default)]
+struct ClassHeader<'a> {
+    span: Option<Span>,
+    name: Option<&'a Ident<'a>>,
+    extends: Option<&'a Extends<'a>>,
+}
 
 struct Ctx<'a, 'src> {
     src: &'src str,
+    /// The offset of `src` in the source map.
+    start: u32,
+    trivia: &'src [Trivia],
     main_ctx: &'a context::Ctx,
+    /// Errors the parser recovered from.
+    errors: RefCell<Vec<SyntaxError>>,
 }
 
 impl<'a, 'src> Ctx<'a, 'src> {
     fn src(&self, r: Range<u32>) -> &'src str {
-        &self.src[r.start as usize .. r.end as usize]
+        &self.src[(r.start - self.start) as usize .. (r.end - self.start) as usize]
     }
 
     fn span(&self, from: u32, to: u32) -> Span {
@@ -30,31 +74,787 @@ impl<'a, 'src> Ctx<'a, 'src> {
     pub fn slice<T: Copy>(&self, src: &[T]) -> &'a [T] {
         self.main_ctx.alloc_slice_copy(src)
     }
+
+    fn str(&self, s: &str) -> &'a str {
+        self.main_ctx.alloc_str(s)
+    }
+
+    fn expr(&self, span: Span, kind: ExprKind<'a>) -> &'a Expr<'a> {
+        self.alloc(Expr { span, kind })
+    }
+
+    fn bin_op(&self, start: u32, end: u32, kind: BinOpKind, lhs: &'a Expr<'a>, rhs: &'a Expr<'a>) -> &'a Expr<'a> {
+        let span = self.span(start, end);
+        self.expr(span, ExprKind::BinOp(self.alloc(BinOp { span, kind, lhs, rhs })))
+    }
+
+    fn unary_op(&self, start: u32, end: u32, kind: UnaryOpKind, expr: &'a Expr<'a>) -> &'a Expr<'a> {
+        let span = self.span(start, end);
+        self.expr(span, ExprKind::UnaryOp(self.alloc(UnaryOp { span, kind, expr })))
+    }
+
+    fn attr(&self, start: u32, end: u32, expr: &'a Expr<'a>, name: &'a Ident<'a>) -> &'a Expr<'a> {
+        let span = self.span(start, end);
+        self.expr(span, ExprKind::Attr(self.alloc(Attr { span, expr, name })))
+    }
+
+    fn index(&self, start: u32, end: u32, expr: &'a Expr<'a>, index: &'a Expr<'a>) -> &'a Expr<'a> {
+        let span = self.span(start, end);
+        self.expr(span, ExprKind::Index(self.alloc(Index { span, expr, index })))
+    }
+
+    fn user_error(&self, span: Span, kind: UserErrorKind) {
+        self.errors.borrow_mut().push(ParseError::User { error: UserError { span, kind } });
+    }
+
+    /// Records the error behind an error-recovery production.
+    fn recover(&self, recovery: ErrorRecovery<u32, TokenKind, UserError>) {
+        self.errors.borrow_mut().push(recovery.error);
+    }
+
+    fn error_stmt(&self, start: u32, end: u32) -> &'a Stmt<'a> {
+        self.alloc(Stmt { span: self.span(start, end), kind: StmtKind::Error })
+    }
+
+    /// Sorts the accessors of a property into its getter and setter, reporting
+    /// names other than `get` and `set`, repeated accessors and setters without
+    /// exactly one parameter. Accessors with errors are left out.
+    fn accessors(&self, accessors: Vec<(&'a Ident<'a>, &'a Accessor<'a>)>) -> (Option<&'a Accessor<'a>>, Option<&'a Accessor<'a>>) {
+        let (mut getter, mut setter) = (None, None);
+        for (kw, accessor) in accessors {
+            let (slot, is_setter) = match kw.name.as_str() {
+                "get" => (&mut getter, false),
+                "set" => (&mut setter, true),
+                _ => {
+                    self.user_error(kw.span, UserErrorKind::UnknownAccessor);
+                    continue;
+                }
+            };
+            if let AccessorKind::Body { param, .. } = accessor.kind {
+                if param.is_some() != is_setter {
+                    self.user_error(accessor.span, UserErrorKind::AccessorParam);
+                    continue;
+                }
+            }
+            if slot.is_some() {
+                self.user_error(accessor.span, UserErrorKind::DuplicateAccessor);
+                continue;
+            }
+            *slot = Some(accessor);
+        }
+        (getter, setter)
+    }
+
+    /// Returns the text of the `##` comment block directly above the declaration starting at `start`.
+    fn doc(&self, start: u32) -> Option<&'a str> {
+        let mut end = start;
+        let mut lines = Vec::new();
+        for trivia in self.trivia.iter().rev().skip_while(|t| t.span.start >= start) {
+            let gap = self.src(trivia.span.end..end);
+            let before = self.src(self.start..trivia.span.start);
+            let indent = &before[before.rfind('\n').map_or(0, |i| i + 1)..];
+            if trivia.kind != TriviaKind::DocComment
+                || !gap.trim().is_empty()
+                || gap.matches('\n').count() != 1
+                || !indent.trim().is_empty()
+            {
+                break;
+            }
+            let text = self.src(trivia.span.start + 2..trivia.span.end);
+            lines.push(text.strip_prefix(' ').unwrap_or(text).trim_end());
+            end = trivia.span.start;
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        lines.reverse();
+        Some(self.str(&lines.join("\n")))
+    }
 }
 
-pub fn parse<'a, 'src>(source: &'src str, tokens: &[Token], ctx: &'a context::Ctx) -> Result<&'a Class<'a>, ParseError<u32, TokenKind, ()>> {
+impl<'a, 'src> Ctx<'a, 'src> {
+    /// Merges the `class_name` and `extends` lines of a script and takes them out of `items`.
+    /// Only class annotations may come before them, and each can only be given once.
+    fn class_header(&self, items: &mut Vec<Item<'a>>) -> ClassHeader<'a> {
+        let mut header = ClassHeader::default();
+        let mut after_stmt = false;
+        items.retain(|item| {
+            let line = match item {
+                Item::Header(line) => line,
+                Item::Stmt(_) => {
+                    after_stmt = true;
+                    return true;
+                }
+                Item::Annotations(_) => return true,
+            };
+            if after_stmt {
+                self.user_error(line.span.unwrap(), UserErrorKind::MisplacedClassHeader);
+                return false;
+            }
+            if let Some(name) = line.name {
+                match header.name {
+                    Some(_) => self.user_error(name.span, UserErrorKind::DuplicateClassName),
+                    None => header.name = Some(name),
+                }
+            }
+            if let Some(extends) = line.extends {
+                match header.extends {
+                    Some(_) => self.user_error(extends.span, UserErrorKind::DuplicateExtends),
+                    None => header.extends = Some(extends),
+                }
+            }
+            header.span = header.span.or(line.span);
+            false
+        });
+        header
+    }
+
+    /// Takes the class annotations, such as `@tool`, out of the annotation lines at the top of a script,
+    /// before any `class_name` or `extends`.
+    fn class_annotations(&self, items: &mut [Item<'a>]) -> Vec<&'a Annotation<'a>> {
+        let mut class = Vec::new();
+        for item in items.iter_mut() {
+            let Item::Annotations(annotations) = item else { break };
+            annotations.retain(|annotation| {
+                let targets = annotation_targets(annotation.name.name.as_str()).unwrap_or_default();
+                let class_only = targets.contains(&AnnotationTarget::Class)
+                    && !targets.contains(&AnnotationTarget::Var)
+                    && !targets.contains(&AnnotationTarget::Stmt);
+                if class_only {
+                    class.push(*annotation);
+                }
+                !class_only
+            });
+        }
+        class
+    }
+
+    /// Builds a statement list, attaching each annotation line to the declaration that follows it.
+    fn stmt_list(&self, span: Span, items: Vec<Item<'a>>) -> &'a StmtList<'a> {
+        let mut stmts = Vec::new();
+        let mut pending = Vec::new();
+        for item in items {
+            match item {
+                Item::Annotations(annotations) => {
+                    for annotation in annotations {
+                        let targets = annotation_targets(annotation.name.name.as_str()).unwrap_or_default();
+                        if targets.contains(&AnnotationTarget::Standalone) {
+                            stmts.push(self.dangling_annotations(&[annotation]));
+                        } else {
+                            pending.push(annotation);
+                        }
+                    }
+                }
+                Item::Header(_) => unreachable!("taken out by `class_header`"),
+                Item::Stmt(stmt) if pending.is_empty() => stmts.push(stmt),
+                Item::Stmt(stmt) => {
+                    let start = pending[0].span.start;
+                    let span = self.span(start, stmt.span.end);
+                    let doc = self.doc(start);
+                    let kind = match stmt.kind {
+                        StmtKind::VarDef(var_def) => {
+                            pending.extend_from_slice(var_def.annotations);
+                            StmtKind::VarDef(self.alloc(VarDef {
+                                span, annotations: self.slice(&pending), doc: var_def.doc.or(doc), ..*var_def
+                            }))
+                        }
+                        StmtKind::FuncDef(func_def) => {
+                            pending.extend_from_slice(func_def.annotations);
+                            StmtKind::FuncDef(self.alloc(FuncDef {
+                                span, annotations: self.slice(&pending), doc: func_def.doc.or(doc), ..*func_def
+                            }))
+                        }
+                        StmtKind::Class(class) => {
+                            pending.extend_from_slice(class.annotations);
+                            StmtKind::Class(self.alloc(Class {
+                                span, annotations: self.slice(&pending), doc: class.doc.or(doc), ..*class
+                            }))
+                        }
+                        _ => {
+                            stmts.push(self.dangling_annotations(&pending));
+                            pending.clear();
+                            stmts.push(stmt);
+                            continue;
+                        }
+                    };
+                    pending.clear();
+                    stmts.push(self.alloc(Stmt { span, kind }));
+                }
+            }
+        }
+        if !pending.is_empty() {
+            stmts.push(self.dangling_annotations(&pending));
+        }
+        self.alloc(StmtList { span, stmts: self.slice(&stmts) })
+    }
+
+    fn dangling_annotations(&self, annotations: &[&'a Annotation<'a>]) -> &'a Stmt<'a> {
+        let span = annotations[0].span.to(annotations[annotations.len() - 1].span);
+        self.alloc(Stmt { span, kind: StmtKind::Annotations(self.slice(annotations)) })
+    }
+}
+
+/// Parses a script, returning every syntax error if there are any.
+pub fn parse<'a>(source: &str, tokens: &TokenStorage, ctx: &'a context::Ctx) -> Result<&'a Class<'a>, Vec<SyntaxError>> {
+    match parse_recovering(source, tokens, ctx) {
+        (Some(class), errors) if errors.is_empty() => Ok(class),
+        (_, errors) => Err(errors),
+    }
+}
+
+/// Parses a script, skipping broken statements and values, which become
+/// `StmtKind::Error` and `ExprKind::Error` nodes. The class is only `None`
+/// if the parser couldn't recover, in which case the last error is the one it stopped at.
+pub fn parse_recovering<'a>(source: &str, tokens: &TokenStorage, ctx: &'a context::Ctx) -> (Option<&'a Class<'a>>, Vec<SyntaxError>) {
     let stream = tokens
+        .tokens
         .iter()
-        .map(|tok| -> Result<_, ()> { Ok((tok.span.start, tok.kind, tok.span.end)) });
+        .map(|tok| -> Result<_, UserError> { Ok((tok.span.start, tok.kind, tok.span.end)) });
     let parser = gdx::ClassParser::new();
-    let ctx = Ctx { src: source, main_ctx: ctx };
-    parser.parse(&ctx, stream)
+    let ctx = Ctx { src: source, start: tokens.start, trivia: &tokens.trivia, main_ctx: ctx, errors: RefCell::new(Vec::new()) };
+    let result = parser.parse(&ctx, stream);
+    let mut errors = ctx.errors.into_inner();
+    match result {
+        Ok(class) => (Some(class), errors),
+        Err(error) => {
+            errors.push(error);
+            (None, errors)
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::ident::IdentCache;
+    use indoc::indoc;
+
+    use crate::ast::Annotation;
+
+    use crate::{ast::{DictKey, ExprKind, FloatVal, LitKind, StmtKind}, lexer};
 
     use super::*;
 
     fn parse_source(source: &str) {
-        let (tokens, _errors) = lexer::tokenize(source);
+        let tokens = lexer::tokenize(source);
         let ctx = context::Ctx::new();
-        parse(source, &tokens, &ctx);
+        parse(source, &tokens, &ctx).unwrap();
     }
 
     #[test]
     fn test() {
         parse_source("1");
     }
-}
\ No newline at end of file
+
+    fn parse_lit<'a>(source: &str, ctx: &'a context::Ctx) -> LitKind<'a> {
+        let tokens = lexer::tokenize(source);
+        let class = parse(source, &tokens, ctx).unwrap();
+        let StmtKind::Expr(expr) = class.stmt_list.stmts[0].kind else { panic!() };
+        let ExprKind::Lit(lit) = expr.kind else { panic!() };
+        lit.kind
+    }
+
+    #[test]
+    fn literals() {
+        let ctx = context::Ctx::new();
+        assert_eq!(parse_lit("'a\\tb'", &ctx), LitKind::Str("a\tb"));
+        assert_eq!(parse_lit("0xFF", &ctx), LitKind::Int(255));
+        assert_eq!(parse_lit("0b1010", &ctx), LitKind::Int(10));
+        assert_eq!(parse_lit("1_000_000", &ctx), LitKind::Int(1_000_000));
+        assert_eq!(parse_lit("1e-5", &ctx), LitKind::Float(FloatVal(1e-5)));
+        assert_eq!(parse_lit(".5", &ctx), LitKind::Float(FloatVal(0.5)));
+        assert_eq!(parse_lit("INF", &ctx), LitKind::Float(FloatVal(f64::INFINITY)));
+        assert_eq!(parse_lit("&'a\\n'", &ctx), LitKind::StringName("a\n"));
+        assert_eq!(parse_lit("^\"a/b:c\"", &ctx), LitKind::NodePath("a/b:c"));
+    }
+
+    #[test]
+    fn get_node() {
+        let ctx = context::Ctx::new();
+        for (source, path) in [("$A/B", "A/B"), ("$\"A B\"", "A B"), ("%Unique", "%Unique")] {
+            let tokens = lexer::tokenize(source);
+            let class = parse(source, &tokens, &ctx).unwrap();
+            let StmtKind::Expr(expr) = class.stmt_list.stmts[0].kind else { panic!() };
+            let ExprKind::GetNode(get_node) = expr.kind else { panic!() };
+            assert_eq!(get_node.path, path);
+        }
+    }
+
+    #[test]
+    fn doc_comments() {
+        let source = indoc! {"
+            ## The answer.
+            ##   Indented.
+            var x = 42
+            var y = 1 ## Not a doc comment.
+            ## Detached.
+
+            var z = 0
+            # Plain comment.
+            func f():
+                pass
+        "};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parse(source, &tokens, &ctx).unwrap();
+        let docs: Vec<_> = class.stmt_list.stmts.iter().map(|stmt| match stmt.kind {
+            StmtKind::VarDef(var_def) => var_def.doc,
+            StmtKind::FuncDef(func_def) => func_def.doc,
+            _ => panic!(),
+        }).collect();
+        assert_eq!(docs, vec![Some("The answer.\n  Indented."), None, None, None]);
+    }
+
+    #[test]
+    fn annotations() {
+        let source = indoc! {r#"
+            @tool
+            @icon("res://icon.svg")
+            ## Health.
+            @export_range(0, 100, 1)
+            var hp = 10
+            @onready var label = 1
+            @export_group("Stats")
+            @warning_ignore("unused") @rpc
+            func f():
+                pass
+        "#};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parse(source, &tokens, &ctx).unwrap();
+        fn names<'a>(annotations: &[&Annotation<'a>]) -> Vec<&'a str> {
+            annotations.iter().map(|a| a.name.name.as_str()).collect()
+        }
+        assert_eq!(names(class.annotations), vec!["tool", "icon"]);
+        let stmts = class.stmt_list.stmts;
+        let StmtKind::VarDef(hp) = stmts[0].kind else { panic!() };
+        assert_eq!(names(hp.annotations), vec!["export_range"]);
+        assert_eq!(hp.annotations[0].args.len(), 3);
+        assert_eq!(hp.doc, Some("Health."));
+        assert_eq!(stmts[0].span.start, source.find("@export_range").unwrap() as u32);
+        let StmtKind::VarDef(label) = stmts[1].kind else { panic!() };
+        assert_eq!(names(label.annotations), vec!["onready"]);
+        let StmtKind::Annotations(group) = stmts[2].kind else { panic!() };
+        assert_eq!(names(group), vec!["export_group"]);
+        let StmtKind::FuncDef(f) = stmts[3].kind else { panic!() };
+        assert_eq!(names(f.annotations), vec!["warning_ignore", "rpc"]);
+    }
+
+    /// Renders an expression as an s-expression to make its shape easy to compare.
+    fn sexpr(expr: &Expr) -> String {
+        match expr.kind {
+            ExprKind::Ident(ident) => ident.name.as_str().to_owned(),
+            ExprKind::Lit(lit) => match lit.kind {
+                LitKind::Int(val) => val.to_string(),
+                kind => format!("{kind:?}"),
+            },
+            ExprKind::BinOp(op) => format!("({:?} {} {})", op.kind, sexpr(op.lhs), sexpr(op.rhs)),
+            ExprKind::UnaryOp(op) => format!("({:?} {})", op.kind, sexpr(op.expr)),
+            ExprKind::Ternary(t) => format!("(if {} {} {})", sexpr(t.cond), sexpr(t.then), sexpr(t.else_)),
+            ExprKind::Call(call) => {
+                let args: Vec<_> = call.arg_list.args.iter().map(|arg| sexpr(arg)).collect();
+                format!("(call {}{})", sexpr(call.callee), args.iter().map(|arg| format!(" {arg}")).collect::<String>())
+            }
+            ExprKind::Attr(attr) => format!("(. {} {})", sexpr(attr.expr), attr.name.name.as_str()),
+            ExprKind::Index(index) => format!("(index {} {})", sexpr(index.expr), sexpr(index.index)),
+            ExprKind::Await(await_) => format!("(await {})", sexpr(await_.expr)),
+            ExprKind::Array(array) => {
+                format!("[{}]", array.items.iter().map(|item| sexpr(item)).collect::<Vec<_>>().join(" "))
+            }
+            ExprKind::Dict(dict) => {
+                let entries: Vec<_> = dict.entries.iter().map(|entry| match entry.key {
+                    DictKey::Expr(key) => format!("{}: {}", sexpr(key), sexpr(entry.val)),
+                    DictKey::Ident(key) => format!("{} = {}", key.name.as_str(), sexpr(entry.val)),
+                }).collect();
+                format!("{{{}}}", entries.join(", "))
+            }
+            ExprKind::Lambda(lambda) => {
+                let params: Vec<_> = lambda.param_list.params.iter().map(|param| param.name.name.as_str()).collect();
+                format!("(func ({}) {})", params.join(" "), lambda.body.stmts.len())
+            }
+            ExprKind::Self_ => "self".to_owned(),
+            ExprKind::Super => "super".to_owned(),
+            ExprKind::GetNode(get_node) => format!("${}", get_node.path),
+            ExprKind::Error => "error".to_owned(),
+        }
+    }
+
+    fn parse_expr(source: &str) -> String {
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parse(source, &tokens, &ctx).unwrap();
+        let StmtKind::Expr(expr) = class.stmt_list.stmts[0].kind else { panic!() };
+        sexpr(expr)
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(parse_expr("1 + 2 * 3"), "(Add 1 (Mul 2 3))");
+        assert_eq!(parse_expr("(1 + 2) * 3"), "(Mul (Add 1 2) 3)");
+        assert_eq!(parse_expr("1 - 2 - 3"), "(Sub (Sub 1 2) 3)");
+        assert_eq!(parse_expr("a % b / c"), "(Div (Mod a b) c)");
+        assert_eq!(parse_expr("2 ** 3 ** 2"), "(Pow (Pow 2 3) 2)");
+        assert_eq!(parse_expr("-2 ** 2"), "(Neg (Pow 2 2))");
+        assert_eq!(parse_expr("2 ** -1"), "(Pow 2 (Neg 1))");
+        assert_eq!(parse_expr("~a * +b"), "(Mul (BitNot a) (Pos b))");
+        assert_eq!(parse_expr("-~a"), "(Neg (BitNot a))");
+        assert_eq!(parse_expr("~-1"), "(BitNot (Neg 1))");
+        assert_eq!(parse_expr("~-a * b"), "(Mul (BitNot (Neg a)) b)");
+        assert_eq!(parse_expr("a << 1 + 2"), "(Shl a (Add 1 2))");
+        assert_eq!(parse_expr("a & b ^ c | d >> e"), "(BitOr (BitXor (BitAnd a b) c) (Shr d e))");
+        assert_eq!(parse_expr("a | b == c < d"), "(Lt (Eq (BitOr a b) c) d)");
+        assert_eq!(parse_expr("a == b in c"), "(In (Eq a b) c)");
+        assert_eq!(parse_expr("not a in b"), "(Not (In a b))");
+        assert_eq!(parse_expr("a not in b"), "(NotIn a b)");
+        assert_eq!(parse_expr("1 == not x"), "(Eq 1 (Not x))");
+        assert_eq!(parse_expr("a != !b == c and d"), "(And (Ne a (Not (Eq b c))) d)");
+        assert_eq!(parse_expr("a in not b"), "(In a (Not b))");
+        assert_eq!(parse_expr("a in b == not c"), "(In a (Eq b (Not c)))");
+        assert_eq!(parse_expr("not a and b or c && d || !e"), "(Or (Or (And (Not a) b) (And c d)) (Not e))");
+        assert_eq!(parse_expr("a if b else c if d else e"), "(if b a (if d c e))");
+        assert_eq!(parse_expr("a or b if c else d"), "(if c (Or a b) d)");
+        assert_eq!(parse_expr("a if b else c as int"), "(As (if b a c) int)");
+        assert_eq!(parse_expr("a is Node and b is not Node"), "(And (Is a Node) (IsNot b Node))");
+        assert_eq!(parse_expr("-a is Node"), "(Neg (Is a Node))");
+    }
+
+    #[test]
+    fn postfix() {
+        assert_eq!(parse_expr("obj.method(1, 2,)"), "(call (. obj method) 1 2)");
+        assert_eq!(parse_expr("f()"), "(call f)");
+        assert_eq!(parse_expr("dict[\"k\"][i]"), "(index (index dict Str(\"k\")) i)");
+        assert_eq!(parse_expr("super.method()"), "(call (. super method))");
+        assert_eq!(parse_expr("super(x)"), "(call super x)");
+        assert_eq!(parse_expr("self.x"), "(. self x)");
+        assert_eq!(parse_expr("s.match(\"a*\")"), "(call (. s match) Str(\"a*\"))");
+        assert_eq!(parse_expr("a.when"), "(. a when)");
+        assert_eq!(parse_expr("$A.b(c)"), "(call (. $A b) c)");
+        assert_eq!(parse_expr("await a.b(c)[0].d"), "(await (. (index (call (. a b) c) 0) d))");
+        assert_eq!(parse_expr("await sig is X"), "(Is (await sig) X)");
+        assert_eq!(parse_expr("-a.b ** 2"), "(Neg (Pow (. a b) 2))");
+        assert_eq!(parse_expr("f(\n    a,\n    b\n)"), "(call f a b)");
+        assert_eq!(parse_expr("x as Foo.Bar"), "(As x (. Foo Bar))");
+        assert_eq!(parse_expr("x is Array[int]"), "(Is x (index Array int))");
+    }
+
+    #[test]
+    fn collections() {
+        assert_eq!(parse_expr("[]"), "[]");
+        assert_eq!(parse_expr("[1, [2, 3],]"), "[1 [2 3]]");
+        assert_eq!(parse_expr("{}"), "{}");
+        assert_eq!(parse_expr("{\"k\": 1, 2: [x]}"), "{Str(\"k\"): 1, 2: [x]}");
+        assert_eq!(parse_expr("{a = 1, b = c + 1,}"), "{a = 1, b = (Add c 1)}");
+        assert_eq!(parse_expr("{a: b}[a]"), "(index {a: b} a)");
+        assert_eq!(parse_expr(indoc! {"
+            {
+                \"a\": [
+                    1,
+                        2,
+                ],
+              b = {}
+            }"}), "{Str(\"a\"): [1 2], b = {}}");
+    }
+
+    #[test]
+    fn assign() {
+        let source = "a += b * 2\nc = d\n";
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parse(source, &tokens, &ctx).unwrap();
+        let StmtKind::Assign(add) = class.stmt_list.stmts[0].kind else { panic!() };
+        assert_eq!(add.op, Some(BinOpKind::Add));
+        assert_eq!((sexpr(add.target).as_str(), sexpr(add.val).as_str()), ("a", "(Mul b 2)"));
+        let StmtKind::Assign(set) = class.stmt_list.stmts[1].kind else { panic!() };
+        assert_eq!(set.op, None);
+
+        let source = "self.items[0].hp -= 1\n";
+        let tokens = lexer::tokenize(source);
+        let class = parse(source, &tokens, &ctx).unwrap();
+        let StmtKind::Assign(sub) = class.stmt_list.stmts[0].kind else { panic!() };
+        assert_eq!(sexpr(sub.target), "(. (index (. self items) 0) hp)");
+    }
+
+    #[test]
+    fn control_flow() {
+        let source = indoc! {"
+            func f(a, b: int, c := 1):
+                var d
+                if a: return
+                elif b:
+                    pass
+                else: return b
+                while true:
+                    if c: break
+                    continue
+                for x: int in [1, 2]:
+                    for y in x:
+                        pass
+        "};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parse(source, &tokens, &ctx).unwrap();
+        let StmtKind::FuncDef(f) = class.stmt_list.stmts[0].kind else { panic!() };
+        let params = f.param_list.params;
+        assert_eq!((params[0].val, params[1].val.is_some(), params[2].strict_type), (None, false, true));
+        let [var, if_, while_, for_] = f.body.stmts else { panic!() };
+        let StmtKind::VarDef(var) = var.kind else { panic!() };
+        assert_eq!(var.def.val, None);
+
+        let StmtKind::If(if_) = if_.kind else { panic!() };
+        assert_eq!(sexpr(if_.cond), "a");
+        assert_eq!(if_.body.stmts[0].kind, StmtKind::Return(None));
+        assert_eq!(sexpr(if_.elifs[0].cond), "b");
+        assert_eq!(if_.elifs[0].body.stmts[0].kind, StmtKind::Pass);
+        let StmtKind::Return(Some(val)) = if_.else_.unwrap().stmts[0].kind else { panic!() };
+        assert_eq!(sexpr(val), "b");
+
+        let StmtKind::While(while_) = while_.kind else { panic!() };
+        let StmtKind::If(inner) = while_.body.stmts[0].kind else { panic!() };
+        assert_eq!(inner.body.stmts[0].kind, StmtKind::Break);
+        assert_eq!(while_.body.stmts[1].kind, StmtKind::Continue);
+
+        let StmtKind::For(for_) = for_.kind else { panic!() };
+        assert_eq!(for_.var.name.name.as_str(), "x");
+        assert_eq!(for_.var.ty.map(sexpr).as_deref(), Some("int"));
+        assert_eq!(sexpr(for_.iter), "[1 2]");
+        let StmtKind::For(inner) = for_.body.stmts[0].kind else { panic!() };
+        assert_eq!((inner.var.ty, sexpr(inner.iter).as_str()), (None, "x"));
+    }
+
+    #[test]
+    fn class_header() {
+        let source = indoc! {r#"
+            @tool
+            ## A player.
+            class_name Player
+            extends "res://actor.gd".Inner
+
+            class Item extends Resource.Base:
+                var count
+        "#};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parse(source, &tokens, &ctx).unwrap();
+        assert_eq!(class.annotations.len(), 1);
+        assert_eq!(class.name.unwrap().name.as_str(), "Player");
+        assert_eq!(class.doc, Some("A player."));
+        let extends = class.extends.unwrap();
+        assert_eq!(extends.path, Some("res://actor.gd"));
+        assert_eq!(extends.names.iter().map(|name| name.name.as_str()).collect::<Vec<_>>(), ["Inner"]);
+        let [item] = class.stmt_list.stmts else { panic!() };
+        let StmtKind::Class(item) = item.kind else { panic!() };
+        assert_eq!(item.name.unwrap().name.as_str(), "Item");
+        assert_eq!(item.extends.unwrap().names.len(), 2);
+        assert_eq!(item.stmt_list.stmts.len(), 1);
+
+        let source = "extends Node\n";
+        let tokens = lexer::tokenize(source);
+        let class = parse(source, &tokens, &ctx).unwrap();
+        assert_eq!((class.name, class.extends.unwrap().names[0].name.as_str()), (None, "Node"));
+
+        let source = indoc! {"
+            extends Node
+            @abstract
+            class Shape:
+                pass
+            @abstract class Solid extends Shape:
+                pass
+        "};
+        let tokens = lexer::tokenize(source);
+        let class = parse(source, &tokens, &ctx).unwrap();
+        for stmt in class.stmt_list.stmts {
+            let StmtKind::Class(inner) = stmt.kind else { panic!() };
+            assert_eq!(inner.annotations.iter().map(|a| a.name.name.as_str()).collect::<Vec<_>>(), ["abstract"]);
+        }
+    }
+
+    #[test]
+    fn misplaced_class_header() {
+        let source = indoc! {"
+            class_name A extends B
+            extends C
+            class_name D
+            var x
+            extends E
+        "};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let errors = parse(source, &tokens, &ctx).unwrap_err();
+        let errors: Vec<_> = errors.into_iter().map(|error| match error {
+            ParseError::User { error } => (error.kind, &source[error.span.start as usize..error.span.end as usize]),
+            error => panic!("{error:?}"),
+        }).collect();
+        assert_eq!(errors, [
+            (UserErrorKind::DuplicateExtends, "C"),
+            (UserErrorKind::DuplicateClassName, "D"),
+            (UserErrorKind::MisplacedClassHeader, "extends E"),
+        ]);
+    }
+
+    #[test]
+    fn properties() {
+        let source = indoc! {"
+            var hp: int = 10:
+                get:
+                    return hp
+                set(value):
+                    hp = value
+            var mp = 1: set = _set_mp, get = _get_mp
+            var sp:
+                get = _get_sp
+            var xp := 0:
+                set(value): xp = value
+            @export var plain: int
+        "};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parse(source, &tokens, &ctx).unwrap();
+        let defs: Vec<_> = class.stmt_list.stmts.iter().map(|stmt| {
+            let StmtKind::VarDef(var_def) = stmt.kind else { panic!() };
+            var_def
+        }).collect();
+        let [hp, mp, sp, xp, plain] = defs[..] else { panic!() };
+
+        assert_eq!(sexpr(hp.def.ty.unwrap()), "int");
+        assert_eq!(sexpr(hp.def.val.unwrap()), "10");
+        let AccessorKind::Body { param: None, body } = hp.getter.unwrap().kind else { panic!() };
+        assert!(matches!(body.stmts[0].kind, StmtKind::Return(Some(_))));
+        let AccessorKind::Body { param: Some(param), .. } = hp.setter.unwrap().kind else { panic!() };
+        assert_eq!(param.name.as_str(), "value");
+        assert_eq!(&source[hp.span.start as usize..hp.span.end as usize], &source[..source.find("var mp").unwrap()]);
+
+        fn func_name<'a>(accessor: Option<&Accessor<'a>>) -> &'a str {
+            let AccessorKind::Func(func) = accessor.unwrap().kind else { panic!() };
+            func.name.as_str()
+        }
+        assert_eq!((func_name(mp.getter), func_name(mp.setter)), ("_get_mp", "_set_mp"));
+        assert_eq!(&source[mp.span.start as usize..mp.span.end as usize], "var mp = 1: set = _set_mp, get = _get_mp");
+        assert_eq!((func_name(sp.getter), sp.setter, sp.def.ty), ("_get_sp", None, None));
+        assert!(xp.def.strict_type && xp.getter.is_none() && xp.setter.is_some());
+        assert_eq!((plain.annotations.len(), plain.getter, plain.setter), (1, None, None));
+
+        for source in [
+            "var a:\n    fetch: return 1\n",
+            "var a = 1: get = f, get = g\n",
+            "var a:\n    get(value): return 1\n",
+            "var a:\n    set: pass\n",
+        ] {
+            let tokens = lexer::tokenize(source);
+            assert!(parse(source, &tokens, &ctx).is_err(), "{source:?}");
+        }
+    }
+
+    #[test]
+    fn static_members() {
+        let source = indoc! {"
+            static var count := 0
+            @warning_ignore(\"unused\") static func make(x: int) -> int:
+                return x
+            func f():
+                pass
+        "};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parse(source, &tokens, &ctx).unwrap();
+        let [count, make, f] = class.stmt_list.stmts else { panic!() };
+        let StmtKind::VarDef(count) = count.kind else { panic!() };
+        assert!(count.is_static);
+        assert!(count.def.strict_type);
+        let StmtKind::FuncDef(make) = make.kind else { panic!() };
+        assert!(make.is_static);
+        assert_eq!(make.annotations.len(), 1);
+        assert_eq!(make.span.start as usize, source.find('@').unwrap());
+        let StmtKind::FuncDef(f) = f.kind else { panic!() };
+        assert!(!f.is_static);
+    }
+
+    #[test]
+    fn lambdas() {
+        assert_eq!(parse_expr("f(func(x): return x * 2)"), "(call f (func (x) 1))");
+        assert_eq!(parse_expr("arr.map(func(x): return x, 1)"), "(call (. arr map) (func (x) 1) 1)");
+        assert_eq!(parse_expr("f(func(a, b: int) -> int: return a if b else -a)"), "(call f (func (a b) 1))");
+        assert_eq!(parse_expr("(func(): return 1)"), "(func () 1)");
+        assert_eq!(parse_expr("(func(x): return x).call(1)"), "(call (. (func (x) 1) call) 1)");
+        assert_eq!(parse_expr(indoc! {"
+            arr.map(func(x):
+                var y = x * 2
+                return y
+            , 1)
+        "}), "(call (. arr map) (func (x) 2) 1)");
+        assert_eq!(parse_expr(indoc! {"
+            f(func():
+                g(func(): pass)
+                var inner = func():
+                    return 1
+                return inner
+            )
+        "}), "(call f (func () 3))");
+
+        let source = indoc! {"
+            func f():
+                var g = func(x):
+                    if x:
+                        return 1
+                    return 2
+                return g.call(0)
+        "};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parse(source, &tokens, &ctx).unwrap();
+        let StmtKind::FuncDef(f) = class.stmt_list.stmts[0].kind else { panic!() };
+        let [g, ret] = f.body.stmts else { panic!() };
+        let StmtKind::VarDef(g) = g.kind else { panic!() };
+        assert_eq!(sexpr(g.def.val.unwrap()), "(func (x) 2)");
+        let StmtKind::Return(Some(ret)) = ret.kind else { panic!() };
+        assert_eq!(sexpr(ret), "(call (. g call) 0)");
+    }
+
+    #[test]
+    fn recovery() {
+        let source = indoc! {"
+            var a = 1 +
+            func f(x):
+                x = )
+                if x
+                    return 1
+                g(1, +, 3)
+                var b = [1, *]
+            var c:
+                get(v): return 1
+            func g(x y):
+                pass
+            var d = 2
+        "};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let (class, errors) = parse_recovering(source, &tokens, &ctx);
+        let error_starts: Vec<_> = errors.iter().map(|error| match *error {
+            ParseError::UnrecognizedToken { token: (start, ..), .. } => &source[start as usize..],
+            ParseError::User { error } => &source[error.span.start as usize..],
+            _ => panic!("{error:?}"),
+        }).collect();
+        let expected = ["\nfunc f", ")\n", "\n        return", ", 3)", "*]", "get(v)", "y):"];
+        assert_eq!(error_starts.len(), expected.len());
+        for (error_start, expected) in error_starts.iter().zip(expected) {
+            assert!(error_start.starts_with(expected), "{error_start:?}");
+        }
+
+        let [a, f, c, g, d] = class.unwrap().stmt_list.stmts else { panic!() };
+        let StmtKind::VarDef(a) = a.kind else { panic!() };
+        assert_eq!(sexpr(a.def.val.unwrap()), "error");
+        let StmtKind::FuncDef(f) = f.kind else { panic!() };
+        let [assign, if_, call, b] = f.body.stmts else { panic!() };
+        let StmtKind::Assign(assign) = assign.kind else { panic!() };
+        assert_eq!(sexpr(assign.val), "error");
+        assert_eq!(if_.kind, StmtKind::Error);
+        assert!(source[if_.span.start as usize..].starts_with("if x\n        return 1"));
+        let StmtKind::Expr(call) = call.kind else { panic!() };
+        assert_eq!(sexpr(call), "(call g 1 error 3)");
+        let StmtKind::VarDef(b) = b.kind else { panic!() };
+        assert_eq!(sexpr(b.def.val.unwrap()), "[1 error]");
+        let StmtKind::VarDef(c) = c.kind else { panic!() };
+        assert_eq!((c.getter, c.setter), (None, None));
+        assert_eq!(g.kind, StmtKind::Error);
+        assert!(matches!(d.kind, StmtKind::VarDef(_)));
+
+        assert_eq!(parse(source, &tokens, &ctx).unwrap_err(), errors);
+    }
+}
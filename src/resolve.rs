@@ -0,0 +1,765 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{
+        self, AccessorKind, Annotation, ConstDef, DictKey, EnumDef, EnumVariant, Expr, ExprKind, Extends, FuncDef,
+        Ident, ParamList, Pattern, PatternKind, SignalDef, Stmt, StmtKind, StmtList, VarDef,
+    },
+    ident::IdentName,
+    lexer::Span,
+};
+
+/// What an identifier refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding<'a> {
+    /// A local variable or constant, a `for` variable or a pattern binding, by the identifier declaring it.
+    Local(&'a Ident<'a>),
+    /// A parameter of a function, lambda or setter, by the identifier declaring it.
+    Param(&'a Ident<'a>),
+    /// A member variable.
+    Field(&'a VarDef<'a>),
+    Func(&'a FuncDef<'a>),
+    Signal(&'a SignalDef<'a>),
+    Const(&'a ConstDef<'a>),
+    /// A named enum, whose members are used as `Name.MEMBER`.
+    Enum(&'a EnumDef<'a>),
+    /// A member of an anonymous enum, or an earlier member inside the enum being defined.
+    EnumMember(&'a EnumVariant<'a>),
+    /// An inner class, or the script itself by its `class_name`.
+    Class(&'a ast::Class<'a>),
+    /// An engine class, singleton or global constant, or a name the project
+    /// declares globally, such as the `class_name` of another script.
+    Global(IdentName<'a>),
+    /// A built-in type, function or constant, such as `int`, `print` or `PI`.
+    Builtin(IdentName<'a>),
+    /// A name that isn't declared in the script, in a class extending something
+    /// outside it, such as an engine class, which may declare it.
+    Inherited(IdentName<'a>),
+}
+
+impl Binding<'_> {
+    /// The span of the identifier declaring the name, if it's declared in the script.
+    pub fn def_span(&self) -> Option<Span> {
+        Some(match self {
+            Binding::Local(ident) | Binding::Param(ident) => ident.span,
+            Binding::Field(var_def) => var_def.def.name.span,
+            Binding::Func(func_def) => func_def.name.span,
+            Binding::Signal(signal_def) => signal_def.name.span,
+            Binding::Const(const_def) => const_def.def.name.span,
+            Binding::Enum(enum_def) => enum_def.name?.span,
+            Binding::EnumMember(variant) => variant.name.span,
+            Binding::Class(class) => class.name?.span,
+            Binding::Global(_) | Binding::Builtin(_) | Binding::Inherited(_) => return None,
+        })
+    }
+
+    /// Whether an inner class can use the name when its outer class declares it.
+    fn is_static_member(&self) -> bool {
+        matches!(self, Binding::Const(_) | Binding::Enum(_) | Binding::EnumMember(_) | Binding::Class(_))
+    }
+
+    /// Whether the name needs an instance, which static functions don't have.
+    fn is_instance_member(&self) -> bool {
+        match self {
+            Binding::Field(var_def) => !var_def.is_static,
+            Binding::Func(func_def) => !func_def.is_static,
+            Binding::Signal(_) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolveError {
+    pub span: Span,
+    pub kind: ResolveErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveErrorKind {
+    Undefined,
+    /// A name declared twice in one scope. `previous` is where it was first declared.
+    Duplicate { previous: Span },
+    /// A local used before the statement declaring it, at `decl`.
+    UseBeforeDeclaration { decl: Span },
+    /// `self` or `$Node` inside a static function.
+    SelfInStaticFunc,
+    /// A non-static variable, function or signal of the class used inside a static function.
+    InstanceMemberInStaticFunc,
+}
+
+/// The bindings of the identifiers of a script, both where names are declared and where they're used.
+#[derive(Debug, Default)]
+pub struct Resolution<'a> {
+    bindings: HashMap<Span, Binding<'a>>,
+}
+
+impl<'a> Resolution<'a> {
+    pub fn get(&self, ident: &Ident) -> Option<Binding<'a>> {
+        self.bindings.get(&ident.span).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bindings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+}
+
+static BUILTINS: &[&str] = &[
+    "true", "false", "null", "PI", "TAU", "INF", "NAN",
+    "void", "bool", "int", "float", "String", "StringName", "NodePath", "Variant",
+    "Vector2", "Vector2i", "Vector3", "Vector3i", "Vector4", "Vector4i", "Rect2", "Rect2i",
+    "Transform2D", "Transform3D", "Plane", "Quaternion", "AABB", "Basis", "Projection", "Color", "RID",
+    "Callable", "Signal", "Dictionary", "Array", "PackedByteArray", "PackedInt32Array", "PackedInt64Array",
+    "PackedFloat32Array", "PackedFloat64Array", "PackedStringArray", "PackedVector2Array",
+    "PackedVector3Array", "PackedVector4Array", "PackedColorArray",
+    "print", "prints", "printt", "printerr", "printraw", "print_rich", "print_debug", "print_verbose",
+    "print_stack", "push_error", "push_warning", "range", "len", "str", "char", "ord", "typeof",
+    "type_exists", "type_string", "is_instance_of", "is_instance_valid", "instance_from_id", "load",
+    "get_stack", "inst_to_dict", "dict_to_inst", "convert", "weakref", "hash", "error_string",
+    "var_to_str", "str_to_var", "var_to_bytes", "bytes_to_var", "Color8",
+    "abs", "absf", "absi", "sign", "signf", "signi", "min", "mini", "minf", "max", "maxi", "maxf",
+    "clamp", "clampi", "clampf", "floor", "floorf", "floori", "ceil", "ceilf", "ceili",
+    "round", "roundf", "roundi", "sqrt", "pow", "exp", "log", "sin", "cos", "tan", "asin", "acos",
+    "atan", "atan2", "sinh", "cosh", "tanh", "fmod", "fposmod", "posmod", "lerp", "lerpf", "lerp_angle",
+    "inverse_lerp", "remap", "move_toward", "smoothstep", "ease", "pingpong", "deg_to_rad", "rad_to_deg",
+    "snapped", "snappedf", "snappedi", "wrap", "wrapi", "wrapf", "is_nan", "is_inf", "is_finite",
+    "is_equal_approx", "is_zero_approx", "nearest_po2", "step_decimals", "linear_to_db", "db_to_linear",
+    "randi", "randf", "randi_range", "randf_range", "randfn", "randomize", "seed",
+];
+
+/// Engine classes and singletons commonly used from scripts.
+static GLOBALS: &[&str] = &[
+    "Object", "RefCounted", "Resource", "Node", "Node2D", "Node3D", "CanvasItem", "CanvasLayer", "Control",
+    "Sprite2D", "Sprite3D", "AnimatedSprite2D", "AnimatedSprite3D", "AnimationPlayer", "AnimationTree",
+    "Camera2D", "Camera3D", "Area2D", "Area3D", "CharacterBody2D", "CharacterBody3D", "RigidBody2D",
+    "RigidBody3D", "StaticBody2D", "StaticBody3D", "CollisionShape2D", "CollisionShape3D", "RayCast2D",
+    "RayCast3D", "Timer", "Label", "Button", "TextureRect", "ColorRect", "Container", "AudioStreamPlayer",
+    "PackedScene", "Texture2D", "Image", "Tween", "SceneTree", "Viewport", "Window", "InputEvent",
+    "InputEventKey", "InputEventMouseButton", "InputEventMouseMotion", "FileAccess", "DirAccess", "JSON",
+    "RandomNumberGenerator", "Input", "OS", "Engine", "Time", "ResourceLoader", "ResourceSaver",
+    "ProjectSettings", "DisplayServer", "RenderingServer", "PhysicsServer2D", "PhysicsServer3D",
+    "AudioServer", "ClassDB", "Performance", "OK", "FAILED",
+];
+
+/// Prefixes of the global enum constants, such as `KEY_ESCAPE` and `ERR_BUSY`.
+static GLOBAL_CONSTANT_PREFIXES: &[&str] = &[
+    "KEY_", "MOUSE_BUTTON_", "JOY_", "MIDI_", "ERR_", "PROPERTY_", "METHOD_", "TYPE_", "OP_",
+    "SIDE_", "CORNER_", "HORIZONTAL", "VERTICAL", "CLOCKWISE", "COUNTERCLOCKWISE", "HORIZONTAL_ALIGNMENT_",
+    "VERTICAL_ALIGNMENT_", "INLINE_ALIGNMENT_", "EULER_ORDER_",
+];
+
+/// Binds the identifiers of a script. `globals` are the names the project declares
+/// outside the script, such as the `class_name`s of its other scripts and its autoloads.
+pub fn resolve<'a>(class: &'a ast::Class<'a>, globals: &[IdentName<'a>]) -> (Resolution<'a>, Vec<ResolveError>) {
+    let mut resolver = Resolver { scopes: Vec::new(), globals, resolution: Resolution::default(), errors: Vec::new() };
+    let mut scope = Scope::new(ScopeKind::Class);
+    if let Some(name) = class.name {
+        scope.names.push((name, Binding::Class(class)));
+    }
+    resolver.scopes.push(scope);
+    resolver.class(class);
+    (resolver.resolution, resolver.errors)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScopeKind {
+    Class,
+    /// A function, lambda or accessor body, including the parameters.
+    Func,
+    /// The body of an `if`, `elif` or `else`.
+    Block,
+    /// The body of a loop, including the `for` variable.
+    Loop,
+    /// A match branch, including the variables its patterns bind.
+    MatchBranch,
+}
+
+struct Scope<'a> {
+    kind: ScopeKind,
+    names: Vec<(&'a Ident<'a>, Binding<'a>)>,
+    /// Locals declared further down the block, which can't be used yet.
+    pending: Vec<&'a Ident<'a>>,
+    /// For class scopes, whether the class extends something outside the script.
+    open: bool,
+    /// For function scopes, whether the function is static.
+    is_static: bool,
+}
+
+impl Scope<'_> {
+    fn new(kind: ScopeKind) -> Self {
+        Scope { kind, names: Vec::new(), pending: Vec::new(), open: false, is_static: false }
+    }
+}
+
+struct Resolver<'a, 'g> {
+    scopes: Vec<Scope<'a>>,
+    globals: &'g [IdentName<'a>],
+    resolution: Resolution<'a>,
+    errors: Vec<ResolveError>,
+}
+
+impl<'a> Resolver<'a, '_> {
+    fn error(&mut self, span: Span, kind: ResolveErrorKind) {
+        self.errors.push(ResolveError { span, kind });
+    }
+
+    fn bind(&mut self, ident: &Ident, binding: Binding<'a>) {
+        self.resolution.bindings.insert(ident.span, binding);
+    }
+
+    /// Declares a name in the innermost scope.
+    fn declare(&mut self, ident: &'a Ident<'a>, binding: Binding<'a>) {
+        self.bind(ident, binding);
+        let scope = self.scopes.last_mut().unwrap();
+        scope.pending.retain(|pending| pending.span != ident.span);
+        if let Some((previous, _)) = scope.names.iter().find(|(name, _)| name.name == ident.name) {
+            let previous = previous.span;
+            self.error(ident.span, ResolveErrorKind::Duplicate { previous });
+        } else {
+            scope.names.push((ident, binding));
+        }
+    }
+
+    fn lookup(&self, name: IdentName<'a>) -> Option<Binding<'a>> {
+        // Only the static members of outer classes are visible in inner classes.
+        let mut outer = false;
+        let mut open = None;
+        for scope in self.scopes.iter().rev() {
+            let found = scope.names.iter().rev().find(|(ident, _)| ident.name == name);
+            if let Some(&(_, binding)) = found {
+                if !outer || binding.is_static_member() {
+                    return Some(binding);
+                }
+            }
+            if scope.kind == ScopeKind::Class {
+                open.get_or_insert(scope.open);
+                outer = true;
+            }
+        }
+        let s = name.as_str();
+        if self.globals.contains(&name) || GLOBALS.contains(&s) || GLOBAL_CONSTANT_PREFIXES.iter().any(|prefix| s.starts_with(prefix)) {
+            Some(Binding::Global(name))
+        } else if BUILTINS.contains(&s) {
+            Some(Binding::Builtin(name))
+        } else if open == Some(true) {
+            Some(Binding::Inherited(name))
+        } else {
+            None
+        }
+    }
+
+    /// Finds a member of the innermost class, including those its base classes in the script declare.
+    fn member(&self, name: IdentName<'a>) -> Option<Binding<'a>> {
+        let class = self.scopes.iter().rev().find(|scope| scope.kind == ScopeKind::Class)?;
+        class.names.iter().find(|(ident, _)| ident.name == name).map(|&(_, binding)| binding)
+    }
+
+    /// Finds a local of the current function declared further down, which `name` can't refer to yet.
+    fn pending(&self, name: IdentName<'a>) -> Option<&'a Ident<'a>> {
+        self.scopes.iter().rev()
+            .take_while(|scope| scope.kind != ScopeKind::Class)
+            .find_map(|scope| scope.pending.iter().find(|pending| pending.name == name).copied())
+    }
+
+    /// Whether the innermost function, or one a lambda is nested in, is static.
+    fn in_static_func(&self) -> bool {
+        self.scopes.iter().rev().take_while(|scope| scope.kind != ScopeKind::Class).any(|scope| scope.is_static)
+    }
+
+    fn use_ident(&mut self, ident: &'a Ident<'a>) {
+        let binding = match self.lookup(ident.name) {
+            // A local declared further down wins over a member the base class might have.
+            Some(Binding::Inherited(_)) if self.pending(ident.name).is_some() => None,
+            binding => binding,
+        };
+        if let Some(binding) = binding {
+            self.bind(ident, binding);
+            if binding.is_instance_member() && self.in_static_func() {
+                self.error(ident.span, ResolveErrorKind::InstanceMemberInStaticFunc);
+            }
+        } else if let Some(decl) = self.pending(ident.name) {
+            self.error(ident.span, ResolveErrorKind::UseBeforeDeclaration { decl: decl.span });
+        } else {
+            self.error(ident.span, ResolveErrorKind::Undefined);
+        }
+    }
+
+    fn class(&mut self, class: &'a ast::Class<'a>) {
+        // Members are declared up front, as they can be used anywhere in the class.
+        self.scopes.push(Scope::new(ScopeKind::Class));
+        for (ident, binding) in members(class.stmt_list) {
+            self.declare(ident, binding);
+        }
+        let mut scope = self.scopes.pop().unwrap();
+
+        // Base classes declared in the script contribute the members the class doesn't override.
+        let mut extends = class.extends;
+        let mut seen = vec![class];
+        while let Some(base) = self.base_class(extends) {
+            if seen.iter().any(|class| std::ptr::eq(*class, base)) {
+                break;
+            }
+            seen.push(base);
+            for (ident, binding) in members(base.stmt_list) {
+                if !scope.names.iter().any(|(name, _)| name.name == ident.name) {
+                    scope.names.push((ident, binding));
+                }
+            }
+            extends = base.extends;
+        }
+        scope.open = extends.is_some();
+
+        self.scopes.push(scope);
+        self.annotations(class.annotations);
+        for stmt in class.stmt_list.stmts {
+            self.stmt(stmt, true);
+        }
+        self.scopes.pop();
+    }
+
+    /// Finds the class declared in the script that `extends` names.
+    fn base_class(&mut self, extends: Option<&'a Extends<'a>>) -> Option<&'a ast::Class<'a>> {
+        let Some(Extends { path: None, names: [name], .. }) = extends else { return None };
+        let binding = self.lookup(name.name)?;
+        self.bind(name, binding);
+        match binding {
+            Binding::Class(class) => Some(class),
+            _ => None,
+        }
+    }
+
+    fn func(&mut self, param_list: &'a ParamList<'a>, result_ty: Option<&'a Expr<'a>>, body: &'a StmtList<'a>, is_static: bool) {
+        if let Some(ty) = result_ty {
+            self.expr(ty);
+        }
+        self.scopes.push(Scope { is_static, ..Scope::new(ScopeKind::Func) });
+        for param in param_list.params {
+            if let Some(ty) = param.ty {
+                self.expr(ty);
+            }
+            if let Some(val) = param.val {
+                self.expr(val);
+            }
+            self.declare(param.name, Binding::Param(param.name));
+        }
+        self.stmts(body);
+        self.scopes.pop();
+    }
+
+    fn block(&mut self, kind: ScopeKind, stmt_list: &'a StmtList<'a>) {
+        self.scopes.push(Scope::new(kind));
+        self.stmts(stmt_list);
+        self.scopes.pop();
+    }
+
+    /// Resolves the statements of a block in the innermost scope.
+    fn stmts(&mut self, stmt_list: &'a StmtList<'a>) {
+        let pending = stmt_list.stmts.iter().filter_map(|stmt| match stmt.kind {
+            StmtKind::VarDef(var_def) => Some(var_def.def.name),
+            StmtKind::ConstDef(const_def) => Some(const_def.def.name),
+            _ => None,
+        });
+        self.scopes.last_mut().unwrap().pending.extend(pending);
+        for stmt in stmt_list.stmts {
+            self.stmt(stmt, false);
+        }
+    }
+
+    /// Resolves a statement. At class level, variables and constants are
+    /// members, which the class scope already declares.
+    fn stmt(&mut self, stmt: &'a Stmt<'a>, class_level: bool) {
+        match stmt.kind {
+            StmtKind::Expr(expr) | StmtKind::Return(Some(expr)) => self.expr(expr),
+            StmtKind::Assign(assign) => {
+                self.expr(assign.target);
+                self.expr(assign.val);
+            }
+            StmtKind::VarDef(var_def) => {
+                self.annotations(var_def.annotations);
+                self.ident_def_exprs(var_def.def);
+                for accessor in [var_def.getter, var_def.setter].into_iter().flatten() {
+                    match accessor.kind {
+                        AccessorKind::Func(name) => self.use_ident(name),
+                        AccessorKind::Body { param, body } => {
+                            self.scopes.push(Scope { is_static: var_def.is_static, ..Scope::new(ScopeKind::Func) });
+                            if let Some(param) = param {
+                                self.declare(param, Binding::Param(param));
+                            }
+                            self.stmts(body);
+                            self.scopes.pop();
+                        }
+                    }
+                }
+                if !class_level {
+                    self.declare(var_def.def.name, Binding::Local(var_def.def.name));
+                }
+            }
+            StmtKind::ConstDef(const_def) => {
+                self.ident_def_exprs(const_def.def);
+                if !class_level {
+                    self.declare(const_def.def.name, Binding::Local(const_def.def.name));
+                }
+            }
+            StmtKind::FuncDef(func_def) => {
+                self.annotations(func_def.annotations);
+                self.func(func_def.param_list, func_def.result_ty, func_def.body, func_def.is_static);
+            }
+            StmtKind::EnumDef(enum_def) => {
+                // Earlier members of the same enum can be used without qualification.
+                self.scopes.push(Scope::new(ScopeKind::Block));
+                for variant in enum_def.variants {
+                    if let Some(val) = variant.val {
+                        self.expr(val);
+                    }
+                    if enum_def.name.is_some() {
+                        self.declare(variant.name, Binding::EnumMember(variant));
+                    }
+                }
+                self.scopes.pop();
+            }
+            StmtKind::SignalDef(signal_def) => {
+                self.scopes.push(Scope::new(ScopeKind::Func));
+                for param in signal_def.param_list.map_or(&[][..], |list| list.params) {
+                    self.ident_def_exprs(param);
+                    self.declare(param.name, Binding::Param(param.name));
+                }
+                self.scopes.pop();
+            }
+            StmtKind::Class(class) => self.class(class),
+            StmtKind::If(if_) => {
+                self.expr(if_.cond);
+                self.block(ScopeKind::Block, if_.body);
+                for elif in if_.elifs {
+                    self.expr(elif.cond);
+                    self.block(ScopeKind::Block, elif.body);
+                }
+                if let Some(else_) = if_.else_ {
+                    self.block(ScopeKind::Block, else_);
+                }
+            }
+            StmtKind::While(while_) => {
+                self.expr(while_.cond);
+                self.block(ScopeKind::Loop, while_.body);
+            }
+            StmtKind::For(for_) => {
+                self.expr(for_.iter);
+                if let Some(ty) = for_.var.ty {
+                    self.expr(ty);
+                }
+                self.scopes.push(Scope::new(ScopeKind::Loop));
+                self.declare(for_.var.name, Binding::Local(for_.var.name));
+                self.stmts(for_.body);
+                self.scopes.pop();
+            }
+            StmtKind::Match(match_) => {
+                self.expr(match_.expr);
+                for branch in match_.branches {
+                    self.scopes.push(Scope::new(ScopeKind::MatchBranch));
+                    for pattern in branch.patterns {
+                        self.pattern(pattern);
+                    }
+                    if let Some(guard) = branch.guard {
+                        self.expr(guard);
+                    }
+                    self.stmts(branch.body);
+                    self.scopes.pop();
+                }
+            }
+            StmtKind::Annotations(annotations) => self.annotations(annotations),
+            StmtKind::Pass | StmtKind::Break | StmtKind::Continue | StmtKind::Return(None) | StmtKind::Error => (),
+        }
+    }
+
+    /// Resolves the type and value of a declaration, but not its name.
+    fn ident_def_exprs(&mut self, def: &'a ast::IdentDef<'a>) {
+        if let Some(ty) = def.ty {
+            self.expr(ty);
+        }
+        if let Some(val) = def.val {
+            self.expr(val);
+        }
+    }
+
+    fn annotations(&mut self, annotations: &'a [&'a Annotation<'a>]) {
+        for annotation in annotations {
+            for arg in annotation.args {
+                self.expr(arg);
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &'a Pattern<'a>) {
+        match pattern.kind {
+            PatternKind::Expr(expr) => self.expr(expr),
+            PatternKind::Bind(name) => self.declare(name, Binding::Local(name)),
+            PatternKind::Wildcard => (),
+            PatternKind::Array { items, .. } => {
+                for item in items {
+                    self.pattern(item);
+                }
+            }
+            PatternKind::Dict { entries, .. } => {
+                for entry in entries {
+                    self.expr(entry.key);
+                    if let Some(val) = entry.val {
+                        self.pattern(val);
+                    }
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &'a Expr<'a>) {
+        match expr.kind {
+            ExprKind::Ident(ident) => self.use_ident(ident),
+            ExprKind::Self_ | ExprKind::GetNode(_) => {
+                if self.in_static_func() {
+                    self.error(expr.span, ResolveErrorKind::SelfInStaticFunc);
+                }
+            }
+            ExprKind::Lit(_) | ExprKind::Super | ExprKind::Error => (),
+            ExprKind::BinOp(op) => {
+                self.expr(op.lhs);
+                self.expr(op.rhs);
+            }
+            ExprKind::UnaryOp(op) => self.expr(op.expr),
+            ExprKind::Ternary(ternary) => {
+                self.expr(ternary.cond);
+                self.expr(ternary.then);
+                self.expr(ternary.else_);
+            }
+            ExprKind::Call(call) => {
+                self.expr(call.callee);
+                for arg in call.arg_list.args {
+                    self.expr(arg);
+                }
+            }
+            // Attribute names depend on the type of the expression, which is only known for `self`.
+            ExprKind::Attr(attr) => {
+                self.expr(attr.expr);
+                if let ExprKind::Self_ = attr.expr.kind {
+                    if let Some(binding) = self.member(attr.name.name) {
+                        self.bind(attr.name, binding);
+                    }
+                }
+            }
+            ExprKind::Index(index) => {
+                self.expr(index.expr);
+                self.expr(index.index);
+            }
+            ExprKind::Await(await_) => self.expr(await_.expr),
+            ExprKind::Lambda(lambda) => self.func(lambda.param_list, lambda.result_ty, lambda.body, false),
+            ExprKind::Array(array) => {
+                for item in array.items {
+                    self.expr(item);
+                }
+            }
+            ExprKind::Dict(dict) => {
+                for entry in dict.entries {
+                    if let DictKey::Expr(key) = entry.key {
+                        self.expr(key);
+                    }
+                    self.expr(entry.val);
+                }
+            }
+        }
+    }
+}
+
+/// The members declared in a class body, in order.
+fn members<'a>(stmt_list: &'a StmtList<'a>) -> Vec<(&'a Ident<'a>, Binding<'a>)> {
+    let mut members = Vec::new();
+    for stmt in stmt_list.stmts {
+        match stmt.kind {
+            StmtKind::VarDef(var_def) => members.push((var_def.def.name, Binding::Field(var_def))),
+            StmtKind::FuncDef(func_def) => members.push((func_def.name, Binding::Func(func_def))),
+            StmtKind::SignalDef(signal_def) => members.push((signal_def.name, Binding::Signal(signal_def))),
+            StmtKind::ConstDef(const_def) => members.push((const_def.def.name, Binding::Const(const_def))),
+            StmtKind::EnumDef(enum_def) => match enum_def.name {
+                Some(name) => members.push((name, Binding::Enum(enum_def))),
+                None => members.extend(enum_def.variants.iter().map(|variant| (variant.name, Binding::EnumMember(variant)))),
+            },
+            StmtKind::Class(class) => members.extend(class.name.map(|name| (name, Binding::Class(class)))),
+            _ => (),
+        }
+    }
+    members
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::{context, lexer, parser};
+
+    use super::*;
+
+    /// Describes the binding of every use of an identifier, then every error, in source
+    /// order as `name: kind`, followed by the line of the related declaration if there's one.
+    fn resolve_source(source: &str) -> (Vec<String>, Vec<String>) {
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parser::parse(source, &tokens, &ctx).unwrap();
+        let globals = [ctx.new_ident_name("Autoload")];
+        let (resolution, errors) = resolve(class, &globals);
+        let describe = |span: Span, kind: &str, related: Option<Span>| {
+            let name = &source[span.start as usize..span.end as usize];
+            match related {
+                Some(related) => format!("{name}: {kind} {}", source[..related.start as usize].matches('\n').count() + 1),
+                None => format!("{name}: {kind}"),
+            }
+        };
+        let mut uses: Vec<_> = resolution.bindings.iter()
+            .filter(|(span, binding)| binding.def_span() != Some(**span))
+            .collect();
+        uses.sort_by_key(|(span, _)| span.start);
+        let uses = uses.into_iter().map(|(span, binding)| {
+            let kind = format!("{binding:?}");
+            describe(*span, &kind[..kind.find('(').unwrap()], binding.def_span())
+        }).collect();
+        let errors = errors.into_iter().map(|error| match error.kind {
+            ResolveErrorKind::Undefined => describe(error.span, "Undefined", None),
+            ResolveErrorKind::Duplicate { previous } => describe(error.span, "Duplicate", Some(previous)),
+            ResolveErrorKind::UseBeforeDeclaration { decl } => describe(error.span, "UseBeforeDeclaration", Some(decl)),
+            ResolveErrorKind::SelfInStaticFunc => describe(error.span, "SelfInStaticFunc", None),
+            ResolveErrorKind::InstanceMemberInStaticFunc => describe(error.span, "InstanceMemberInStaticFunc", None),
+        }).collect();
+        (uses, errors)
+    }
+
+    #[test]
+    fn bindings() {
+        let (uses, errors) = resolve_source(indoc! {"
+            class_name Player
+            const MAX = LIMIT * 2
+            const LIMIT = 5
+            enum {A, B = A + 1}
+            enum State {IDLE, RUN = IDLE}
+            signal died(cause: State)
+            var hp: int = MAX:
+                set(value):
+                    hp = clamp(value, 0, MAX)
+            func f(x, y = x):
+                var z = x + hp
+                for i in range(z):
+                    var g = func(w): return w + i + z
+                match y:
+                    [var a, B]:
+                        print(a, died, Player, Autoload, Node, KEY_ESCAPE)
+                return f(z, State.RUN)
+        "});
+        assert_eq!(errors, Vec::<String>::new());
+        assert_eq!(uses, [
+            "LIMIT: Const 3", "A: EnumMember 4", "IDLE: EnumMember 5", "State: Enum 5", "int: Builtin",
+            "MAX: Const 2", "hp: Field 7", "clamp: Builtin", "value: Param 8", "MAX: Const 2",
+            "x: Param 10", "x: Param 10", "hp: Field 7", "range: Builtin", "z: Local 11",
+            "w: Param 13", "i: Local 12", "z: Local 11", "y: Param 10", "B: EnumMember 4",
+            "print: Builtin", "a: Local 15", "died: Signal 6", "Player: Class 1", "Autoload: Global", "Node: Global",
+            "KEY_ESCAPE: Global", "f: Func 10", "z: Local 11", "State: Enum 5",
+        ]);
+    }
+
+    #[test]
+    fn classes() {
+        let (uses, errors) = resolve_source(indoc! {"
+            const SPEED = 10
+            var hp = 1
+            class Base:
+                var mp = SPEED
+                func cast():
+                    return mp + hp
+            class Leaf extends Base:
+                func f():
+                    return cast() + mp + Base.new().mp + self.mp + self.position
+            class Ext extends Node2D:
+                func f():
+                    return position + SPEED
+                func g():
+                    speed = 1
+                    var speed = 2
+        "});
+        // Inner classes only see the constants, enums and classes of the outer one.
+        assert_eq!(errors, ["hp: Undefined", "speed: UseBeforeDeclaration 15"]);
+        assert_eq!(uses, [
+            "SPEED: Const 1", "mp: Field 4", "Base: Class 3", "cast: Func 5", "mp: Field 4", "Base: Class 3",
+            "mp: Field 4", "Node2D: Global", "position: Inherited", "SPEED: Const 1",
+        ]);
+    }
+
+    #[test]
+    fn errors() {
+        let (_, errors) = resolve_source(indoc! {"
+            var hp = 1
+            func hp():
+                pass
+            enum E {A, B, A}
+            func f(x, x):
+                var x = 1
+                print(y, later)
+                var y = y
+                if true:
+                    var later = 2
+                    var z = 1
+                    var z = 2
+                print(z)
+                var later = 3
+                match x:
+                    [var a, var a]:
+                        pass
+        "});
+        assert_eq!(errors, [
+            "hp: Duplicate 1", "A: Duplicate 4", "x: Duplicate 5", "x: Duplicate 5",
+            "y: UseBeforeDeclaration 8", "later: UseBeforeDeclaration 14", "y: UseBeforeDeclaration 8",
+            "z: Duplicate 11", "z: Undefined", "a: Duplicate 16",
+        ]);
+    }
+
+    #[test]
+    fn static_funcs() {
+        let (_, errors) = resolve_source(indoc! {"
+            var hp = 10
+            static var count = 0
+            signal died
+            func heal():
+                hp += 1
+            static func make(hp):
+                count += 1
+                var heal = func(): return hp
+                for died in range(3):
+                    heal.call()
+                return make(hp)
+        "});
+        assert_eq!(errors, Vec::<String>::new());
+        let (_, errors) = resolve_source(indoc! {"
+            var hp = 10
+            signal died
+            func heal():
+                pass
+            static func f():
+                self.hp = 1
+                heal()
+                died.emit()
+                if true:
+                    var hp = 2
+                var g = func(): return hp
+                return $Sprite
+            static var mp = 0:
+                set(value):
+                    mp = value + hp
+            class A:
+                var mp = 1
+            class B extends A:
+                static func f():
+                    return mp
+        "});
+        assert_eq!(errors, [
+            "self: SelfInStaticFunc", "heal: InstanceMemberInStaticFunc", "died: InstanceMemberInStaticFunc",
+            "hp: InstanceMemberInStaticFunc", "$Sprite: SelfInStaticFunc", "hp: InstanceMemberInStaticFunc",
+            "mp: InstanceMemberInStaticFunc",
+        ]);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::lexer::Span;
+
+/// Identifies a file registered with a `SourceMap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(u32);
+
+/// The files of a compilation, laid out one after another in a single range of
+/// offsets so that every span belongs to exactly one file.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+#[derive(Debug)]
+pub struct SourceFile {
+    pub id: FileId,
+    pub name: String,
+    pub src: String,
+    /// The offset of the first byte of `src`.
+    pub start: u32,
+    /// The offsets at which lines start, relative to `start`.
+    line_starts: Vec<u32>,
+}
+
+/// A 1-based line and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub file: FileId,
+    pub line: u32,
+    /// The column counted in UTF-8 bytes.
+    pub col: u32,
+    /// The column counted in UTF-16 code units, as editors using the Language Server Protocol expect.
+    pub col_utf16: u32,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a file, returning the ID its spans resolve to.
+    pub fn add_file(&mut self, name: &str, src: String) -> FileId {
+        // Leave a gap after each file, so that an offset at the end of a file isn't also the start of the next one.
+        let start = self.files.last().map_or(0, |file| file.end() + 1);
+        let id = FileId(self.files.len() as u32);
+        let line_starts = line_starts(&src);
+        self.files.push(SourceFile { id, name: name.to_owned(), src, start, line_starts });
+        id
+    }
+
+    pub fn file(&self, id: FileId) -> &SourceFile {
+        &self.files[id.0 as usize]
+    }
+
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// Returns the file containing `pos`, which may also be the offset just past its end.
+    pub fn lookup_file(&self, pos: u32) -> &SourceFile {
+        let i = self.files.partition_point(|file| file.start <= pos);
+        let file = &self.files[i.checked_sub(1).expect("offset before the first file")];
+        assert!(pos <= file.end(), "offset in the gap after a file");
+        file
+    }
+
+    pub fn span_file(&self, span: Span) -> FileId {
+        self.lookup_file(span.start).id
+    }
+
+    pub fn location(&self, pos: u32) -> Location {
+        self.lookup_file(pos).location(pos)
+    }
+
+    /// Returns the source text of a span.
+    pub fn span_text(&self, span: Span) -> &str {
+        let file = self.lookup_file(span.start);
+        &file.src[(span.start - file.start) as usize..(span.end - file.start) as usize]
+    }
+}
+
+impl SourceFile {
+    /// The offset just past the last byte of the file.
+    pub fn end(&self) -> u32 {
+        self.start + self.src.len() as u32
+    }
+
+    pub fn span(&self) -> Span {
+        Span::new(self.start, self.end())
+    }
+
+    pub fn line_count(&self) -> u32 {
+        self.line_starts.len() as u32
+    }
+
+    /// Returns the offset of the start of a 1-based line, relative to `start`.
+    pub fn line_start(&self, line: u32) -> u32 {
+        self.line_starts[line as usize - 1]
+    }
+
+    /// Returns the text of a 1-based line, without its line break.
+    pub fn line(&self, line: u32) -> &str {
+        let start = self.line_starts[line as usize - 1] as usize;
+        let end = self.line_starts.get(line as usize).map_or(self.src.len(), |&end| end as usize);
+        self.src[start..end].trim_end_matches(['\n', '\r'])
+    }
+
+    /// Resolves an offset in this file to a line and column. An offset in the
+    /// middle of a character resolves to the start of that character.
+    pub fn location(&self, pos: u32) -> Location {
+        let mut rel = (pos - self.start) as usize;
+        while !self.src.is_char_boundary(rel) {
+            rel -= 1;
+        }
+        let line = self.line_starts.partition_point(|&start| start as usize <= rel);
+        let line_start = self.line_starts[line - 1] as usize;
+        let before = &self.src[line_start..rel];
+        Location {
+            file: self.id,
+            line: line as u32,
+            col: before.len() as u32 + 1,
+            col_utf16: before.encode_utf16().count() as u32 + 1,
+        }
+    }
+}
+
+/// Finds where lines start. As in the lexer, `\n`, `\r\n` and a lone `\r` all end a line.
+fn line_starts(src: &str) -> Vec<u32> {
+    let bytes = src.as_bytes();
+    let mut starts = vec![0];
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == b'\n' || (b == b'\r' && bytes.get(i + 1) != Some(&b'\n')) {
+            starts.push(i as u32 + 1);
+        }
+    }
+    starts
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::{ast::StmtKind, context, lexer, parser};
+
+    use super::*;
+
+    fn line_col(map: &SourceMap, pos: u32) -> (u32, u32, u32) {
+        let loc = map.location(pos);
+        (loc.line, loc.col, loc.col_utf16)
+    }
+
+    #[test]
+    fn locations() {
+        let mut map = SourceMap::new();
+        let a = map.add_file("a.gd", "var x\r\n\tvar é = \"😀\"\rpass".to_owned());
+        let b = map.add_file("b.gd", "".to_owned());
+        let c = map.add_file("c.gd", "x\n".to_owned());
+
+        assert_eq!(line_col(&map, 0), (1, 1, 1));
+        assert_eq!(line_col(&map, 5), (1, 6, 6));
+        assert_eq!(line_col(&map, 7), (2, 1, 1));
+        let src = &map.file(a).src;
+        let quote = src.find('"').unwrap() as u32;
+        assert_eq!(line_col(&map, quote), (2, 11, 10));
+        assert_eq!(line_col(&map, quote + 1), (2, 12, 11));
+        // In the middle of the emoji.
+        assert_eq!(line_col(&map, quote + 2), (2, 12, 11));
+        assert_eq!(line_col(&map, quote + 5), (2, 16, 13));
+        let pass = src.find("pass").unwrap() as u32;
+        assert_eq!(line_col(&map, pass), (3, 1, 1));
+        assert_eq!(map.file(a).line_count(), 3);
+        assert_eq!(map.file(a).line(1), "var x");
+        assert_eq!(map.file(a).line(2), "\tvar é = \"😀\"");
+
+        let end_a = map.file(a).end();
+        assert_eq!(map.location(end_a).file, a);
+        assert_eq!(map.file(b).start, end_a + 1);
+        assert_eq!(map.location(end_a + 1).file, b);
+        let c_start = map.file(c).start;
+        assert_eq!(c_start, end_a + 2);
+        assert_eq!(map.location(c_start + 2), Location { file: c, line: 2, col: 1, col_utf16: 1 });
+        assert_eq!(map.span_file(Span::new(c_start, c_start + 1)), c);
+        assert_eq!(map.span_text(Span::new(pass, pass + 4)), "pass");
+    }
+
+    #[test]
+    fn parse_files() {
+        let ctx = context::Ctx::new();
+        ctx.add_file("a.gd", "var a = 1\n".to_owned());
+        let b = ctx.add_file("b.gd", indoc! {"
+            var b = 2
+            ## Doubles.
+            func f(x):
+                return x * 2
+        "}.to_owned());
+        let source_map = ctx.source_map();
+        let file = source_map.file(b);
+        let tokens = lexer::tokenize_file(file);
+        let class = parser::parse(&file.src, &tokens, &ctx).unwrap();
+        let StmtKind::FuncDef(f) = class.stmt_list.stmts[1].kind else { panic!() };
+        assert_eq!(f.doc, Some("Doubles."));
+        let ret = f.body.stmts[0];
+        assert_eq!(source_map.span_text(ret.span), "return x * 2");
+        assert_eq!(source_map.location(ret.span.start), Location { file: b, line: 4, col: 5, col_utf16: 5 });
+    }
+}
@@ -1,13 +1,196 @@
-use crate::ast::*;
+use crate::{ast::{BinOpKind, UnaryOpKind}, ident::IdentName, lexer::Span};
 
+use self::ty::Ty;
+
+pub mod builtins;
+pub mod lower;
+
+/// A script lowered for code generation, with every name resolved and every expression typed.
+/// Its top-level statements make up `main`.
+#[derive(Debug, Clone, Copy)]
+pub struct Class<'a> {
+    pub span: Span,
+    /// A `TyKind::Class` describing the members.
+    pub ty: Ty<'a>,
+    /// Indexed by `GlobalId`.
+    pub globals: &'a [Global<'a>],
+    /// Indexed by `FuncId`.
+    pub funcs: &'a [&'a Func<'a>],
+    pub main: &'a Func<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GlobalId(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FuncId(pub u32);
+
+/// A local of a function or lambda, indexing its `locals`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LocalId(pub u32);
+
+/// Storage that outlives any function: a static variable, or a member variable of the script's one instance.
+#[derive(Debug, Clone, Copy)]
+pub struct Global<'a> {
+    pub span: Span,
+    pub name: IdentName<'a>,
+    pub ty: Ty<'a>,
+    /// The initial value of a static variable, assigned when the program starts.
+    /// Member variables are assigned theirs where `main` declares them.
+    pub val: Option<&'a Expr<'a>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FuncKind {
+    /// The top-level statements of the script.
+    Main,
+    Static,
+    Method,
+    /// The `get:` body of the property the function is named after.
+    Getter,
+    /// The `set(value):` body of the property the function is named after.
+    Setter,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Func<'a> {
+    pub span: Span,
+    pub name: IdentName<'a>,
+    pub kind: FuncKind,
+    pub params: &'a [LocalId],
+    pub ret_ty: Ty<'a>,
+    /// Every local of the function, including its parameters. Locals of lambdas inside it aren't included.
+    pub locals: &'a [Local<'a>],
+    pub body: &'a Block<'a>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Local<'a> {
+    pub name: IdentName<'a>,
+    pub ty: Ty<'a>,
+}
+
+/// `func(x): ...` as a value. Its locals are separate from those of the enclosing function.
+#[derive(Debug, Clone, Copy)]
+pub struct Lambda<'a> {
+    pub span: Span,
+    pub params: &'a [LocalId],
+    pub ret_ty: Ty<'a>,
+    pub captures: &'a [Capture],
+    pub locals: &'a [Local<'a>],
+    pub body: &'a Block<'a>,
+}
+
+/// A local of the enclosing function, copied into one of the lambda's locals when the lambda is created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Capture {
+    pub outer: LocalId,
+    pub inner: LocalId,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Block<'a> {
+    pub span: Span,
+    pub stmts: &'a [&'a Stmt<'a>],
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Stmt<'a> {
+    pub span: Span,
+    pub kind: StmtKind<'a>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum StmtKind<'a> {
+    Expr(&'a Expr<'a>),
+    /// Declares a local, starting out as `val` or the zero value of its type.
+    Var { local: LocalId, val: Option<&'a Expr<'a>> },
+    /// `place = val`, or a compound assignment like `place += val`.
+    Assign { place: Place, op: Option<BinOpKind>, val: &'a Expr<'a> },
+    /// `elif` branches are nested in `else_`.
+    If { cond: &'a Expr<'a>, then: &'a Block<'a>, else_: Option<&'a Block<'a>> },
+    While { cond: &'a Expr<'a>, body: &'a Block<'a> },
+    /// Counts `var` from `start` up to `end`, or down when `step` is negative. The bounds are `int`s, evaluated once.
+    For { var: LocalId, start: &'a Expr<'a>, end: &'a Expr<'a>, step: &'a Expr<'a>, body: &'a Block<'a> },
+    /// Stores `val` in `local`, then runs the first branch matching it.
+    Match { local: LocalId, val: &'a Expr<'a>, branches: &'a [MatchBranch<'a>] },
+    Break,
+    Continue,
+    Return(Option<&'a Expr<'a>>),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MatchBranch<'a> {
+    pub span: Span,
+    /// Whether one of the patterns matches, or `None` when one matches anything.
+    pub cond: Option<&'a Expr<'a>>,
+    /// Declares the locals the patterns bind, before `guard` is tested.
+    pub binds: &'a [&'a Stmt<'a>],
+    pub guard: Option<&'a Expr<'a>>,
+    pub body: &'a Block<'a>,
+}
+
+/// Where an assignment stores its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Place {
+    Local(LocalId),
+    Global(GlobalId),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Expr<'a> {
+    pub span: Span,
+    pub ty: Ty<'a>,
+    pub kind: ExprKind<'a>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExprKind<'a> {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(&'a str),
+    /// `null`, a `Variant` holding nothing.
+    Nil,
+    Local(LocalId),
+    Global(GlobalId),
+    /// A direct call to a function of the class, including property accessors.
+    Call { func: FuncId, args: &'a [&'a Expr<'a>] },
+    /// `callee.call(args)` on a `Callable`.
+    CallClosure { callee: &'a Expr<'a>, args: &'a [&'a Expr<'a>] },
+    /// A call to a builtin function, which takes its arguments as `Variant`s.
+    CallBuiltin { func: BuiltinFunc, args: &'a [&'a Expr<'a>] },
+    BinOp { kind: BinOpKind, lhs: &'a Expr<'a>, rhs: &'a Expr<'a> },
+    UnaryOp { kind: UnaryOpKind, expr: &'a Expr<'a> },
+    /// `then if cond else else_`, where both branches have the type of the expression.
+    Ternary { cond: &'a Expr<'a>, then: &'a Expr<'a>, else_: &'a Expr<'a> },
+    /// `expr is ty`, testing what a `Variant` holds at runtime.
+    Is { expr: &'a Expr<'a>, ty: Ty<'a> },
+    Lambda(&'a Lambda<'a>),
+    /// Wraps a value of a static type in a `Variant`.
+    ToVariant(&'a Expr<'a>),
+    /// Unwraps a `Variant` into the expression's type, failing at runtime if it holds something else.
+    /// Integers and floats convert to each other.
+    FromVariant(&'a Expr<'a>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinFunc {
+    /// `print(args)`, writing the arguments converted to strings on one line.
+    Print,
+    /// `str(args)`, concatenating the arguments converted to strings.
+    Str,
+}
 
 pub mod ty {
-    use std::{collections::HashMap, ops::Deref};
-    use internment::ArenaIntern;
+    use std::{cell::RefCell, ops::Deref};
+    use internment::{Arena, ArenaIntern};
 
-    use crate::{ast, ident::IdentName};
+    use crate::{ast::{self, ExprKind, StmtKind}, ident::IdentName};
 
-    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    use super::consts::{self, ConstError, ConstErrorKind, ConstScope, ConstVal};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct Ty<'a>(ArenaIntern<'a, TyKind<'a>>);
 
     impl<'a> Deref for Ty<'a> {
@@ -17,6 +200,12 @@ pub mod ty {
         }
     }
 
+    impl std::fmt::Display for Ty<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            (**self).fmt(f)
+        }
+    }
+
     impl<'a> From<ArenaIntern<'a, TyKind<'a>>> for Ty<'a> {
         fn from(value: ArenaIntern<'a, TyKind<'a>>) -> Self {
             Self(value)
@@ -31,31 +220,467 @@ pub mod ty {
     #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     pub enum TyKind<'a> {
         Int(IntKind),
+        Float,
+        String,
+        Bool,
+        /// The result of a function that doesn't return a value.
+        Void,
+        /// Any value, tagged with its type at runtime. Untyped variables, parameters
+        /// and return values are `Variant`s.
+        Variant,
         Class(Class<'a>),
+        /// A function value, such as a lambda. Its signature isn't tracked.
+        Callable,
+        StringName,
+        NodePath,
+        Vector2,
+        Vector2i,
+        Vector3,
+        Vector3i,
+        Vector4,
+        Vector4i,
+        Rect2,
+        Rect2i,
+        Transform2D,
+        Transform3D,
+        Basis,
+        Quaternion,
+        Plane,
+        Aabb,
+        Projection,
+        Color,
+        Rid,
+        Signal,
+        /// A `Dictionary` of any keys and values.
+        Dictionary,
+        /// An `Array` of any elements.
+        Array,
+        PackedByteArray,
+        PackedInt32Array,
+        PackedInt64Array,
+        PackedFloat32Array,
+        PackedFloat64Array,
+        PackedStringArray,
+        PackedVector2Array,
+        PackedVector3Array,
+        PackedVector4Array,
+        PackedColorArray,
+    }
+
+    /// The types `TyKind::builtin` knows, by name.
+    static BUILTINS: &[(&str, TyKind<'static>)] = &[
+        ("int", TyKind::Int(IntKind::I64)),
+        ("float", TyKind::Float),
+        ("String", TyKind::String),
+        ("bool", TyKind::Bool),
+        ("void", TyKind::Void),
+        ("Variant", TyKind::Variant),
+        ("Callable", TyKind::Callable),
+        ("StringName", TyKind::StringName),
+        ("NodePath", TyKind::NodePath),
+        ("Vector2", TyKind::Vector2),
+        ("Vector2i", TyKind::Vector2i),
+        ("Vector3", TyKind::Vector3),
+        ("Vector3i", TyKind::Vector3i),
+        ("Vector4", TyKind::Vector4),
+        ("Vector4i", TyKind::Vector4i),
+        ("Rect2", TyKind::Rect2),
+        ("Rect2i", TyKind::Rect2i),
+        ("Transform2D", TyKind::Transform2D),
+        ("Transform3D", TyKind::Transform3D),
+        ("Basis", TyKind::Basis),
+        ("Quaternion", TyKind::Quaternion),
+        ("Plane", TyKind::Plane),
+        ("AABB", TyKind::Aabb),
+        ("Projection", TyKind::Projection),
+        ("Color", TyKind::Color),
+        ("RID", TyKind::Rid),
+        ("Signal", TyKind::Signal),
+        ("Dictionary", TyKind::Dictionary),
+        ("Array", TyKind::Array),
+        ("PackedByteArray", TyKind::PackedByteArray),
+        ("PackedInt32Array", TyKind::PackedInt32Array),
+        ("PackedInt64Array", TyKind::PackedInt64Array),
+        ("PackedFloat32Array", TyKind::PackedFloat32Array),
+        ("PackedFloat64Array", TyKind::PackedFloat64Array),
+        ("PackedStringArray", TyKind::PackedStringArray),
+        ("PackedVector2Array", TyKind::PackedVector2Array),
+        ("PackedVector3Array", TyKind::PackedVector3Array),
+        ("PackedVector4Array", TyKind::PackedVector4Array),
+        ("PackedColorArray", TyKind::PackedColorArray),
+    ];
+
+    impl<'a> TyKind<'a> {
+        pub fn is_numeric(&self) -> bool {
+            matches!(self, TyKind::Int(_) | TyKind::Float)
+        }
+
+        /// The builtin type GDScript spells `name`, such as `Vector2`.
+        pub fn builtin(name: &str) -> Option<TyKind<'a>> {
+            BUILTINS.iter().find(|(builtin, _)| *builtin == name).map(|(_, kind)| kind.clone())
+        }
+
+        /// The name of a builtin type, or `None` for a class.
+        pub fn builtin_name(&self) -> Option<&'static str> {
+            BUILTINS.iter().find(|(_, kind)| kind == self).map(|(name, _)| *name)
+        }
+    }
+
+    /// Shows the type the way GDScript spells it.
+    impl std::fmt::Display for TyKind<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                TyKind::Class(class) => write!(f, "{}", class.name.map_or("script", |name| name.as_str())),
+                kind => write!(f, "{}", kind.builtin_name().unwrap()),
+            }
+        }
+    }
+
+    /// Resolves a type annotation such as the `int` in `var x: int`.
+    /// Returns `None` for types that aren't known yet.
+    pub fn resolve<'a>(expr: &ast::Expr, tys: &'a Arena<TyKind<'a>>) -> Option<Ty<'a>> {
+        match expr.kind {
+            ExprKind::Ident(ident) => TyKind::builtin(ident.name.as_str()).map(|kind| tys.intern(kind).into()),
+            _ => None,
+        }
     }
 
     #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     pub struct Class<'a> {
+        pub name: Option<IdentName<'a>>,
+        /// The class this one extends, when it's known. Member lookups continue there.
+        pub base: Option<Ty<'a>>,
         pub fields: Vec<Field<'a>>,
+        pub consts: Vec<Const<'a>>,
+        pub enums: Vec<Enum<'a>>,
+        pub signals: Vec<Signal<'a>>,
+        pub funcs: Vec<FuncDef<'a>>,
+        /// Inner classes, each a `TyKind::Class`.
+        pub classes: Vec<Ty<'a>>,
+    }
+
+    impl<'a> Class<'a> {
+        /// Collects the members declared at the top level of `ast`. Constants and enum
+        /// values are evaluated on demand, so they can use ones declared further down.
+        ///
+        /// `base` is the already resolved class that `ast` extends. Inner classes
+        /// resolve their own base among the inner classes declared before them.
+        pub fn new(ast: &'a ast::Class<'a>, base: Option<Ty<'a>>, tys: &'a Arena<TyKind<'a>>) -> (Self, Vec<ConstError>) {
+            let mut class = Class {
+                name: ast.name.map(|name| name.name),
+                base,
+                fields: Vec::new(),
+                consts: Vec::new(),
+                enums: Vec::new(),
+                signals: Vec::new(),
+                funcs: Vec::new(),
+                classes: Vec::new(),
+            };
+            let mut errors = Vec::new();
+            let mut items = Vec::new();
+            for stmt in ast.stmt_list.stmts {
+                match stmt.kind {
+                    StmtKind::VarDef(var_def) => class.fields.push(Field {
+                        name: var_def.def.name.name,
+                        ty: var_def.def.ty.and_then(|ty| resolve(ty, tys)),
+                        ast: var_def,
+                    }),
+                    StmtKind::FuncDef(func_def) => class.funcs.push(FuncDef {
+                        name: func_def.name.name,
+                        ret_ty: func_def.result_ty.and_then(|ty| resolve(ty, tys)),
+                        ast: func_def,
+                    }),
+                    StmtKind::ConstDef(const_def) => items.push(ConstItem::Const(const_def)),
+                    StmtKind::EnumDef(enum_def) => items.push(ConstItem::Enum(enum_def)),
+                    StmtKind::SignalDef(signal_def) => class.signals.push(Signal {
+                        name: signal_def.name.name,
+                        params: signal_def.param_list.map_or(&[][..], |list| list.params).iter().map(|param| Param {
+                            name: param.name.name,
+                            ty: param.ty.and_then(|ty| resolve(ty, tys)),
+                        }).collect(),
+                        ast: signal_def,
+                    }),
+                    StmtKind::Class(inner) => {
+                        let base = match inner.extends {
+                            Some(ast::Extends { path: None, names: [name], .. }) => class.class(name.name),
+                            _ => None,
+                        };
+                        let (inner, inner_errors) = Class::new(inner, base, tys);
+                        errors.extend(inner_errors);
+                        class.classes.push(tys.intern(TyKind::Class(inner)).into());
+                    }
+                    _ => (),
+                }
+            }
+            let states = RefCell::new(items.iter().map(|_| ConstState::Pending).collect());
+            let items = ConstItems { class: &class, tys, items, states };
+            for index in 0..items.items.len() {
+                items.eval(index);
+            }
+            let (consts, enums, const_errors) = items.into_members();
+            class.consts = consts;
+            class.enums = enums;
+            errors.extend(const_errors);
+            (class, errors)
+        }
+
+        pub fn base_class(&self) -> Option<&Class<'a>> {
+            match self.base.as_deref() {
+                Some(TyKind::Class(base)) => Some(base),
+                _ => None,
+            }
+        }
+
+        /// Iterates over this class followed by its base classes.
+        pub fn ancestors(&self) -> impl Iterator<Item = &Class<'a>> {
+            std::iter::successors(Some(self), |class| class.base_class())
+        }
+
+        pub fn field(&self, name: IdentName<'a>) -> Option<&Field<'a>> {
+            self.ancestors().find_map(|class| class.fields.iter().find(|field| field.name == name))
+        }
+
+        pub fn func(&self, name: IdentName<'a>) -> Option<&FuncDef<'a>> {
+            self.ancestors().find_map(|class| class.funcs.iter().find(|func| func.name == name))
+        }
+
+        pub fn enum_(&self, name: IdentName<'a>) -> Option<&Enum<'a>> {
+            self.ancestors().find_map(|class| class.enums.iter().find(|enum_| enum_.name == Some(name)))
+        }
+
+        pub fn signal(&self, name: IdentName<'a>) -> Option<&Signal<'a>> {
+            self.ancestors().find_map(|class| class.signals.iter().find(|signal| signal.name == name))
+        }
+
+        /// Looks up an inner class by name.
+        pub fn class(&self, name: IdentName<'a>) -> Option<Ty<'a>> {
+            self.ancestors().find_map(|class| {
+                class.classes.iter().find(|ty| matches!(&***ty, TyKind::Class(inner) if inner.name == Some(name))).cloned()
+            })
+        }
+
+        /// Looks up a constant, including the members of anonymous enums.
+        pub fn const_val(&self, name: IdentName<'a>) -> Option<ConstVal> {
+            self.ancestors().find_map(|class| {
+                let const_ = class.consts.iter().find(|const_| const_.name == name).map(|const_| const_.val.clone());
+                const_.or_else(|| {
+                    class.enums.iter()
+                        .filter(|enum_| enum_.name.is_none())
+                        .find_map(|enum_| enum_.member(name))
+                        .map(ConstVal::Int)
+                })
+            })
+        }
+    }
+
+    impl<'a> ConstScope<'a> for Class<'a> {
+        fn lookup(&self, name: IdentName<'a>) -> Result<Option<ConstVal>, ConstErrorKind> {
+            Ok(self.const_val(name))
+        }
+
+        fn lookup_member(&self, base: IdentName<'a>, name: IdentName<'a>) -> Result<Option<ConstVal>, ConstErrorKind> {
+            Ok(self.enum_(base).and_then(|enum_| enum_.member(name)).map(ConstVal::Int))
+        }
+    }
+
+    /// A constant or enum of a class, before it's evaluated.
+    enum ConstItem<'a> {
+        Const(&'a ast::ConstDef<'a>),
+        Enum(&'a ast::EnumDef<'a>),
+    }
+
+    enum ConstState<'a> {
+        Pending,
+        InProgress,
+        Const(Result<ConstVal, ConstError>),
+        Enum(Vec<EnumMember<'a>>, Vec<ConstError>),
+    }
+
+    /// The constants and enums of a class, each evaluated the first time it's used.
+    /// `class` holds everything else, and its base class is complete.
+    struct ConstItems<'c, 'a> {
+        class: &'c Class<'a>,
+        tys: &'a Arena<TyKind<'a>>,
+        items: Vec<ConstItem<'a>>,
+        states: RefCell<Vec<ConstState<'a>>>,
+    }
+
+    impl<'a> ConstItems<'_, 'a> {
+        /// Evaluates an item unless it already is. Returns `false` when the item is being
+        /// evaluated already, which means that it depends on itself.
+        fn eval(&self, index: usize) -> bool {
+            match self.states.borrow()[index] {
+                ConstState::Pending => (),
+                ConstState::InProgress => return false,
+                _ => return true,
+            }
+            self.states.borrow_mut()[index] = ConstState::InProgress;
+            let state = match self.items[index] {
+                ConstItem::Const(const_def) => ConstState::Const(consts::eval(const_def.def.val.unwrap(), self)),
+                ConstItem::Enum(enum_def) => {
+                    let mut members: Vec<EnumMember> = Vec::new();
+                    let mut errors = Vec::new();
+                    let mut next = 0;
+                    for variant in enum_def.variants {
+                        if let Some(val) = variant.val {
+                            // Earlier members of the same enum can be used without qualification.
+                            match consts::eval(val, &EnumScope { scope: self, members: &members }) {
+                                Ok(ConstVal::Int(val)) => next = val,
+                                Ok(_) => errors.push(ConstError { span: val.span, kind: ConstErrorKind::EnumValueNotInt }),
+                                Err(err) if err.kind == ConstErrorKind::Invalid => (),
+                                Err(err) => errors.push(err),
+                            }
+                        }
+                        members.push(EnumMember { name: variant.name.name, val: next });
+                        next = next.wrapping_add(1);
+                    }
+                    ConstState::Enum(members, errors)
+                }
+            };
+            self.states.borrow_mut()[index] = state;
+            true
+        }
+
+        /// Splits the evaluated items into the constants and enums of the class, in declaration order.
+        fn into_members(self) -> (Vec<Const<'a>>, Vec<Enum<'a>>, Vec<ConstError>) {
+            let (mut consts, mut enums, mut errors) = (Vec::new(), Vec::new(), Vec::new());
+            for (item, state) in self.items.into_iter().zip(self.states.into_inner()) {
+                match (item, state) {
+                    (ConstItem::Const(const_def), ConstState::Const(Ok(val))) => consts.push(Const {
+                        name: const_def.def.name.name,
+                        ty: const_def.def.ty.and_then(|ty| resolve(ty, self.tys)),
+                        val,
+                        ast: const_def,
+                    }),
+                    (ConstItem::Const(_), ConstState::Const(Err(err))) if err.kind == ConstErrorKind::Invalid => (),
+                    (ConstItem::Const(_), ConstState::Const(Err(err))) => errors.push(err),
+                    (ConstItem::Enum(enum_def), ConstState::Enum(members, enum_errors)) => {
+                        enums.push(Enum { name: enum_def.name.map(|name| name.name), members, ast: enum_def });
+                        errors.extend(enum_errors);
+                    }
+                    _ => unreachable!("every item is evaluated"),
+                }
+            }
+            (consts, enums, errors)
+        }
+    }
+
+    impl<'a> ConstScope<'a> for ConstItems<'_, 'a> {
+        fn lookup(&self, name: IdentName<'a>) -> Result<Option<ConstVal>, ConstErrorKind> {
+            let index = self.items.iter().position(|item| match item {
+                ConstItem::Const(const_def) => const_def.def.name.name == name,
+                ConstItem::Enum(enum_def) => {
+                    enum_def.name.is_none() && enum_def.variants.iter().any(|variant| variant.name.name == name)
+                }
+            });
+            let Some(index) = index else { return self.class.lookup(name) };
+            if !self.eval(index) {
+                return Err(ConstErrorKind::Cycle);
+            }
+            Ok(match &self.states.borrow()[index] {
+                ConstState::Const(Ok(val)) => Some(val.clone()),
+                // Every constant in a cycle reports it, not just the one closing it.
+                ConstState::Const(Err(err)) if err.kind == ConstErrorKind::Cycle => return Err(ConstErrorKind::Cycle),
+                ConstState::Enum(members, _) => {
+                    members.iter().find(|member| member.name == name).map(|member| ConstVal::Int(member.val))
+                }
+                _ => None,
+            })
+        }
+
+        fn lookup_member(&self, base: IdentName<'a>, name: IdentName<'a>) -> Result<Option<ConstVal>, ConstErrorKind> {
+            let index = self.items.iter().position(|item| {
+                matches!(item, ConstItem::Enum(enum_def) if enum_def.name.is_some_and(|enum_name| enum_name.name == base))
+            });
+            let Some(index) = index else { return self.class.lookup_member(base, name) };
+            if !self.eval(index) {
+                return Err(ConstErrorKind::Cycle);
+            }
+            let ConstState::Enum(members, _) = &self.states.borrow()[index] else { unreachable!() };
+            Ok(members.iter().find(|member| member.name == name).map(|member| ConstVal::Int(member.val)))
+        }
+    }
+
+    /// The scope of an enum value, which also sees the members declared before it.
+    struct EnumScope<'c, 'a, S> {
+        scope: &'c S,
+        members: &'c [EnumMember<'a>],
+    }
+
+    impl<'a, S: ConstScope<'a>> ConstScope<'a> for EnumScope<'_, 'a, S> {
+        fn lookup(&self, name: IdentName<'a>) -> Result<Option<ConstVal>, ConstErrorKind> {
+            match self.members.iter().find(|member| member.name == name) {
+                Some(member) => Ok(Some(ConstVal::Int(member.val))),
+                None => self.scope.lookup(name),
+            }
+        }
+
+        fn lookup_member(&self, base: IdentName<'a>, name: IdentName<'a>) -> Result<Option<ConstVal>, ConstErrorKind> {
+            self.scope.lookup_member(base, name)
+        }
     }
 
     #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     pub struct Field<'a> {
         pub name: IdentName<'a>,
-        pub ty: Ty<'a>,
+        /// `None` for untyped fields and types that aren't resolved yet.
+        pub ty: Option<Ty<'a>>,
         pub ast: &'a ast::VarDef<'a>,
     }
 
     #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-    pub struct FuncDef<'a> {
+    pub struct Const<'a> {
         pub name: IdentName<'a>,
-        pub ret_ty: Ty<'a>,
+        pub ty: Option<Ty<'a>>,
+        pub val: ConstVal,
+        pub ast: &'a ast::ConstDef<'a>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Enum<'a> {
+        /// `None` for anonymous enums, whose members are class constants.
+        pub name: Option<IdentName<'a>>,
+        pub members: Vec<EnumMember<'a>>,
+        pub ast: &'a ast::EnumDef<'a>,
+    }
+
+    impl<'a> Enum<'a> {
+        pub fn member(&self, name: IdentName<'a>) -> Option<i64> {
+            self.members.iter().find(|member| member.name == name).map(|member| member.val)
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct EnumMember<'a> {
+        pub name: IdentName<'a>,
+        pub val: i64,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Signal<'a> {
+        pub name: IdentName<'a>,
+        pub params: Vec<Param<'a>>,
+        pub ast: &'a ast::SignalDef<'a>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Param<'a> {
+        pub name: IdentName<'a>,
+        pub ty: Option<Ty<'a>>,
+    }
 
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct FuncDef<'a> {
+        pub name: IdentName<'a>,
+        pub ret_ty: Option<Ty<'a>>,
+        pub ast: &'a ast::FuncDef<'a>,
     }
 
     #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     pub struct FuncSig<'a> {
         pub params: Vec<IdentTyPair<'a>>,
+        /// The number of leading parameters without a default value.
+        pub required: usize,
         pub ret_ty: Ty<'a>,
     }
 
@@ -66,3 +691,322 @@ pub mod ty {
     }
 }
 
+/// Compile-time evaluation of `const` initializers and enum values.
+pub mod consts {
+    use crate::{ast::{BinOpKind, Expr, ExprKind, FloatVal, LitKind, UnaryOpKind}, ident::IdentName, lexer::Span};
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub enum ConstVal {
+        Null,
+        Bool(bool),
+        Int(i64),
+        Float(FloatVal),
+        Str(String),
+    }
+
+    impl ConstVal {
+        pub fn is_truthy(&self) -> bool {
+            match self {
+                ConstVal::Null => false,
+                ConstVal::Bool(b) => *b,
+                ConstVal::Int(i) => *i != 0,
+                ConstVal::Float(f) => f.0 != 0.0,
+                ConstVal::Str(s) => !s.is_empty(),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ConstError {
+        pub span: Span,
+        pub kind: ConstErrorKind,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum ConstErrorKind {
+        /// The expression isn't something that can be evaluated at compile time.
+        NotConstant,
+        InvalidOperands,
+        DivisionByZero,
+        Overflow,
+        EnumValueNotInt,
+        /// The value depends on itself, through the constants or enums it uses.
+        Cycle,
+        /// The expression contains a syntax error, which the parser already reported.
+        Invalid,
+    }
+
+    /// Where the evaluator looks up names. Lookups return `None` for names that aren't
+    /// constants, and fail for constants that can't be used.
+    pub trait ConstScope<'a> {
+        fn lookup(&self, name: IdentName<'a>) -> Result<Option<ConstVal>, ConstErrorKind>;
+        /// Looks up `base.name`, such as a member of a named enum.
+        fn lookup_member(&self, base: IdentName<'a>, name: IdentName<'a>) -> Result<Option<ConstVal>, ConstErrorKind>;
+    }
+
+    pub fn eval<'a>(expr: &Expr<'a>, scope: &impl ConstScope<'a>) -> Result<ConstVal, ConstError> {
+        let error = |kind| ConstError { span: expr.span, kind };
+        match expr.kind {
+            ExprKind::Lit(lit) => match lit.kind {
+                LitKind::Int(val) => i64::try_from(val).map(ConstVal::Int).map_err(|_| error(ConstErrorKind::Overflow)),
+                LitKind::Float(val) => Ok(ConstVal::Float(val)),
+                LitKind::Str(val) => Ok(ConstVal::Str(val.to_owned())),
+                LitKind::StringName(_) | LitKind::NodePath(_) => Err(error(ConstErrorKind::NotConstant)),
+            },
+            ExprKind::Ident(ident) => scope.lookup(ident.name).map_err(error)?.or_else(|| builtin(ident.name.as_str()))
+                .ok_or_else(|| error(ConstErrorKind::NotConstant)),
+            ExprKind::Attr(attr) => match attr.expr.kind {
+                ExprKind::Ident(base) => scope.lookup_member(base.name, attr.name.name).map_err(error)?
+                    .ok_or_else(|| error(ConstErrorKind::NotConstant)),
+                _ => Err(error(ConstErrorKind::NotConstant)),
+            },
+            ExprKind::UnaryOp(op) => unary_op(op.kind, eval(op.expr, scope)?).map_err(error),
+            ExprKind::BinOp(op) => {
+                let lhs = eval(op.lhs, scope)?;
+                // `and` and `or` short-circuit like they do at runtime.
+                match op.kind {
+                    BinOpKind::And if !lhs.is_truthy() => return Ok(ConstVal::Bool(false)),
+                    BinOpKind::Or if lhs.is_truthy() => return Ok(ConstVal::Bool(true)),
+                    _ => (),
+                }
+                bin_op(op.kind, lhs, eval(op.rhs, scope)?).map_err(error)
+            }
+            ExprKind::Ternary(ternary) => {
+                if eval(ternary.cond, scope)?.is_truthy() {
+                    eval(ternary.then, scope)
+                } else {
+                    eval(ternary.else_, scope)
+                }
+            }
+            ExprKind::Error => Err(error(ConstErrorKind::Invalid)),
+            _ => Err(error(ConstErrorKind::NotConstant)),
+        }
+    }
+
+    fn builtin(name: &str) -> Option<ConstVal> {
+        Some(match name {
+            "true" => ConstVal::Bool(true),
+            "false" => ConstVal::Bool(false),
+            "null" => ConstVal::Null,
+            "PI" => ConstVal::Float(FloatVal(std::f64::consts::PI)),
+            "TAU" => ConstVal::Float(FloatVal(std::f64::consts::TAU)),
+            _ => return None,
+        })
+    }
+
+    fn unary_op(kind: UnaryOpKind, val: ConstVal) -> Result<ConstVal, ConstErrorKind> {
+        Ok(match (kind, val) {
+            (UnaryOpKind::Neg, ConstVal::Int(i)) => ConstVal::Int(i.checked_neg().ok_or(ConstErrorKind::Overflow)?),
+            (UnaryOpKind::Neg, ConstVal::Float(f)) => ConstVal::Float(FloatVal(-f.0)),
+            (UnaryOpKind::Pos, val @ (ConstVal::Int(_) | ConstVal::Float(_))) => val,
+            (UnaryOpKind::BitNot, ConstVal::Int(i)) => ConstVal::Int(!i),
+            (UnaryOpKind::Not, val) => ConstVal::Bool(!val.is_truthy()),
+            _ => return Err(ConstErrorKind::InvalidOperands),
+        })
+    }
+
+    fn bin_op(kind: BinOpKind, lhs: ConstVal, rhs: ConstVal) -> Result<ConstVal, ConstErrorKind> {
+        use ConstVal::*;
+        use BinOpKind::*;
+
+        Ok(match (kind, lhs, rhs) {
+            (And | Or, _, rhs) => Bool(rhs.is_truthy()),
+            (Add | Sub | Mul | Div | Mod | Pow | Shl | Shr | BitAnd | BitOr | BitXor, Int(a), Int(b)) => {
+                Int(int_op(kind, a, b)?)
+            }
+            (Add | Sub | Mul | Div | Pow | Lt | Le | Gt | Ge | Eq | Ne, a @ (Int(_) | Float(_)), b @ (Int(_) | Float(_))) => {
+                let (a, b) = (as_float(&a), as_float(&b));
+                match kind {
+                    Add => Float(FloatVal(a + b)),
+                    Sub => Float(FloatVal(a - b)),
+                    Mul => Float(FloatVal(a * b)),
+                    Div => Float(FloatVal(a / b)),
+                    Pow => Float(FloatVal(a.powf(b))),
+                    Lt => Bool(a < b),
+                    Le => Bool(a <= b),
+                    Gt => Bool(a > b),
+                    Ge => Bool(a >= b),
+                    Eq => Bool(a == b),
+                    _ => Bool(a != b),
+                }
+            }
+            (Add, Str(a), Str(b)) => Str(a + &b),
+            (Lt, Str(a), Str(b)) => Bool(a < b),
+            (Le, Str(a), Str(b)) => Bool(a <= b),
+            (Gt, Str(a), Str(b)) => Bool(a > b),
+            (Ge, Str(a), Str(b)) => Bool(a >= b),
+            (Eq, a, b) => Bool(a == b),
+            (Ne, a, b) => Bool(a != b),
+            (In | NotIn | Is | IsNot | As, _, _) => return Err(ConstErrorKind::NotConstant),
+            _ => return Err(ConstErrorKind::InvalidOperands),
+        })
+    }
+
+    fn int_op(kind: BinOpKind, a: i64, b: i64) -> Result<i64, ConstErrorKind> {
+        if matches!(kind, BinOpKind::Div | BinOpKind::Mod) && b == 0 {
+            return Err(ConstErrorKind::DivisionByZero);
+        }
+        let shift = || u32::try_from(b).map_err(|_| ConstErrorKind::InvalidOperands);
+        match kind {
+            BinOpKind::Add => a.checked_add(b),
+            BinOpKind::Sub => a.checked_sub(b),
+            BinOpKind::Mul => a.checked_mul(b),
+            BinOpKind::Div => a.checked_div(b),
+            BinOpKind::Mod => a.checked_rem(b),
+            BinOpKind::Pow => a.checked_pow(shift()?),
+            BinOpKind::Shl => a.checked_shl(shift()?),
+            BinOpKind::Shr => a.checked_shr(shift()?),
+            BinOpKind::BitAnd => Some(a & b),
+            BinOpKind::BitOr => Some(a | b),
+            BinOpKind::BitXor => Some(a ^ b),
+            _ => unreachable!(),
+        }.ok_or(ConstErrorKind::Overflow)
+    }
+
+    fn as_float(val: &ConstVal) -> f64 {
+        match val {
+            ConstVal::Int(i) => *i as f64,
+            ConstVal::Float(f) => f.0,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use internment::Arena;
+
+    use crate::{context, lexer, parser};
+
+    use super::{consts::{ConstErrorKind, ConstVal}, ty::{Class, TyKind}};
+
+    #[test]
+    fn class_members() {
+        let source = indoc! {r#"
+            const A = 2
+            const B: int = A * 3 + 1
+            const NAME := "gd" + "x"
+            enum {X, Y = B, Z}
+            enum State {
+                IDLE,
+                RUN = Y - 1,
+                JUMP = RUN + IDLE,
+            }
+            const C = State.JUMP if X == 0 else -1
+            const D = 1 / 0
+            const E = hp
+            enum Bad {Q = "q"}
+            signal died
+            signal hit(damage: int, source)
+            var hp: int = B
+        "#};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let ast = parser::parse(source, &tokens, &ctx).unwrap();
+        // The arena borrows from the AST, so it lives in `ctx` too.
+        let tys = ctx.alloc(Arena::new());
+        let (class, errors) = Class::new(ast, None, tys);
+        let errors: Vec<_> = errors.into_iter().map(|e| e.kind).collect();
+        assert_eq!(errors, vec![ConstErrorKind::DivisionByZero, ConstErrorKind::NotConstant, ConstErrorKind::EnumValueNotInt]);
+
+        let name = |s| ctx.new_ident_name(s);
+        assert_eq!(class.const_val(name("B")), Some(ConstVal::Int(7)));
+        assert_eq!(class.const_val(name("NAME")), Some(ConstVal::Str("gdx".to_owned())));
+        assert_eq!(class.const_val(name("Z")), Some(ConstVal::Int(8)));
+        assert_eq!(class.const_val(name("RUN")), None);
+        assert_eq!(class.enum_(name("State")).unwrap().member(name("JUMP")), Some(6));
+        assert_eq!(class.const_val(name("C")), Some(ConstVal::Int(6)));
+        assert_eq!(class.const_val(name("D")), None);
+        assert_eq!(class.consts[1].ty.as_deref(), Some(&TyKind::Int(super::ty::IntKind::I64)));
+
+        let hit = class.signal(name("hit")).unwrap();
+        assert_eq!(hit.params.len(), 2);
+        assert!(hit.params[0].ty.is_some() && hit.params[1].ty.is_none());
+        assert!(class.signal(name("died")).unwrap().params.is_empty());
+        assert!(class.field(name("hp")).unwrap().ty.is_some());
+    }
+
+    #[test]
+    fn forward_references() {
+        let source = indoc! {r#"
+            const MAX = LIMIT * 2
+            enum {A = B_VAL, B}
+            const B_VAL = State.RUN + 1
+            enum State {IDLE = LIMIT, RUN}
+            const LIMIT = 5
+            const LOOP = AGAIN + 1
+            const AGAIN = LOOP
+            enum Loop {X = Loop.Y, Y}
+        "#};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let ast = parser::parse(source, &tokens, &ctx).unwrap();
+        let tys = ctx.alloc(Arena::new());
+        let (class, errors) = Class::new(ast, None, tys);
+        let errors: Vec<_> = errors.into_iter().map(|e| (e.kind, &source[e.span.start as usize..e.span.end as usize])).collect();
+        assert_eq!(errors, [
+            (ConstErrorKind::Cycle, "AGAIN"),
+            (ConstErrorKind::Cycle, "LOOP"),
+            (ConstErrorKind::Cycle, "Loop.Y"),
+        ]);
+
+        let name = |s| ctx.new_ident_name(s);
+        assert_eq!(class.const_val(name("MAX")), Some(ConstVal::Int(10)));
+        assert_eq!(class.const_val(name("B_VAL")), Some(ConstVal::Int(7)));
+        assert_eq!(class.const_val(name("B")), Some(ConstVal::Int(8)));
+        // Constants and enums keep their declaration order.
+        let names: Vec<_> = class.consts.iter().map(|const_| const_.name.as_str()).collect();
+        assert_eq!(names, ["MAX", "B_VAL", "LIMIT"]);
+        assert_eq!(class.enums[1].name, Some(name("State")));
+    }
+
+    #[test]
+    fn inheritance() {
+        let source = indoc! {r#"
+            class_name Player extends "res://actor.gd"
+
+            class Base:
+                const SPEED = 10
+                enum Kind {A, B}
+                var hp: int
+                signal died
+                func hit():
+                    pass
+
+            class Mid extends Base:
+                var mp
+
+            class Leaf extends Mid:
+                const FAST = SPEED * 2
+                func hit():
+                    pass
+
+            class Orphan extends Node:
+                pass
+        "#};
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let ast = parser::parse(source, &tokens, &ctx).unwrap();
+        let tys = ctx.alloc(Arena::new());
+        let (class, errors) = Class::new(ast, None, tys);
+        assert_eq!(errors, vec![]);
+        let name = |s| ctx.new_ident_name(s);
+        assert_eq!(class.name, Some(name("Player")));
+
+        let leaf = class.class(name("Leaf")).unwrap();
+        let TyKind::Class(leaf) = &*leaf else { panic!() };
+        assert_eq!(leaf.ancestors().map(|class| class.name.unwrap().as_str()).collect::<Vec<_>>(), ["Leaf", "Mid", "Base"]);
+        assert!(leaf.field(name("hp")).is_some() && leaf.field(name("mp")).is_some());
+        assert!(leaf.signal(name("died")).is_some());
+        assert_eq!(leaf.enum_(name("Kind")).unwrap().member(name("B")), Some(1));
+        assert_eq!(leaf.const_val(name("FAST")), Some(ConstVal::Int(20)));
+        // Overrides shadow the base method.
+        assert_eq!(leaf.func(name("hit")).unwrap().ast.span.start, leaf.funcs[0].ast.span.start);
+        assert!(leaf.field(name("nope")).is_none());
+
+        let orphan = class.class(name("Orphan")).unwrap();
+        let TyKind::Class(orphan) = &*orphan else { panic!() };
+        assert!(orphan.base.is_none());
+    }
+}
@@ -0,0 +1,1378 @@
+use crate::ast::{BinOpKind, UnaryOpKind};
+
+/// What GDScript knows about a builtin type, written the way Godot's class reference shows it.
+///
+/// Signatures read like `lerp(to: Vector2, weight: float) -> Vector2`. A `static` prefix marks
+/// methods called on the type, an `= default` marks parameters that can be left out, and a final
+/// `...` takes any number of further arguments. Constructors leave out the name and the result.
+/// Types the table doesn't know, like `Object`, are `Variant`s.
+pub struct BuiltinTy {
+    pub name: &'static str,
+    /// The element type of packed arrays, which `T` stands for in signatures. `Self` stands
+    /// for the type itself, which lets types share methods.
+    element: Option<&'static str>,
+    /// What indexing a value gives, as in `v[0]`.
+    pub index: Option<&'static str>,
+    /// Properties, such as `x, y: float`.
+    members: &'static [&'static str],
+    /// Constants reached through the type, such as `ZERO, ONE: Vector2`.
+    constants: &'static [&'static str],
+    constructors: &'static [&'static str],
+    methods: &'static [&'static [&'static str]],
+}
+
+/// A signature of a method or constructor, with `Self` and `T` replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sig {
+    pub is_static: bool,
+    /// The types of the parameters.
+    pub params: Vec<&'static str>,
+    /// How many of the parameters have no default.
+    pub required: usize,
+    /// Whether any number of arguments can follow the parameters.
+    pub vararg: bool,
+    pub ret: &'static str,
+}
+
+/// The builtin type named `name`.
+pub fn ty(name: &str) -> Option<&'static BuiltinTy> {
+    TYS.iter().find(|ty| ty.name == name)
+}
+
+impl BuiltinTy {
+    /// The type of the property `name`.
+    pub fn member(&self, name: &str) -> Option<&'static str> {
+        find(self.members, name)
+    }
+
+    /// The type of the constant `name`.
+    pub fn constant(&self, name: &str) -> Option<&'static str> {
+        find(self.constants, name)
+    }
+
+    pub fn constructors(&self) -> impl Iterator<Item = Sig> + '_ {
+        self.constructors.iter().map(|sig| self.sig(sig).1)
+    }
+
+    pub fn method(&self, name: &str) -> Option<Sig> {
+        self.methods.iter().flat_map(|methods| methods.iter())
+            .map(|sig| self.sig(sig))
+            .find(|(method, _)| *method == name)
+            .map(|(_, sig)| sig)
+    }
+
+    fn subst(&self, ty: &'static str) -> &'static str {
+        match ty {
+            "Self" => self.name,
+            "T" => self.element.unwrap(),
+            ty => ty,
+        }
+    }
+
+    /// Parses a signature, returning the name of the method along with it.
+    fn sig(&self, sig: &'static str) -> (&'static str, Sig) {
+        let (is_static, sig) = match sig.strip_prefix("static ") {
+            Some(sig) => (true, sig),
+            None => (false, sig),
+        };
+        let (name, rest) = sig.split_once('(').unwrap();
+        let (params, ret) = rest.rsplit_once(')').unwrap();
+        let ret = ret.strip_prefix(" -> ").map_or(self.name, |ret| self.subst(ret));
+        let mut sig = Sig { is_static, params: Vec::new(), required: 0, vararg: false, ret };
+        for param in split_params(params) {
+            if param == "..." {
+                sig.vararg = true;
+                continue;
+            }
+            let (_, ty) = param.split_once(": ").unwrap();
+            match ty.split_once(" = ") {
+                Some((ty, _)) => sig.params.push(self.subst(ty)),
+                None => {
+                    sig.params.push(self.subst(ty));
+                    sig.required += 1;
+                }
+            }
+        }
+        (name, sig)
+    }
+}
+
+/// Finds `name` in entries like `x, y: float`, returning its type.
+fn find(entries: &'static [&'static str], name: &str) -> Option<&'static str> {
+    entries.iter().find_map(|entry| {
+        let (names, ty) = entry.split_once(": ").unwrap();
+        names.split(", ").any(|candidate| candidate == name).then_some(ty)
+    })
+}
+
+/// Splits parameters at the commas between them, skipping those inside defaults.
+fn split_params(params: &'static str) -> Vec<&'static str> {
+    let mut split = Vec::new();
+    let (mut depth, mut quoted, mut start) = (0, false, 0);
+    for (i, c) in params.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                split.push(params[start..i].trim());
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    if !params[start..].trim().is_empty() {
+        split.push(params[start..].trim());
+    }
+    split
+}
+
+/// The type of `lhs op rhs` for builtin types, or `None` when Godot doesn't allow it.
+pub fn bin_op(op: BinOpKind, lhs: &str, rhs: &str) -> Option<&'static str> {
+    if matches!(op, BinOpKind::Eq | BinOpKind::Ne) && lhs == rhs {
+        return Some("bool");
+    }
+    let op = op.as_str();
+    BIN_OPS.iter()
+        .find(|entry| entry.0 == lhs && entry.1 == op && (entry.2 == rhs || entry.2 == "*"))
+        .map(|entry| entry.3)
+}
+
+/// The type of `op ty` for builtin types, or `None` when Godot doesn't allow it.
+pub fn unary_op(op: UnaryOpKind, ty: &str) -> Option<&'static str> {
+    let op = op.as_str();
+    UNARY_OPS.iter().find(|entry| entry.0 == op && entry.1 == ty).map(|entry| entry.2)
+}
+
+/// Binary operators as `(lhs, op, rhs, result)`, following Godot's own table. A `*` on the
+/// right takes any type. Every type also compares with itself through `==` and `!=`.
+static BIN_OPS: &[(&str, &str, &str, &str)] = &[
+    // Integer arithmetic stays integral.
+    ("int", "+", "int", "int"), ("int", "-", "int", "int"), ("int", "*", "int", "int"), ("int", "/", "int", "int"),
+    ("int", "%", "int", "int"), ("int", "**", "int", "int"), ("int", "<<", "int", "int"), ("int", ">>", "int", "int"),
+    ("int", "&", "int", "int"), ("int", "|", "int", "int"), ("int", "^", "int", "int"),
+    // Anything with a float is a float. There's no `%` on floats; `fmod` does that.
+    ("int", "+", "float", "float"), ("int", "-", "float", "float"), ("int", "*", "float", "float"),
+    ("int", "/", "float", "float"), ("int", "**", "float", "float"), ("float", "+", "int", "float"),
+    ("float", "-", "int", "float"), ("float", "*", "int", "float"), ("float", "/", "int", "float"),
+    ("float", "**", "int", "float"), ("float", "+", "float", "float"), ("float", "-", "float", "float"),
+    ("float", "*", "float", "float"), ("float", "/", "float", "float"), ("float", "**", "float", "float"),
+    // Numbers compare across `int` and `float`, and `false` orders before `true`.
+    ("int", "==", "int", "bool"), ("int", "!=", "int", "bool"), ("int", "<", "int", "bool"),
+    ("int", "<=", "int", "bool"), ("int", ">", "int", "bool"), ("int", ">=", "int", "bool"),
+    ("int", "==", "float", "bool"), ("int", "!=", "float", "bool"), ("int", "<", "float", "bool"),
+    ("int", "<=", "float", "bool"), ("int", ">", "float", "bool"), ("int", ">=", "float", "bool"),
+    ("float", "==", "int", "bool"), ("float", "!=", "int", "bool"), ("float", "<", "int", "bool"),
+    ("float", "<=", "int", "bool"), ("float", ">", "int", "bool"), ("float", ">=", "int", "bool"),
+    ("float", "==", "float", "bool"), ("float", "!=", "float", "bool"), ("float", "<", "float", "bool"),
+    ("float", "<=", "float", "bool"), ("float", ">", "float", "bool"), ("float", ">=", "float", "bool"),
+    ("bool", "<", "bool", "bool"), ("bool", "<=", "bool", "bool"), ("bool", ">", "bool", "bool"),
+    ("bool", ">=", "bool", "bool"),
+    // Strings concatenate into a `String` and format with `%` whatever is on the right.
+    ("String", "+", "String", "String"), ("String", "+", "StringName", "String"),
+    ("StringName", "+", "String", "String"), ("StringName", "+", "StringName", "String"),
+    ("String", "==", "StringName", "bool"), ("String", "!=", "StringName", "bool"),
+    ("StringName", "==", "String", "bool"), ("StringName", "!=", "String", "bool"), ("String", "<", "String", "bool"),
+    ("String", "<=", "String", "bool"), ("String", ">", "String", "bool"), ("String", ">=", "String", "bool"),
+    ("StringName", "<", "StringName", "bool"), ("StringName", "<=", "StringName", "bool"),
+    ("StringName", ">", "StringName", "bool"), ("StringName", ">=", "StringName", "bool"),
+    ("String", "%", "*", "String"), ("StringName", "%", "*", "String"),
+    // Vectors work component-wise, and scale by numbers. Scaling an integer vector by a float gives a float vector.
+    ("Vector2", "+", "Vector2", "Vector2"), ("Vector2", "-", "Vector2", "Vector2"),
+    ("Vector2", "*", "Vector2", "Vector2"), ("Vector2", "/", "Vector2", "Vector2"),
+    ("Vector2", "*", "int", "Vector2"), ("Vector2", "/", "int", "Vector2"), ("int", "*", "Vector2", "Vector2"),
+    ("Vector2", "*", "float", "Vector2"), ("Vector2", "/", "float", "Vector2"), ("float", "*", "Vector2", "Vector2"),
+    ("Vector2", "<", "Vector2", "bool"), ("Vector2", "<=", "Vector2", "bool"), ("Vector2", ">", "Vector2", "bool"),
+    ("Vector2", ">=", "Vector2", "bool"), ("Vector2i", "+", "Vector2i", "Vector2i"),
+    ("Vector2i", "-", "Vector2i", "Vector2i"), ("Vector2i", "*", "Vector2i", "Vector2i"),
+    ("Vector2i", "/", "Vector2i", "Vector2i"), ("Vector2i", "%", "Vector2i", "Vector2i"),
+    ("Vector2i", "*", "int", "Vector2i"), ("Vector2i", "/", "int", "Vector2i"), ("Vector2i", "%", "int", "Vector2i"),
+    ("int", "*", "Vector2i", "Vector2i"), ("Vector2i", "*", "float", "Vector2"),
+    ("Vector2i", "/", "float", "Vector2"), ("float", "*", "Vector2i", "Vector2"),
+    ("Vector2i", "<", "Vector2i", "bool"), ("Vector2i", "<=", "Vector2i", "bool"),
+    ("Vector2i", ">", "Vector2i", "bool"), ("Vector2i", ">=", "Vector2i", "bool"),
+    ("Vector3", "+", "Vector3", "Vector3"), ("Vector3", "-", "Vector3", "Vector3"),
+    ("Vector3", "*", "Vector3", "Vector3"), ("Vector3", "/", "Vector3", "Vector3"),
+    ("Vector3", "*", "int", "Vector3"), ("Vector3", "/", "int", "Vector3"), ("int", "*", "Vector3", "Vector3"),
+    ("Vector3", "*", "float", "Vector3"), ("Vector3", "/", "float", "Vector3"), ("float", "*", "Vector3", "Vector3"),
+    ("Vector3", "<", "Vector3", "bool"), ("Vector3", "<=", "Vector3", "bool"), ("Vector3", ">", "Vector3", "bool"),
+    ("Vector3", ">=", "Vector3", "bool"), ("Vector3i", "+", "Vector3i", "Vector3i"),
+    ("Vector3i", "-", "Vector3i", "Vector3i"), ("Vector3i", "*", "Vector3i", "Vector3i"),
+    ("Vector3i", "/", "Vector3i", "Vector3i"), ("Vector3i", "%", "Vector3i", "Vector3i"),
+    ("Vector3i", "*", "int", "Vector3i"), ("Vector3i", "/", "int", "Vector3i"), ("Vector3i", "%", "int", "Vector3i"),
+    ("int", "*", "Vector3i", "Vector3i"), ("Vector3i", "*", "float", "Vector3"),
+    ("Vector3i", "/", "float", "Vector3"), ("float", "*", "Vector3i", "Vector3"),
+    ("Vector3i", "<", "Vector3i", "bool"), ("Vector3i", "<=", "Vector3i", "bool"),
+    ("Vector3i", ">", "Vector3i", "bool"), ("Vector3i", ">=", "Vector3i", "bool"),
+    ("Vector4", "+", "Vector4", "Vector4"), ("Vector4", "-", "Vector4", "Vector4"),
+    ("Vector4", "*", "Vector4", "Vector4"), ("Vector4", "/", "Vector4", "Vector4"),
+    ("Vector4", "*", "int", "Vector4"), ("Vector4", "/", "int", "Vector4"), ("int", "*", "Vector4", "Vector4"),
+    ("Vector4", "*", "float", "Vector4"), ("Vector4", "/", "float", "Vector4"), ("float", "*", "Vector4", "Vector4"),
+    ("Vector4", "<", "Vector4", "bool"), ("Vector4", "<=", "Vector4", "bool"), ("Vector4", ">", "Vector4", "bool"),
+    ("Vector4", ">=", "Vector4", "bool"), ("Vector4i", "+", "Vector4i", "Vector4i"),
+    ("Vector4i", "-", "Vector4i", "Vector4i"), ("Vector4i", "*", "Vector4i", "Vector4i"),
+    ("Vector4i", "/", "Vector4i", "Vector4i"), ("Vector4i", "%", "Vector4i", "Vector4i"),
+    ("Vector4i", "*", "int", "Vector4i"), ("Vector4i", "/", "int", "Vector4i"), ("Vector4i", "%", "int", "Vector4i"),
+    ("int", "*", "Vector4i", "Vector4i"), ("Vector4i", "*", "float", "Vector4"),
+    ("Vector4i", "/", "float", "Vector4"), ("float", "*", "Vector4i", "Vector4"),
+    ("Vector4i", "<", "Vector4i", "bool"), ("Vector4i", "<=", "Vector4i", "bool"),
+    ("Vector4i", ">", "Vector4i", "bool"), ("Vector4i", ">=", "Vector4i", "bool"),
+    // Transforms compose with each other and apply to what they transform. With the transform on the right, the inverse applies.
+    ("Transform2D", "*", "Transform2D", "Transform2D"), ("Transform2D", "*", "Vector2", "Vector2"),
+    ("Vector2", "*", "Transform2D", "Vector2"), ("Transform2D", "*", "Rect2", "Rect2"),
+    ("Rect2", "*", "Transform2D", "Rect2"), ("Transform2D", "*", "PackedVector2Array", "PackedVector2Array"),
+    ("PackedVector2Array", "*", "Transform2D", "PackedVector2Array"),
+    ("Transform3D", "*", "Transform3D", "Transform3D"), ("Transform3D", "*", "Vector3", "Vector3"),
+    ("Vector3", "*", "Transform3D", "Vector3"), ("Transform3D", "*", "AABB", "AABB"),
+    ("AABB", "*", "Transform3D", "AABB"), ("Transform3D", "*", "Plane", "Plane"),
+    ("Plane", "*", "Transform3D", "Plane"), ("Transform3D", "*", "PackedVector3Array", "PackedVector3Array"),
+    ("PackedVector3Array", "*", "Transform3D", "PackedVector3Array"), ("Basis", "*", "Basis", "Basis"),
+    ("Basis", "*", "Vector3", "Vector3"), ("Vector3", "*", "Basis", "Vector3"),
+    ("Quaternion", "+", "Quaternion", "Quaternion"), ("Quaternion", "-", "Quaternion", "Quaternion"),
+    ("Quaternion", "*", "Quaternion", "Quaternion"), ("Quaternion", "*", "Vector3", "Vector3"),
+    ("Vector3", "*", "Quaternion", "Vector3"), ("Projection", "*", "Projection", "Projection"),
+    ("Projection", "*", "Vector4", "Vector4"), ("Vector4", "*", "Projection", "Vector4"),
+    // Transforms and quaternions scale by numbers.
+    ("Transform2D", "*", "int", "Transform2D"), ("Transform2D", "/", "int", "Transform2D"),
+    ("int", "*", "Transform2D", "Transform2D"), ("Transform2D", "*", "float", "Transform2D"),
+    ("Transform2D", "/", "float", "Transform2D"), ("float", "*", "Transform2D", "Transform2D"),
+    ("Transform3D", "*", "int", "Transform3D"), ("Transform3D", "/", "int", "Transform3D"),
+    ("int", "*", "Transform3D", "Transform3D"), ("Transform3D", "*", "float", "Transform3D"),
+    ("Transform3D", "/", "float", "Transform3D"), ("float", "*", "Transform3D", "Transform3D"),
+    ("Basis", "*", "int", "Basis"), ("Basis", "/", "int", "Basis"), ("int", "*", "Basis", "Basis"),
+    ("Basis", "*", "float", "Basis"), ("Basis", "/", "float", "Basis"), ("float", "*", "Basis", "Basis"),
+    ("Quaternion", "*", "int", "Quaternion"), ("Quaternion", "/", "int", "Quaternion"),
+    ("int", "*", "Quaternion", "Quaternion"), ("Quaternion", "*", "float", "Quaternion"),
+    ("Quaternion", "/", "float", "Quaternion"), ("float", "*", "Quaternion", "Quaternion"),
+    // Colors work component-wise, alpha included.
+    ("Color", "+", "Color", "Color"), ("Color", "-", "Color", "Color"), ("Color", "*", "Color", "Color"),
+    ("Color", "/", "Color", "Color"), ("Color", "*", "int", "Color"), ("Color", "/", "int", "Color"),
+    ("int", "*", "Color", "Color"), ("Color", "*", "float", "Color"), ("Color", "/", "float", "Color"),
+    ("float", "*", "Color", "Color"),
+    // Arrays concatenate, and compare element by element.
+    ("Array", "+", "Array", "Array"), ("Array", "<", "Array", "bool"), ("Array", "<=", "Array", "bool"),
+    ("Array", ">", "Array", "bool"), ("Array", ">=", "Array", "bool"),
+    ("PackedByteArray", "+", "PackedByteArray", "PackedByteArray"),
+    ("PackedInt32Array", "+", "PackedInt32Array", "PackedInt32Array"),
+    ("PackedInt64Array", "+", "PackedInt64Array", "PackedInt64Array"),
+    ("PackedFloat32Array", "+", "PackedFloat32Array", "PackedFloat32Array"),
+    ("PackedFloat64Array", "+", "PackedFloat64Array", "PackedFloat64Array"),
+    ("PackedStringArray", "+", "PackedStringArray", "PackedStringArray"),
+    ("PackedVector2Array", "+", "PackedVector2Array", "PackedVector2Array"),
+    ("PackedVector3Array", "+", "PackedVector3Array", "PackedVector3Array"),
+    ("PackedVector4Array", "+", "PackedVector4Array", "PackedVector4Array"),
+    ("PackedColorArray", "+", "PackedColorArray", "PackedColorArray"), ("RID", "<", "RID", "bool"),
+    ("RID", "<=", "RID", "bool"), ("RID", ">", "RID", "bool"), ("RID", ">=", "RID", "bool"),
+];
+
+/// Unary operators as `(op, operand, result)`. Any value can be negated with `not`.
+static UNARY_OPS: &[(&str, &str, &str)] = &[
+    ("-", "int", "int"), ("+", "int", "int"), ("~", "int", "int"), ("-", "float", "float"), ("+", "float", "float"),
+    ("-", "Vector2", "Vector2"), ("+", "Vector2", "Vector2"), ("-", "Vector2i", "Vector2i"), ("+", "Vector2i", "Vector2i"),
+    ("-", "Vector3", "Vector3"), ("+", "Vector3", "Vector3"), ("-", "Vector3i", "Vector3i"), ("+", "Vector3i", "Vector3i"),
+    ("-", "Vector4", "Vector4"), ("+", "Vector4", "Vector4"), ("-", "Vector4i", "Vector4i"), ("+", "Vector4i", "Vector4i"),
+    ("-", "Quaternion", "Quaternion"), ("+", "Quaternion", "Quaternion"), ("-", "Plane", "Plane"), ("+", "Plane", "Plane"),
+    ("-", "Color", "Color"), ("+", "Color", "Color"),
+];
+
+/// Every builtin type with something to look up.
+static TYS: &[BuiltinTy] = &[
+    BuiltinTy {
+        name: "bool",
+        element: None,
+        index: None,
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: bool)", "(from: int)", "(from: float)"],
+        methods: &[],
+    },
+    BuiltinTy {
+        name: "int",
+        element: None,
+        index: None,
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: int)", "(from: float)", "(from: bool)", "(from: String)"],
+        methods: &[],
+    },
+    BuiltinTy {
+        name: "float",
+        element: None,
+        index: None,
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: float)", "(from: int)", "(from: bool)", "(from: String)"],
+        methods: &[],
+    },
+    BuiltinTy {
+        name: "String",
+        element: None,
+        index: Some("String"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: String)", "(from: StringName)", "(from: NodePath)"],
+        methods: &[STRING_METHODS],
+    },
+    BuiltinTy {
+        name: "StringName",
+        element: None,
+        index: Some("String"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: StringName)", "(from: String)"],
+        methods: &[STRING_METHODS],
+    },
+    BuiltinTy {
+        name: "NodePath",
+        element: None,
+        index: None,
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: NodePath)", "(from: String)"],
+        methods: &[&[
+            "get_as_property_path() -> NodePath",
+            "get_concatenated_names() -> StringName",
+            "get_concatenated_subnames() -> StringName",
+            "get_name(idx: int) -> StringName",
+            "get_name_count() -> int",
+            "get_subname(idx: int) -> StringName",
+            "get_subname_count() -> int",
+            "hash() -> int",
+            "is_absolute() -> bool",
+            "is_empty() -> bool",
+            "slice(begin: int, end: int = 2147483647) -> NodePath",
+        ]],
+    },
+    BuiltinTy {
+        name: "Vector2",
+        element: None,
+        index: Some("float"),
+        members: &["x, y: float"],
+        constants: &["ZERO, ONE, INF, LEFT, RIGHT, UP, DOWN: Vector2", "AXIS_X, AXIS_Y: int"],
+        constructors: &["()", "(from: Vector2)", "(from: Vector2i)", "(x: float, y: float)"],
+        methods: &[VECTOR_METHODS, &[
+            "angle() -> float",
+            "angle_to(to: Vector2) -> float",
+            "angle_to_point(to: Vector2) -> float",
+            "aspect() -> float",
+            "bezier_derivative(control_1: Vector2, control_2: Vector2, end: Vector2, t: float) -> Vector2",
+            "bezier_interpolate(control_1: Vector2, control_2: Vector2, end: Vector2, t: float) -> Vector2",
+            "bounce(n: Vector2) -> Vector2",
+            "cross(with: Vector2) -> float",
+            "static from_angle(angle: float) -> Vector2",
+            "limit_length(length: float = 1.0) -> Vector2",
+            "move_toward(to: Vector2, delta: float) -> Vector2",
+            "orthogonal() -> Vector2",
+            "project(b: Vector2) -> Vector2",
+            "reflect(line: Vector2) -> Vector2",
+            "rotated(angle: float) -> Vector2",
+            "slerp(to: Vector2, weight: float) -> Vector2",
+            "slide(n: Vector2) -> Vector2",
+        ]],
+    },
+    BuiltinTy {
+        name: "Vector2i",
+        element: None,
+        index: Some("int"),
+        members: &["x, y: int"],
+        constants: &["ZERO, ONE, MIN, MAX, LEFT, RIGHT, UP, DOWN: Vector2i", "AXIS_X, AXIS_Y: int"],
+        constructors: &["()", "(from: Vector2i)", "(from: Vector2)", "(x: int, y: int)"],
+        methods: &[INT_VECTOR_METHODS, &["aspect() -> float"]],
+    },
+    BuiltinTy {
+        name: "Vector3",
+        element: None,
+        index: Some("float"),
+        members: &["x, y, z: float"],
+        constants: &[
+            "ZERO, ONE, INF, LEFT, RIGHT, UP, DOWN, FORWARD, BACK: Vector3",
+            "MODEL_LEFT, MODEL_RIGHT, MODEL_TOP, MODEL_BOTTOM, MODEL_FRONT, MODEL_REAR: Vector3",
+            "AXIS_X, AXIS_Y, AXIS_Z: int",
+        ],
+        constructors: &["()", "(from: Vector3)", "(from: Vector3i)", "(x: float, y: float, z: float)"],
+        methods: &[VECTOR_METHODS, &[
+            "angle_to(to: Vector3) -> float",
+            "bezier_derivative(control_1: Vector3, control_2: Vector3, end: Vector3, t: float) -> Vector3",
+            "bezier_interpolate(control_1: Vector3, control_2: Vector3, end: Vector3, t: float) -> Vector3",
+            "bounce(n: Vector3) -> Vector3",
+            "cross(with: Vector3) -> Vector3",
+            "inverse() -> Vector3",
+            "limit_length(length: float = 1.0) -> Vector3",
+            "move_toward(to: Vector3, delta: float) -> Vector3",
+            "static octahedron_decode(uv: Vector2) -> Vector3",
+            "octahedron_encode() -> Vector2",
+            "outer(with: Vector3) -> Basis",
+            "project(b: Vector3) -> Vector3",
+            "reflect(n: Vector3) -> Vector3",
+            "rotated(axis: Vector3, angle: float) -> Vector3",
+            "signed_angle_to(to: Vector3, axis: Vector3) -> float",
+            "slerp(to: Vector3, weight: float) -> Vector3",
+            "slide(n: Vector3) -> Vector3",
+        ]],
+    },
+    BuiltinTy {
+        name: "Vector3i",
+        element: None,
+        index: Some("int"),
+        members: &["x, y, z: int"],
+        constants: &["ZERO, ONE, MIN, MAX, LEFT, RIGHT, UP, DOWN, FORWARD, BACK: Vector3i", "AXIS_X, AXIS_Y, AXIS_Z: int"],
+        constructors: &["()", "(from: Vector3i)", "(from: Vector3)", "(x: int, y: int, z: int)"],
+        methods: &[INT_VECTOR_METHODS],
+    },
+    BuiltinTy {
+        name: "Vector4",
+        element: None,
+        index: Some("float"),
+        members: &["x, y, z, w: float"],
+        constants: &["ZERO, ONE, INF: Vector4", "AXIS_X, AXIS_Y, AXIS_Z, AXIS_W: int"],
+        constructors: &["()", "(from: Vector4)", "(from: Vector4i)", "(x: float, y: float, z: float, w: float)"],
+        methods: &[VECTOR_METHODS, &["inverse() -> Vector4"]],
+    },
+    BuiltinTy {
+        name: "Vector4i",
+        element: None,
+        index: Some("int"),
+        members: &["x, y, z, w: int"],
+        constants: &["ZERO, ONE, MIN, MAX: Vector4i", "AXIS_X, AXIS_Y, AXIS_Z, AXIS_W: int"],
+        constructors: &["()", "(from: Vector4i)", "(from: Vector4)", "(x: int, y: int, z: int, w: int)"],
+        methods: &[INT_VECTOR_METHODS],
+    },
+    BuiltinTy {
+        name: "Rect2",
+        element: None,
+        index: None,
+        members: &["position, size, end: Vector2"],
+        constants: &[],
+        constructors: &[
+            "()",
+            "(from: Rect2)",
+            "(from: Rect2i)",
+            "(position: Vector2, size: Vector2)",
+            "(x: float, y: float, width: float, height: float)",
+        ],
+        methods: &[&[
+            "abs() -> Rect2",
+            "encloses(b: Rect2) -> bool",
+            "expand(to: Vector2) -> Rect2",
+            "get_area() -> float",
+            "get_center() -> Vector2",
+            "get_support(direction: Vector2) -> Vector2",
+            "grow(amount: float) -> Rect2",
+            "grow_individual(left: float, top: float, right: float, bottom: float) -> Rect2",
+            "grow_side(side: int, amount: float) -> Rect2",
+            "has_area() -> bool",
+            "has_point(point: Vector2) -> bool",
+            "intersection(b: Rect2) -> Rect2",
+            "intersects(b: Rect2, include_borders: bool = false) -> bool",
+            "is_equal_approx(rect: Rect2) -> bool",
+            "is_finite() -> bool",
+            "merge(b: Rect2) -> Rect2",
+        ]],
+    },
+    BuiltinTy {
+        name: "Rect2i",
+        element: None,
+        index: None,
+        members: &["position, size, end: Vector2i"],
+        constants: &[],
+        constructors: &[
+            "()",
+            "(from: Rect2i)",
+            "(from: Rect2)",
+            "(position: Vector2i, size: Vector2i)",
+            "(x: int, y: int, width: int, height: int)",
+        ],
+        methods: &[&[
+            "abs() -> Rect2i",
+            "encloses(b: Rect2i) -> bool",
+            "expand(to: Vector2i) -> Rect2i",
+            "get_area() -> int",
+            "get_center() -> Vector2i",
+            "grow(amount: int) -> Rect2i",
+            "grow_individual(left: int, top: int, right: int, bottom: int) -> Rect2i",
+            "grow_side(side: int, amount: int) -> Rect2i",
+            "has_area() -> bool",
+            "has_point(point: Vector2i) -> bool",
+            "intersection(b: Rect2i) -> Rect2i",
+            "intersects(b: Rect2i) -> bool",
+            "merge(b: Rect2i) -> Rect2i",
+        ]],
+    },
+    BuiltinTy {
+        name: "Transform2D",
+        element: None,
+        index: Some("Vector2"),
+        members: &["x, y, origin: Vector2"],
+        constants: &["IDENTITY, FLIP_X, FLIP_Y: Transform2D"],
+        constructors: &[
+            "()",
+            "(from: Transform2D)",
+            "(rotation: float, position: Vector2)",
+            "(rotation: float, scale: Vector2, skew: float, position: Vector2)",
+            "(x_axis: Vector2, y_axis: Vector2, origin: Vector2)",
+        ],
+        methods: &[&[
+            "affine_inverse() -> Transform2D",
+            "basis_xform(v: Vector2) -> Vector2",
+            "basis_xform_inv(v: Vector2) -> Vector2",
+            "determinant() -> float",
+            "get_origin() -> Vector2",
+            "get_rotation() -> float",
+            "get_scale() -> Vector2",
+            "get_skew() -> float",
+            "interpolate_with(xform: Transform2D, weight: float) -> Transform2D",
+            "inverse() -> Transform2D",
+            "is_conformal() -> bool",
+            "is_equal_approx(xform: Transform2D) -> bool",
+            "is_finite() -> bool",
+            "looking_at(target: Vector2 = Vector2(0, 0)) -> Transform2D",
+            "orthonormalized() -> Transform2D",
+            "rotated(angle: float) -> Transform2D",
+            "rotated_local(angle: float) -> Transform2D",
+            "scaled(scale: Vector2) -> Transform2D",
+            "scaled_local(scale: Vector2) -> Transform2D",
+            "translated(offset: Vector2) -> Transform2D",
+            "translated_local(offset: Vector2) -> Transform2D",
+        ]],
+    },
+    BuiltinTy {
+        name: "Transform3D",
+        element: None,
+        index: None,
+        members: &["basis: Basis", "origin: Vector3"],
+        constants: &["IDENTITY, FLIP_X, FLIP_Y, FLIP_Z: Transform3D"],
+        constructors: &[
+            "()",
+            "(from: Transform3D)",
+            "(basis: Basis, origin: Vector3)",
+            "(from: Projection)",
+            "(x_axis: Vector3, y_axis: Vector3, z_axis: Vector3, origin: Vector3)",
+        ],
+        methods: &[&[
+            "affine_inverse() -> Transform3D",
+            "interpolate_with(xform: Transform3D, weight: float) -> Transform3D",
+            "inverse() -> Transform3D",
+            "is_equal_approx(xform: Transform3D) -> bool",
+            "is_finite() -> bool",
+            "looking_at(target: Vector3, up: Vector3 = Vector3(0, 1, 0), use_model_front: bool = false) -> Transform3D",
+            "orthonormalized() -> Transform3D",
+            "rotated(axis: Vector3, angle: float) -> Transform3D",
+            "rotated_local(axis: Vector3, angle: float) -> Transform3D",
+            "scaled(scale: Vector3) -> Transform3D",
+            "scaled_local(scale: Vector3) -> Transform3D",
+            "translated(offset: Vector3) -> Transform3D",
+            "translated_local(offset: Vector3) -> Transform3D",
+        ]],
+    },
+    BuiltinTy {
+        name: "Basis",
+        element: None,
+        index: Some("Vector3"),
+        members: &["x, y, z: Vector3"],
+        constants: &["IDENTITY, FLIP_X, FLIP_Y, FLIP_Z: Basis"],
+        constructors: &[
+            "()",
+            "(from: Basis)",
+            "(axis: Vector3, angle: float)",
+            "(from: Quaternion)",
+            "(x_axis: Vector3, y_axis: Vector3, z_axis: Vector3)",
+        ],
+        methods: &[&[
+            "determinant() -> float",
+            "static from_euler(euler: Vector3, order: int = 2) -> Basis",
+            "static from_scale(scale: Vector3) -> Basis",
+            "get_euler(order: int = 2) -> Vector3",
+            "get_rotation_quaternion() -> Quaternion",
+            "get_scale() -> Vector3",
+            "inverse() -> Basis",
+            "is_conformal() -> bool",
+            "is_equal_approx(b: Basis) -> bool",
+            "is_finite() -> bool",
+            "static looking_at(target: Vector3, up: Vector3 = Vector3(0, 1, 0), use_model_front: bool = false) -> Basis",
+            "orthonormalized() -> Basis",
+            "rotated(axis: Vector3, angle: float) -> Basis",
+            "scaled(scale: Vector3) -> Basis",
+            "slerp(to: Basis, weight: float) -> Basis",
+            "tdotx(with: Vector3) -> float",
+            "tdoty(with: Vector3) -> float",
+            "tdotz(with: Vector3) -> float",
+            "transposed() -> Basis",
+        ]],
+    },
+    BuiltinTy {
+        name: "Quaternion",
+        element: None,
+        index: Some("float"),
+        members: &["x, y, z, w: float"],
+        constants: &["IDENTITY: Quaternion"],
+        constructors: &[
+            "()",
+            "(from: Quaternion)",
+            "(arc_from: Vector3, arc_to: Vector3)",
+            "(axis: Vector3, angle: float)",
+            "(from: Basis)",
+            "(x: float, y: float, z: float, w: float)",
+        ],
+        methods: &[&[
+            "angle_to(to: Quaternion) -> float",
+            "dot(with: Quaternion) -> float",
+            "exp() -> Quaternion",
+            "static from_euler(euler: Vector3) -> Quaternion",
+            "get_angle() -> float",
+            "get_axis() -> Vector3",
+            "get_euler(order: int = 2) -> Vector3",
+            "inverse() -> Quaternion",
+            "is_equal_approx(to: Quaternion) -> bool",
+            "is_finite() -> bool",
+            "is_normalized() -> bool",
+            "length() -> float",
+            "length_squared() -> float",
+            "log() -> Quaternion",
+            "normalized() -> Quaternion",
+            "slerp(to: Quaternion, weight: float) -> Quaternion",
+            "slerpni(to: Quaternion, weight: float) -> Quaternion",
+            "spherical_cubic_interpolate(b: Quaternion, pre_a: Quaternion, post_b: Quaternion, weight: float) -> Quaternion",
+            "spherical_cubic_interpolate_in_time(b: Quaternion, pre_a: Quaternion, post_b: Quaternion, weight: float, \
+                b_t: float, pre_a_t: float, post_b_t: float) -> Quaternion",
+        ]],
+    },
+    BuiltinTy {
+        name: "Plane",
+        element: None,
+        index: None,
+        members: &["normal: Vector3", "x, y, z, d: float"],
+        constants: &["PLANE_YZ, PLANE_XZ, PLANE_XY: Plane"],
+        constructors: &[
+            "()",
+            "(from: Plane)",
+            "(a: float, b: float, c: float, d: float)",
+            "(normal: Vector3)",
+            "(normal: Vector3, d: float)",
+            "(normal: Vector3, point: Vector3)",
+            "(point1: Vector3, point2: Vector3, point3: Vector3)",
+        ],
+        methods: &[&[
+            "distance_to(point: Vector3) -> float",
+            "get_center() -> Vector3",
+            "has_point(point: Vector3, tolerance: float = 1e-05) -> bool",
+            "intersect_3(b: Plane, c: Plane) -> Variant",
+            "intersects_ray(from: Vector3, dir: Vector3) -> Variant",
+            "intersects_segment(from: Vector3, to: Vector3) -> Variant",
+            "is_equal_approx(to_plane: Plane) -> bool",
+            "is_finite() -> bool",
+            "is_point_over(point: Vector3) -> bool",
+            "normalized() -> Plane",
+            "project(point: Vector3) -> Vector3",
+        ]],
+    },
+    BuiltinTy {
+        name: "AABB",
+        element: None,
+        index: None,
+        members: &["position, size, end: Vector3"],
+        constants: &[],
+        constructors: &["()", "(from: AABB)", "(position: Vector3, size: Vector3)"],
+        methods: &[&[
+            "abs() -> AABB",
+            "encloses(with: AABB) -> bool",
+            "expand(to_point: Vector3) -> AABB",
+            "get_center() -> Vector3",
+            "get_endpoint(idx: int) -> Vector3",
+            "get_longest_axis() -> Vector3",
+            "get_longest_axis_index() -> int",
+            "get_longest_axis_size() -> float",
+            "get_shortest_axis() -> Vector3",
+            "get_shortest_axis_index() -> int",
+            "get_shortest_axis_size() -> float",
+            "get_support(direction: Vector3) -> Vector3",
+            "get_volume() -> float",
+            "grow(by: float) -> AABB",
+            "has_point(point: Vector3) -> bool",
+            "has_surface() -> bool",
+            "has_volume() -> bool",
+            "intersection(with: AABB) -> AABB",
+            "intersects(with: AABB) -> bool",
+            "intersects_plane(plane: Plane) -> bool",
+            "intersects_ray(from: Vector3, dir: Vector3) -> Variant",
+            "intersects_segment(from: Vector3, to: Vector3) -> Variant",
+            "is_equal_approx(aabb: AABB) -> bool",
+            "is_finite() -> bool",
+            "merge(with: AABB) -> AABB",
+        ]],
+    },
+    BuiltinTy {
+        name: "Projection",
+        element: None,
+        index: Some("Vector4"),
+        members: &["x, y, z, w: Vector4"],
+        constants: &[
+            "PLANE_NEAR, PLANE_FAR, PLANE_LEFT, PLANE_TOP, PLANE_RIGHT, PLANE_BOTTOM: int",
+            "IDENTITY, ZERO: Projection",
+        ],
+        constructors: &[
+            "()",
+            "(from: Projection)",
+            "(from: Transform3D)",
+            "(x_axis: Vector4, y_axis: Vector4, z_axis: Vector4, w_axis: Vector4)",
+        ],
+        methods: &[&[
+            "static create_depth_correction(flip_y: bool) -> Projection",
+            "static create_fit_aabb(aabb: AABB) -> Projection",
+            "static create_for_hmd(eye: int, aspect: float, intraocular_dist: float, display_width: float, \
+                display_to_lens: float, oversample: float, z_near: float, z_far: float) -> Projection",
+            "static create_frustum(left: float, right: float, bottom: float, top: float, z_near: float, z_far: float) \
+                -> Projection",
+            "static create_frustum_aspect(size: float, aspect: float, offset: Vector2, z_near: float, z_far: float, \
+                flip_fov: bool = false) -> Projection",
+            "static create_light_atlas_rect(rect: Rect2) -> Projection",
+            "static create_orthogonal(left: float, right: float, bottom: float, top: float, z_near: float, z_far: float) \
+                -> Projection",
+            "static create_orthogonal_aspect(size: float, aspect: float, z_near: float, z_far: float, \
+                flip_fov: bool = false) -> Projection",
+            "static create_perspective(fovy: float, aspect: float, z_near: float, z_far: float, flip_fov: bool = false) \
+                -> Projection",
+            "static create_perspective_hmd(fovy: float, aspect: float, z_near: float, z_far: float, flip_fov: bool, \
+                eye: int, intraocular_dist: float, convergence_dist: float) -> Projection",
+            "determinant() -> float",
+            "flipped_y() -> Projection",
+            "get_aspect() -> float",
+            "get_far_plane_half_extents() -> Vector2",
+            "get_fov() -> float",
+            "static get_fovy(fovx: float, aspect: float) -> float",
+            "get_lod_multiplier() -> float",
+            "get_pixels_per_meter(for_pixel_width: int) -> int",
+            "get_projection_plane(plane: int) -> Plane",
+            "get_viewport_half_extents() -> Vector2",
+            "get_z_far() -> float",
+            "get_z_near() -> float",
+            "inverse() -> Projection",
+            "is_orthogonal() -> bool",
+            "jitter_offseted(offset: Vector2) -> Projection",
+            "perspective_znear_adjusted(new_znear: float) -> Projection",
+        ]],
+    },
+    BuiltinTy {
+        name: "Color",
+        element: None,
+        index: Some("float"),
+        members: &["r, g, b, a, h, s, v, ok_hsl_h, ok_hsl_s, ok_hsl_l: float", "r8, g8, b8, a8: int"],
+        constants: &[
+            "ALICE_BLUE, ANTIQUE_WHITE, AQUA, AQUAMARINE, AZURE, BEIGE, BISQUE, BLACK, BLANCHED_ALMOND, BLUE, \
+                BLUE_VIOLET, BROWN, BURLYWOOD, CADET_BLUE, CHARTREUSE, CHOCOLATE, CORAL, CORNFLOWER_BLUE, CORNSILK, \
+                CRIMSON, CYAN, DARK_BLUE, DARK_CYAN, DARK_GOLDENROD, DARK_GRAY, DARK_GREEN, DARK_KHAKI, DARK_MAGENTA, \
+                DARK_OLIVE_GREEN, DARK_ORANGE, DARK_ORCHID, DARK_RED, DARK_SALMON, DARK_SEA_GREEN, DARK_SLATE_BLUE, \
+                DARK_SLATE_GRAY, DARK_TURQUOISE, DARK_VIOLET, DEEP_PINK, DEEP_SKY_BLUE, DIM_GRAY, DODGER_BLUE, \
+                FIREBRICK, FLORAL_WHITE, FOREST_GREEN, FUCHSIA, GAINSBORO, GHOST_WHITE, GOLD, GOLDENROD, GRAY, GREEN, \
+                GREEN_YELLOW, HONEYDEW, HOT_PINK, INDIAN_RED, INDIGO, IVORY, KHAKI, LAVENDER, LAVENDER_BLUSH, \
+                LAWN_GREEN, LEMON_CHIFFON, LIGHT_BLUE, LIGHT_CORAL, LIGHT_CYAN, LIGHT_GOLDENROD, LIGHT_GRAY, \
+                LIGHT_GREEN, LIGHT_PINK, LIGHT_SALMON, LIGHT_SEA_GREEN, LIGHT_SKY_BLUE, LIGHT_SLATE_GRAY, \
+                LIGHT_STEEL_BLUE, LIGHT_YELLOW, LIME, LIME_GREEN, LINEN, MAGENTA, MAROON, MEDIUM_AQUAMARINE, \
+                MEDIUM_BLUE, MEDIUM_ORCHID, MEDIUM_PURPLE, MEDIUM_SEA_GREEN, MEDIUM_SLATE_BLUE, MEDIUM_SPRING_GREEN, \
+                MEDIUM_TURQUOISE, MEDIUM_VIOLET_RED, MIDNIGHT_BLUE, MINT_CREAM, MISTY_ROSE, MOCCASIN, NAVAJO_WHITE, \
+                NAVY_BLUE, OLD_LACE, OLIVE, OLIVE_DRAB, ORANGE, ORANGE_RED, ORCHID, PALE_GOLDENROD, PALE_GREEN, \
+                PALE_TURQUOISE, PALE_VIOLET_RED, PAPAYA_WHIP, PEACH_PUFF, PERU, PINK, PLUM, POWDER_BLUE, PURPLE, \
+                REBECCA_PURPLE, RED, ROSY_BROWN, ROYAL_BLUE, SADDLE_BROWN, SALMON, SANDY_BROWN, SEA_GREEN, SEASHELL, \
+                SIENNA, SILVER, SKY_BLUE, SLATE_BLUE, SLATE_GRAY, SNOW, SPRING_GREEN, STEEL_BLUE, TAN, TEAL, THISTLE, \
+                TOMATO, TRANSPARENT, TURQUOISE, VIOLET, WEB_GRAY, WEB_GREEN, WEB_MAROON, WEB_PURPLE, WHEAT, WHITE, \
+                WHITE_SMOKE, YELLOW, YELLOW_GREEN: Color",
+        ],
+        constructors: &[
+            "()",
+            "(from: Color)",
+            "(from: Color, alpha: float)",
+            "(code: String)",
+            "(code: String, alpha: float)",
+            "(r: float, g: float, b: float)",
+            "(r: float, g: float, b: float, a: float)",
+        ],
+        methods: &[&[
+            "blend(over: Color) -> Color",
+            "clamp(min: Color = Color(0, 0, 0, 0), max: Color = Color(1, 1, 1, 1)) -> Color",
+            "darkened(amount: float) -> Color",
+            "static from_hsv(h: float, s: float, v: float, alpha: float = 1.0) -> Color",
+            "static from_ok_hsl(h: float, s: float, l: float, alpha: float = 1.0) -> Color",
+            "static from_rgbe9995(rgbe: int) -> Color",
+            "static from_string(str: String, default: Color) -> Color",
+            "get_luminance() -> float",
+            "static hex(hex: int) -> Color",
+            "static hex64(hex: int) -> Color",
+            "static html(rgba: String) -> Color",
+            "static html_is_valid(color: String) -> bool",
+            "inverted() -> Color",
+            "is_equal_approx(to: Color) -> bool",
+            "lerp(to: Color, weight: float) -> Color",
+            "lightened(amount: float) -> Color",
+            "linear_to_srgb() -> Color",
+            "srgb_to_linear() -> Color",
+            "to_abgr32() -> int",
+            "to_abgr64() -> int",
+            "to_argb32() -> int",
+            "to_argb64() -> int",
+            "to_html(with_alpha: bool = true) -> String",
+            "to_rgba32() -> int",
+            "to_rgba64() -> int",
+        ]],
+    },
+    BuiltinTy {
+        name: "RID",
+        element: None,
+        index: None,
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: RID)"],
+        methods: &[&["get_id() -> int", "is_valid() -> bool"]],
+    },
+    BuiltinTy {
+        name: "Callable",
+        element: None,
+        index: None,
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: Callable)", "(object: Object, method: StringName)"],
+        methods: &[&[
+            "bind(...) -> Callable",
+            "bindv(arguments: Array) -> Callable",
+            "call(...) -> Variant",
+            "call_deferred(...) -> void",
+            "callv(arguments: Array) -> Variant",
+            "static create(variant: Variant, method: StringName) -> Callable",
+            "get_argument_count() -> int",
+            "get_bound_arguments() -> Array",
+            "get_bound_arguments_count() -> int",
+            "get_method() -> StringName",
+            "get_object() -> Object",
+            "get_object_id() -> int",
+            "hash() -> int",
+            "is_custom() -> bool",
+            "is_null() -> bool",
+            "is_standard() -> bool",
+            "is_valid() -> bool",
+            "rpc(...) -> void",
+            "rpc_id(peer_id: int, ...) -> void",
+            "unbind(argcount: int) -> Callable",
+        ]],
+    },
+    BuiltinTy {
+        name: "Signal",
+        element: None,
+        index: None,
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: Signal)", "(object: Object, signal: StringName)"],
+        methods: &[&[
+            "connect(callable: Callable, flags: int = 0) -> int",
+            "disconnect(callable: Callable) -> void",
+            "emit(...) -> void",
+            "get_connections() -> Array",
+            "get_name() -> StringName",
+            "get_object() -> Object",
+            "get_object_id() -> int",
+            "is_connected(callable: Callable) -> bool",
+            "is_null() -> bool",
+        ]],
+    },
+    BuiltinTy {
+        name: "Dictionary",
+        element: None,
+        index: Some("Variant"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: Dictionary)"],
+        methods: &[&[
+            "clear() -> void",
+            "duplicate(deep: bool = false) -> Dictionary",
+            "erase(key: Variant) -> bool",
+            "find_key(value: Variant) -> Variant",
+            "get(key: Variant, default: Variant = null) -> Variant",
+            "get_or_add(key: Variant, default: Variant = null) -> Variant",
+            "has(key: Variant) -> bool",
+            "has_all(keys: Array) -> bool",
+            "hash() -> int",
+            "is_empty() -> bool",
+            "is_read_only() -> bool",
+            "keys() -> Array",
+            "make_read_only() -> void",
+            "merge(dictionary: Dictionary, overwrite: bool = false) -> void",
+            "merged(dictionary: Dictionary, overwrite: bool = false) -> Dictionary",
+            "recursive_equal(dictionary: Dictionary, recursion_count: int) -> bool",
+            "size() -> int",
+            "values() -> Array",
+        ]],
+    },
+    BuiltinTy {
+        name: "Array",
+        element: None,
+        index: Some("Variant"),
+        members: &[],
+        constants: &[],
+        constructors: &[
+            "()",
+            "(from: Array)",
+            "(base: Array, type: int, class_name: StringName, script: Variant)",
+            "(from: PackedByteArray)",
+            "(from: PackedInt32Array)",
+            "(from: PackedInt64Array)",
+            "(from: PackedFloat32Array)",
+            "(from: PackedFloat64Array)",
+            "(from: PackedStringArray)",
+            "(from: PackedVector2Array)",
+            "(from: PackedVector3Array)",
+            "(from: PackedVector4Array)",
+            "(from: PackedColorArray)",
+        ],
+        methods: &[&[
+            "all(method: Callable) -> bool",
+            "any(method: Callable) -> bool",
+            "append(value: Variant) -> void",
+            "append_array(array: Array) -> void",
+            "assign(array: Array) -> void",
+            "back() -> Variant",
+            "bsearch(value: Variant, before: bool = true) -> int",
+            "bsearch_custom(value: Variant, func: Callable, before: bool = true) -> int",
+            "clear() -> void",
+            "count(value: Variant) -> int",
+            "duplicate(deep: bool = false) -> Array",
+            "erase(value: Variant) -> void",
+            "fill(value: Variant) -> void",
+            "filter(method: Callable) -> Array",
+            "find(what: Variant, from: int = 0) -> int",
+            "front() -> Variant",
+            "get_typed_builtin() -> int",
+            "get_typed_class_name() -> StringName",
+            "get_typed_script() -> Variant",
+            "has(value: Variant) -> bool",
+            "hash() -> int",
+            "insert(position: int, value: Variant) -> int",
+            "is_empty() -> bool",
+            "is_read_only() -> bool",
+            "is_same_typed(array: Array) -> bool",
+            "is_typed() -> bool",
+            "make_read_only() -> void",
+            "map(method: Callable) -> Array",
+            "max() -> Variant",
+            "min() -> Variant",
+            "pick_random() -> Variant",
+            "pop_at(position: int) -> Variant",
+            "pop_back() -> Variant",
+            "pop_front() -> Variant",
+            "push_back(value: Variant) -> void",
+            "push_front(value: Variant) -> void",
+            "reduce(method: Callable, accum: Variant = null) -> Variant",
+            "remove_at(position: int) -> void",
+            "resize(size: int) -> int",
+            "reverse() -> void",
+            "rfind(what: Variant, from: int = -1) -> int",
+            "shuffle() -> void",
+            "size() -> int",
+            "slice(begin: int, end: int = 2147483647, step: int = 1, deep: bool = false) -> Array",
+            "sort() -> void",
+            "sort_custom(func: Callable) -> void",
+        ]],
+    },
+    BuiltinTy {
+        name: "PackedByteArray",
+        element: Some("int"),
+        index: Some("int"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: PackedByteArray)", "(from: Array)"],
+        methods: &[PACKED_ARRAY_METHODS, BYTE_ARRAY_METHODS],
+    },
+    BuiltinTy {
+        name: "PackedInt32Array",
+        element: Some("int"),
+        index: Some("int"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: PackedInt32Array)", "(from: Array)"],
+        methods: &[PACKED_ARRAY_METHODS],
+    },
+    BuiltinTy {
+        name: "PackedInt64Array",
+        element: Some("int"),
+        index: Some("int"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: PackedInt64Array)", "(from: Array)"],
+        methods: &[PACKED_ARRAY_METHODS],
+    },
+    BuiltinTy {
+        name: "PackedFloat32Array",
+        element: Some("float"),
+        index: Some("float"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: PackedFloat32Array)", "(from: Array)"],
+        methods: &[PACKED_ARRAY_METHODS],
+    },
+    BuiltinTy {
+        name: "PackedFloat64Array",
+        element: Some("float"),
+        index: Some("float"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: PackedFloat64Array)", "(from: Array)"],
+        methods: &[PACKED_ARRAY_METHODS],
+    },
+    BuiltinTy {
+        name: "PackedStringArray",
+        element: Some("String"),
+        index: Some("String"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: PackedStringArray)", "(from: Array)"],
+        methods: &[PACKED_ARRAY_METHODS],
+    },
+    BuiltinTy {
+        name: "PackedVector2Array",
+        element: Some("Vector2"),
+        index: Some("Vector2"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: PackedVector2Array)", "(from: Array)"],
+        methods: &[PACKED_ARRAY_METHODS],
+    },
+    BuiltinTy {
+        name: "PackedVector3Array",
+        element: Some("Vector3"),
+        index: Some("Vector3"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: PackedVector3Array)", "(from: Array)"],
+        methods: &[PACKED_ARRAY_METHODS],
+    },
+    BuiltinTy {
+        name: "PackedVector4Array",
+        element: Some("Vector4"),
+        index: Some("Vector4"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: PackedVector4Array)", "(from: Array)"],
+        methods: &[PACKED_ARRAY_METHODS],
+    },
+    BuiltinTy {
+        name: "PackedColorArray",
+        element: Some("Color"),
+        index: Some("Color"),
+        members: &[],
+        constants: &[],
+        constructors: &["()", "(from: PackedColorArray)", "(from: Array)"],
+        methods: &[PACKED_ARRAY_METHODS],
+    },
+];
+
+
+/// Methods of `Vector2`, `Vector3` and `Vector4`.
+static VECTOR_METHODS: &[&str] = &[
+    "abs() -> Self",
+    "ceil() -> Self",
+    "clamp(min: Self, max: Self) -> Self",
+    "clampf(min: float, max: float) -> Self",
+    "cubic_interpolate(b: Self, pre_a: Self, post_b: Self, weight: float) -> Self",
+    "cubic_interpolate_in_time(b: Self, pre_a: Self, post_b: Self, weight: float, b_t: float, pre_a_t: float, \
+        post_b_t: float) -> Self",
+    "direction_to(to: Self) -> Self",
+    "distance_squared_to(to: Self) -> float",
+    "distance_to(to: Self) -> float",
+    "dot(with: Self) -> float",
+    "floor() -> Self",
+    "is_equal_approx(to: Self) -> bool",
+    "is_finite() -> bool",
+    "is_normalized() -> bool",
+    "is_zero_approx() -> bool",
+    "length() -> float",
+    "length_squared() -> float",
+    "lerp(to: Self, weight: float) -> Self",
+    "max(with: Self) -> Self",
+    "max_axis_index() -> int",
+    "maxf(with: float) -> Self",
+    "min(with: Self) -> Self",
+    "min_axis_index() -> int",
+    "minf(with: float) -> Self",
+    "normalized() -> Self",
+    "posmod(mod: float) -> Self",
+    "posmodv(modv: Self) -> Self",
+    "round() -> Self",
+    "sign() -> Self",
+    "snapped(step: Self) -> Self",
+    "snappedf(step: float) -> Self",
+];
+
+/// Methods of `Vector2i`, `Vector3i` and `Vector4i`.
+static INT_VECTOR_METHODS: &[&str] = &[
+    "abs() -> Self",
+    "clamp(min: Self, max: Self) -> Self",
+    "clampi(min: int, max: int) -> Self",
+    "distance_squared_to(to: Self) -> int",
+    "distance_to(to: Self) -> float",
+    "length() -> float",
+    "length_squared() -> int",
+    "max(with: Self) -> Self",
+    "max_axis_index() -> int",
+    "maxi(with: int) -> Self",
+    "min(with: Self) -> Self",
+    "min_axis_index() -> int",
+    "mini(with: int) -> Self",
+    "sign() -> Self",
+    "snapped(step: Self) -> Self",
+    "snappedi(step: int) -> Self",
+];
+
+/// Methods of `String`, which `StringName` has too.
+static STRING_METHODS: &[&str] = &[
+    "begins_with(text: String) -> bool",
+    "bigrams() -> PackedStringArray",
+    "bin_to_int() -> int",
+    "c_escape() -> String",
+    "c_unescape() -> String",
+    "capitalize() -> String",
+    "casecmp_to(to: String) -> int",
+    "static chr(char: int) -> String",
+    "contains(what: String) -> bool",
+    "containsn(what: String) -> bool",
+    "count(what: String, from: int = 0, to: int = 0) -> int",
+    "countn(what: String, from: int = 0, to: int = 0) -> int",
+    "dedent() -> String",
+    "ends_with(text: String) -> bool",
+    "erase(position: int, chars: int = 1) -> String",
+    "filecasecmp_to(to: String) -> int",
+    "filenocasecmp_to(to: String) -> int",
+    "find(what: String, from: int = 0) -> int",
+    "findn(what: String, from: int = 0) -> int",
+    "format(values: Variant, placeholder: String = \"{_}\") -> String",
+    "get_base_dir() -> String",
+    "get_basename() -> String",
+    "get_extension() -> String",
+    "get_file() -> String",
+    "get_slice(delimiter: String, slice: int) -> String",
+    "get_slice_count(delimiter: String) -> int",
+    "get_slicec(delimiter: int, slice: int) -> String",
+    "hash() -> int",
+    "hex_decode() -> PackedByteArray",
+    "hex_to_int() -> int",
+    "static humanize_size(size: int) -> String",
+    "indent(prefix: String) -> String",
+    "insert(position: int, what: String) -> String",
+    "is_absolute_path() -> bool",
+    "is_empty() -> bool",
+    "is_relative_path() -> bool",
+    "is_subsequence_of(text: String) -> bool",
+    "is_subsequence_ofn(text: String) -> bool",
+    "is_valid_filename() -> bool",
+    "is_valid_float() -> bool",
+    "is_valid_hex_number(with_prefix: bool = false) -> bool",
+    "is_valid_html_color() -> bool",
+    "is_valid_identifier() -> bool",
+    "is_valid_int() -> bool",
+    "is_valid_ip_address() -> bool",
+    "join(parts: PackedStringArray) -> String",
+    "json_escape() -> String",
+    "left(length: int) -> String",
+    "length() -> int",
+    "lpad(min_length: int, character: String = \" \") -> String",
+    "lstrip(chars: String) -> String",
+    "match(expr: String) -> bool",
+    "matchn(expr: String) -> bool",
+    "md5_buffer() -> PackedByteArray",
+    "md5_text() -> String",
+    "naturalcasecmp_to(to: String) -> int",
+    "naturalnocasecmp_to(to: String) -> int",
+    "nocasecmp_to(to: String) -> int",
+    "static num(number: float, decimals: int = -1) -> String",
+    "static num_int64(number: int, base: int = 10, capitalize_hex: bool = false) -> String",
+    "static num_scientific(number: float) -> String",
+    "static num_uint64(number: int, base: int = 10, capitalize_hex: bool = false) -> String",
+    "pad_decimals(digits: int) -> String",
+    "pad_zeros(digits: int) -> String",
+    "path_join(file: String) -> String",
+    "repeat(count: int) -> String",
+    "replace(what: String, forwhat: String) -> String",
+    "replacen(what: String, forwhat: String) -> String",
+    "reverse() -> String",
+    "rfind(what: String, from: int = -1) -> int",
+    "rfindn(what: String, from: int = -1) -> int",
+    "right(length: int) -> String",
+    "rpad(min_length: int, character: String = \" \") -> String",
+    "rsplit(delimiter: String = \"\", allow_empty: bool = true, maxsplit: int = 0) -> PackedStringArray",
+    "rstrip(chars: String) -> String",
+    "sha1_buffer() -> PackedByteArray",
+    "sha1_text() -> String",
+    "sha256_buffer() -> PackedByteArray",
+    "sha256_text() -> String",
+    "similarity(text: String) -> float",
+    "simplify_path() -> String",
+    "split(delimiter: String = \"\", allow_empty: bool = true, maxsplit: int = 0) -> PackedStringArray",
+    "split_floats(delimiter: String, allow_empty: bool = true) -> PackedFloat64Array",
+    "strip_edges(left: bool = true, right: bool = true) -> String",
+    "strip_escapes() -> String",
+    "substr(from: int, len: int = -1) -> String",
+    "to_ascii_buffer() -> PackedByteArray",
+    "to_camel_case() -> String",
+    "to_float() -> float",
+    "to_int() -> int",
+    "to_lower() -> String",
+    "to_pascal_case() -> String",
+    "to_snake_case() -> String",
+    "to_upper() -> String",
+    "to_utf8_buffer() -> PackedByteArray",
+    "to_utf16_buffer() -> PackedByteArray",
+    "to_utf32_buffer() -> PackedByteArray",
+    "to_wchar_buffer() -> PackedByteArray",
+    "trim_prefix(prefix: String) -> String",
+    "trim_suffix(suffix: String) -> String",
+    "unicode_at(at: int) -> int",
+    "uri_decode() -> String",
+    "uri_encode() -> String",
+    "validate_filename() -> String",
+    "validate_node_name() -> String",
+    "xml_escape(escape_quotes: bool = false) -> String",
+    "xml_unescape() -> String",
+];
+
+/// Methods of every packed array.
+static PACKED_ARRAY_METHODS: &[&str] = &[
+    "append(value: T) -> bool",
+    "append_array(array: Self) -> void",
+    "bsearch(value: T, before: bool = true) -> int",
+    "clear() -> void",
+    "count(value: T) -> int",
+    "duplicate() -> Self",
+    "erase(value: T) -> bool",
+    "fill(value: T) -> void",
+    "find(value: T, from: int = 0) -> int",
+    "get(index: int) -> T",
+    "has(value: T) -> bool",
+    "insert(at_index: int, value: T) -> int",
+    "is_empty() -> bool",
+    "push_back(value: T) -> bool",
+    "remove_at(index: int) -> void",
+    "resize(new_size: int) -> int",
+    "reverse() -> void",
+    "rfind(value: T, from: int = -1) -> int",
+    "set(index: int, value: T) -> void",
+    "size() -> int",
+    "slice(begin: int, end: int = 2147483647) -> Self",
+    "sort() -> void",
+    "to_byte_array() -> PackedByteArray",
+];
+
+/// Methods only `PackedByteArray` has, for reading and writing binary data.
+static BYTE_ARRAY_METHODS: &[&str] = &[
+    "compress(compression_mode: int = 0) -> PackedByteArray",
+    "decode_double(byte_offset: int) -> float",
+    "decode_float(byte_offset: int) -> float",
+    "decode_half(byte_offset: int) -> float",
+    "decode_s8(byte_offset: int) -> int",
+    "decode_s16(byte_offset: int) -> int",
+    "decode_s32(byte_offset: int) -> int",
+    "decode_s64(byte_offset: int) -> int",
+    "decode_u8(byte_offset: int) -> int",
+    "decode_u16(byte_offset: int) -> int",
+    "decode_u32(byte_offset: int) -> int",
+    "decode_u64(byte_offset: int) -> int",
+    "decode_var(byte_offset: int, allow_objects: bool = false) -> Variant",
+    "decode_var_size(byte_offset: int, allow_objects: bool = false) -> int",
+    "decompress(buffer_size: int, compression_mode: int = 0) -> PackedByteArray",
+    "decompress_dynamic(max_output_size: int, compression_mode: int = 0) -> PackedByteArray",
+    "encode_double(byte_offset: int, value: float) -> void",
+    "encode_float(byte_offset: int, value: float) -> void",
+    "encode_half(byte_offset: int, value: float) -> void",
+    "encode_s8(byte_offset: int, value: int) -> void",
+    "encode_s16(byte_offset: int, value: int) -> void",
+    "encode_s32(byte_offset: int, value: int) -> void",
+    "encode_s64(byte_offset: int, value: int) -> void",
+    "encode_u8(byte_offset: int, value: int) -> void",
+    "encode_u16(byte_offset: int, value: int) -> void",
+    "encode_u32(byte_offset: int, value: int) -> void",
+    "encode_u64(byte_offset: int, value: int) -> void",
+    "encode_var(byte_offset: int, value: Variant, allow_objects: bool = false) -> int",
+    "get_string_from_ascii() -> String",
+    "get_string_from_utf8() -> String",
+    "get_string_from_utf16() -> String",
+    "get_string_from_utf32() -> String",
+    "get_string_from_wchar() -> String",
+    "has_encoded_var(byte_offset: int, allow_objects: bool = false) -> bool",
+    "hex_encode() -> String",
+    "to_float32_array() -> PackedFloat32Array",
+    "to_float64_array() -> PackedFloat64Array",
+    "to_int32_array() -> PackedInt32Array",
+    "to_int64_array() -> PackedInt64Array",
+];
+
+#[cfg(test)]
+mod test {
+    use crate::{ast::{BinOpKind, UnaryOpKind}, thir::ty::TyKind};
+
+    use super::{bin_op, split_params, ty, unary_op, Sig, BIN_OPS, TYS, UNARY_OPS};
+
+    #[test]
+    fn operators() {
+        assert_eq!(bin_op(BinOpKind::Mul, "int", "Vector2"), Some("Vector2"));
+        assert_eq!(bin_op(BinOpKind::Mul, "Vector2i", "float"), Some("Vector2"));
+        assert_eq!(bin_op(BinOpKind::Div, "Vector3i", "int"), Some("Vector3i"));
+        assert_eq!(bin_op(BinOpKind::Mul, "Transform2D", "Vector2"), Some("Vector2"));
+        assert_eq!(bin_op(BinOpKind::Mul, "Vector3", "Basis"), Some("Vector3"));
+        assert_eq!(bin_op(BinOpKind::Mod, "String", "Array"), Some("String"));
+        assert_eq!(bin_op(BinOpKind::Add, "StringName", "String"), Some("String"));
+        assert_eq!(bin_op(BinOpKind::Eq, "Plane", "Plane"), Some("bool"));
+        assert_eq!(bin_op(BinOpKind::Mod, "float", "float"), None);
+        assert_eq!(bin_op(BinOpKind::Add, "Vector2", "Vector2i"), None);
+        assert_eq!(bin_op(BinOpKind::Mul, "Vector2", "Transform3D"), None);
+        assert_eq!(unary_op(UnaryOpKind::Neg, "Color"), Some("Color"));
+        assert_eq!(unary_op(UnaryOpKind::BitNot, "Vector2i"), None);
+    }
+
+    #[test]
+    fn lookups() {
+        let vector2 = ty("Vector2").unwrap();
+        assert_eq!(vector2.member("y"), Some("float"));
+        assert_eq!(vector2.constant("UP"), Some("Vector2"));
+        assert_eq!(vector2.constant("AXIS_Y"), Some("int"));
+        assert_eq!(vector2.member("z"), None);
+        assert_eq!(
+            vector2.method("lerp"),
+            Some(Sig { is_static: false, params: vec!["Vector2", "float"], required: 2, vararg: false, ret: "Vector2" }),
+        );
+        assert_eq!(vector2.method("from_angle").map(|sig| sig.is_static), Some(true));
+        assert_eq!(vector2.constructors().map(|sig| sig.params).collect::<Vec<_>>(), [
+            vec![],
+            vec!["Vector2"],
+            vec!["Vector2i"],
+            vec!["float", "float"],
+        ]);
+
+        let split = ty("StringName").unwrap().method("split").unwrap();
+        assert_eq!((split.params.len(), split.required, split.ret), (3, 0, "PackedStringArray"));
+        let call = ty("Callable").unwrap().method("call").unwrap();
+        assert_eq!((call.params.len(), call.vararg), (0, true));
+        let get = ty("PackedColorArray").unwrap().method("get").unwrap();
+        assert_eq!((get.params, get.ret), (vec!["int"], "Color"));
+        assert_eq!(split_params(r#"a: String = ", ", b: Color = Color(0, 0, 0)"#), [r#"a: String = ", ""#, "b: Color = Color(0, 0, 0)"]);
+    }
+
+    /// Every type the table names is one `TyKind` knows, except engine classes.
+    #[test]
+    fn names_known_types() {
+        let known = |name: &str| TyKind::builtin(name).is_some() || name == "Object";
+        for builtin in TYS {
+            assert!(known(builtin.name), "{}", builtin.name);
+            let entries = builtin.members.iter().chain(builtin.constants);
+            for ty in entries.map(|entry| entry.split_once(": ").unwrap().1).chain(builtin.index) {
+                assert!(known(ty), "{ty} in {}", builtin.name);
+            }
+            let methods = builtin.methods.iter().flat_map(|methods| methods.iter());
+            for sig in builtin.constructors.iter().chain(methods) {
+                let (_, sig) = builtin.sig(sig);
+                for ty in sig.params.iter().chain([&sig.ret]) {
+                    assert!(known(ty), "{ty} in {}", builtin.name);
+                }
+            }
+        }
+        for &(lhs, _, rhs, ret) in BIN_OPS {
+            assert!(known(lhs) && (known(rhs) || rhs == "*") && known(ret), "{lhs} {rhs}");
+        }
+        for &(_, ty, ret) in UNARY_OPS {
+            assert!(known(ty) && known(ret), "{ty}");
+        }
+    }
+}
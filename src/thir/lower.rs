@@ -0,0 +1,1048 @@
+use std::collections::HashMap;
+
+use internment::Arena;
+
+use crate::{
+    ast::{self, AccessorKind, BinOpKind, Elif, Ident, IdentDef, LitKind, PatternKind, StmtList, UnaryOpKind, VarDef},
+    context::Ctx,
+    ident::IdentName,
+    lexer::Span,
+    resolve::{Binding, Resolution},
+};
+
+use super::{
+    builtins,
+    consts::ConstVal,
+    ty::{self, IntKind, Ty, TyKind},
+    Block, BuiltinFunc, Capture, Class, Expr, ExprKind, Func, FuncId, FuncKind, Global, GlobalId, Lambda, Local,
+    LocalId, MatchBranch, Place, Stmt, StmtKind,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LowerError {
+    pub span: Span,
+    pub kind: LowerErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LowerErrorKind {
+    /// Something code generation can't represent yet, such as arrays, described for the diagnostic.
+    Unsupported(&'static str),
+}
+
+/// Lowers a script that was checked without errors, using the bindings `resolution` holds for it.
+/// Fails with an error for each construct that code generation doesn't support yet.
+pub fn lower<'a>(
+    ctx: &'a Ctx,
+    ast: &'a ast::Class<'a>,
+    resolution: &Resolution<'a>,
+    tys: &'a Arena<TyKind<'a>>,
+) -> Result<&'a Class<'a>, Vec<LowerError>> {
+    // Constant errors were reported by the checks that ran before lowering.
+    let (class, _) = ty::Class::new(ast, None, tys);
+    let mut lowerer = Lowerer {
+        ctx,
+        tys,
+        resolution,
+        class: tys.intern(TyKind::Class(class)).into(),
+        globals: Vec::new(),
+        global_ids: HashMap::new(),
+        field_vals: HashMap::new(),
+        funcs: Vec::new(),
+        func_ids: HashMap::new(),
+        accessors: HashMap::new(),
+        accessor_funcs: HashMap::new(),
+        frames: Vec::new(),
+        default_frames: None,
+        property: None,
+        errors: Vec::new(),
+    };
+
+    // Functions are numbered first, so that calls can refer to functions declared further down.
+    for stmt in ast.stmt_list.stmts {
+        if let ast::StmtKind::FuncDef(func_def) = stmt.kind {
+            let params = func_def.param_list.params;
+            let param_tys = params.iter().map(|param| lowerer.param_ty(param)).collect();
+            let ret_ty = lowerer.ret_ty(func_def.result_ty);
+            let id = lowerer.declare_func(param_tys, ret_ty);
+            lowerer.funcs[id.0 as usize].defaults = params.iter().map(|param| param.val).collect();
+            lowerer.func_ids.insert(func_def.name.span, id);
+        }
+    }
+    // The script has a single instance, so its member variables are stored as globals, like static ones.
+    for stmt in ast.stmt_list.stmts {
+        if let ast::StmtKind::VarDef(var_def) = stmt.kind {
+            lowerer.global(var_def);
+        }
+    }
+
+    let mut bodies = Vec::new();
+    for stmt in ast.stmt_list.stmts {
+        match stmt.kind {
+            ast::StmtKind::FuncDef(func_def) => {
+                let id = lowerer.func_ids[&func_def.name.span];
+                // A function named by `get = f` or `set = f` accesses the property directly.
+                lowerer.property = lowerer.accessor_funcs.get(&id).copied();
+                let kind = if func_def.is_static { FuncKind::Static } else { FuncKind::Method };
+                let decl = &lowerer.funcs[id.0 as usize];
+                let params: Vec<_> = func_def.param_list.params.iter().map(|param| param.name).zip(decl.param_tys.clone()).collect();
+                let ret_ty = decl.ret_ty;
+                let func = lowerer.func(func_def.span, func_def.name.name, kind, ret_ty, params, func_def.body);
+                bodies.push((id, func));
+            }
+            ast::StmtKind::VarDef(var_def) => {
+                lowerer.property = Some(var_def.def.name.span);
+                let ty = lowerer.global_ids.get(&var_def.def.name.span).map(|id| lowerer.globals[id.0 as usize].ty);
+                let accessors = lowerer.accessors.get(&var_def.def.name.span).copied().unwrap_or_default();
+                for (accessor, id, kind) in [
+                    (var_def.getter, accessors.getter, FuncKind::Getter),
+                    (var_def.setter, accessors.setter, FuncKind::Setter),
+                ] {
+                    if let (Some(accessor), Some(id)) = (accessor, id) {
+                        if let AccessorKind::Body { param, body } = accessor.kind {
+                            let params = param.zip(ty);
+                            let ret_ty = lowerer.funcs[id.0 as usize].ret_ty;
+                            let func = lowerer.func(accessor.span, var_def.def.name.name, kind, ret_ty, params, body);
+                            bodies.push((id, func));
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+        lowerer.property = None;
+    }
+    for (id, func) in bodies {
+        lowerer.funcs[id.0 as usize].func = Some(ctx.alloc(func));
+    }
+
+    let int = lowerer.ty(TyKind::Int(IntKind::I64));
+    let main = lowerer.func(ast.span, ctx.new_ident_name("main"), FuncKind::Main, int, None, ast.stmt_list);
+    if !lowerer.errors.is_empty() {
+        return Err(lowerer.errors);
+    }
+    let funcs: Vec<_> = lowerer.funcs.iter().map(|decl| decl.func.expect("every declared function is lowered")).collect();
+    Ok(ctx.alloc(Class {
+        span: ast.span,
+        ty: lowerer.class,
+        globals: ctx.alloc_slice_copy(&lowerer.globals),
+        funcs: ctx.alloc_slice_copy(&funcs),
+        main: ctx.alloc(main),
+    }))
+}
+
+struct Lowerer<'a, 'r> {
+    ctx: &'a Ctx,
+    tys: &'a Arena<TyKind<'a>>,
+    resolution: &'r Resolution<'a>,
+    /// A `TyKind::Class` with the evaluated constants.
+    class: Ty<'a>,
+    globals: Vec<Global<'a>>,
+    /// By the span of the variable's name.
+    global_ids: HashMap<Span, GlobalId>,
+    /// The initial values of member variables, which `main` assigns where they're declared.
+    /// By the span of the variable's name.
+    field_vals: HashMap<Span, &'a Expr<'a>>,
+    /// Indexed by `FuncId`.
+    funcs: Vec<FuncDecl<'a>>,
+    /// By the span of the function's name.
+    func_ids: HashMap<Span, FuncId>,
+    /// By the span of the property's name.
+    accessors: HashMap<Span, Accessors>,
+    /// The properties of the functions named by `get = f` or `set = f`.
+    accessor_funcs: HashMap<FuncId, Span>,
+    /// The function being lowered, followed by the lambdas being lowered inside it.
+    frames: Vec<Frame<'a>>,
+    /// While a default argument is lowered at a call, the number of frames of the caller,
+    /// whose locals the default can't use.
+    default_frames: Option<usize>,
+    /// The property whose accessor is being lowered, which reads and writes its value directly.
+    property: Option<Span>,
+    errors: Vec<LowerError>,
+}
+
+/// The signature of a function, known before its body is lowered.
+struct FuncDecl<'a> {
+    param_tys: Vec<Ty<'a>>,
+    /// The default values of the parameters, which calls leaving them out evaluate.
+    defaults: Vec<Option<&'a ast::Expr<'a>>>,
+    ret_ty: Ty<'a>,
+    func: Option<&'a Func<'a>>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Accessors {
+    getter: Option<FuncId>,
+    setter: Option<FuncId>,
+}
+
+struct Frame<'a> {
+    /// What `return` converts its value to.
+    ret_ty: Ty<'a>,
+    /// Lambdas return a `Variant` after converting the value to `ret_ty`,
+    /// as calls through a `Callable` don't know the type.
+    is_lambda: bool,
+    locals: Vec<Local<'a>>,
+    /// By the span of the identifier declaring the local.
+    local_ids: HashMap<Span, LocalId>,
+    captures: Vec<Capture>,
+}
+
+impl<'a> Lowerer<'a, '_> {
+    fn error(&mut self, span: Span, kind: LowerErrorKind) {
+        self.errors.push(LowerError { span, kind });
+    }
+
+    /// Reports a construct code generation doesn't support, and stands in for it with `null`.
+    fn unsupported(&mut self, span: Span, what: &'static str) -> &'a Expr<'a> {
+        self.error(span, LowerErrorKind::Unsupported(what));
+        self.ctx.alloc(Expr { span, ty: self.variant(), kind: ExprKind::Nil })
+    }
+
+    fn ty(&self, kind: TyKind<'a>) -> Ty<'a> {
+        self.tys.intern(kind).into()
+    }
+
+    /// Resolves a type annotation. Types that code generation can't represent yet are reported,
+    /// and stand in as `Variant`s.
+    fn resolve_ty(&mut self, ty: Option<&ast::Expr>) -> Option<Ty<'a>> {
+        let ty_expr = ty?;
+        let ty = ty::resolve(ty_expr, self.tys)?;
+        if !has_c_type(&ty) {
+            self.error(ty_expr.span, LowerErrorKind::Unsupported(
+                "values of types other than `int`, `float`, `bool`, `String`, `Callable` and `Variant`",
+            ));
+            return Some(self.variant());
+        }
+        Some(ty)
+    }
+
+    fn variant(&self) -> Ty<'a> {
+        self.ty(TyKind::Variant)
+    }
+
+    fn int(&self, val: i64, span: Span) -> &'a Expr<'a> {
+        self.ctx.alloc(Expr { span, ty: self.ty(TyKind::Int(IntKind::I64)), kind: ExprKind::Int(val) })
+    }
+
+    fn ret_ty(&mut self, result_ty: Option<&ast::Expr>) -> Ty<'a> {
+        self.resolve_ty(result_ty).unwrap_or_else(|| self.variant())
+    }
+
+    /// Untyped parameters are `Variant`s, even when their default value has a type.
+    fn param_ty(&mut self, param: &IdentDef) -> Ty<'a> {
+        self.resolve_ty(param.ty).unwrap_or_else(|| self.variant())
+    }
+
+    /// The type of a variable. Without `:` or `:=`, it's a `Variant` whatever its initial value.
+    fn var_ty(&mut self, def: &IdentDef, val: Option<&Expr<'a>>) -> Ty<'a> {
+        let inferred = val.filter(|_| def.strict_type).map(|val| val.ty);
+        self.resolve_ty(def.ty).or(inferred).unwrap_or_else(|| self.variant())
+    }
+
+    fn declare_func(&mut self, param_tys: Vec<Ty<'a>>, ret_ty: Ty<'a>) -> FuncId {
+        self.funcs.push(FuncDecl { param_tys, defaults: Vec::new(), ret_ty, func: None });
+        FuncId(self.funcs.len() as u32 - 1)
+    }
+
+    fn new_frame(&self, ret_ty: Ty<'a>, is_lambda: bool) -> Frame<'a> {
+        Frame { ret_ty, is_lambda, locals: Vec::new(), local_ids: HashMap::new(), captures: Vec::new() }
+    }
+
+    /// Converts a value to `ty`, going through a runtime check when it's a `Variant`.
+    fn coerce(&self, expr: &'a Expr<'a>, ty: Ty<'a>) -> &'a Expr<'a> {
+        let kind = match (&*expr.ty, &*ty) {
+            (from, to) if from == to => return expr,
+            (TyKind::Variant, _) => ExprKind::FromVariant(expr),
+            (_, TyKind::Variant) => ExprKind::ToVariant(expr),
+            // C converts between `int` and `float` itself.
+            (from, to) if from.is_numeric() && to.is_numeric() => return expr,
+            // Anything else, such as a `String` returned from `main`, fails the runtime check.
+            _ => ExprKind::FromVariant(self.coerce(expr, self.variant())),
+        };
+        self.ctx.alloc(Expr { span: expr.span, ty, kind })
+    }
+
+    fn binding(&self, ident: &Ident) -> Binding<'a> {
+        self.resolution.get(ident).expect("identifiers are resolved before lowering")
+    }
+
+    /// Declares the global behind a member variable, along with its accessors.
+    fn global(&mut self, var_def: &'a VarDef<'a>) {
+        let span = var_def.def.name.span;
+        let val = var_def.def.val.map(|val| self.expr(val));
+        let ty = self.var_ty(var_def.def, val);
+        let val = val.map(|val| self.coerce(val, ty));
+        self.global_ids.insert(span, GlobalId(self.globals.len() as u32));
+        let val = match val {
+            Some(val) if !var_def.is_static => {
+                self.field_vals.insert(span, val);
+                None
+            }
+            val => val,
+        };
+        self.globals.push(Global { span: var_def.span, name: var_def.def.name.name, ty, val });
+
+        let getter = self.accessor(span, var_def.getter, Vec::new(), ty);
+        let setter = self.accessor(span, var_def.setter, vec![ty], self.ty(TyKind::Void));
+        self.accessors.insert(span, Accessors { getter, setter });
+    }
+
+    fn accessor(
+        &mut self,
+        property: Span,
+        accessor: Option<&'a ast::Accessor<'a>>,
+        param_tys: Vec<Ty<'a>>,
+        ret_ty: Ty<'a>,
+    ) -> Option<FuncId> {
+        Some(match accessor?.kind {
+            AccessorKind::Func(name) => {
+                let Binding::Func(func_def) = self.binding(name) else {
+                    self.error(name.span, LowerErrorKind::Unsupported("accessors that aren't functions of the script"));
+                    return None;
+                };
+                let id = self.func_ids[&func_def.name.span];
+                self.accessor_funcs.insert(id, property);
+                id
+            }
+            AccessorKind::Body { .. } => self.declare_func(param_tys, ret_ty),
+        })
+    }
+
+    /// The accessors to go through to use a property, which are bypassed inside its own accessors.
+    fn accessors(&self, var_def: &VarDef) -> Accessors {
+        let span = var_def.def.name.span;
+        if self.property == Some(span) {
+            return Accessors::default();
+        }
+        self.accessors.get(&span).copied().unwrap_or_default()
+    }
+
+    fn func(
+        &mut self,
+        span: Span,
+        name: IdentName<'a>,
+        kind: FuncKind,
+        ret_ty: Ty<'a>,
+        params: impl IntoIterator<Item = (&'a Ident<'a>, Ty<'a>)>,
+        body: &'a StmtList<'a>,
+    ) -> Func<'a> {
+        self.frames.push(self.new_frame(ret_ty, false));
+        let params = self.params(params);
+        let body = self.block(body, kind == FuncKind::Main);
+        let frame = self.frames.pop().unwrap();
+        Func {
+            span,
+            name,
+            kind,
+            params: self.ctx.alloc_slice_copy(&params),
+            ret_ty,
+            locals: self.ctx.alloc_slice_copy(&frame.locals),
+            body,
+        }
+    }
+
+    /// Adds a local to the current function, such as one holding a temporary value.
+    fn new_local(&mut self, name: IdentName<'a>, ty: Ty<'a>) -> LocalId {
+        let frame = self.frames.last_mut().unwrap();
+        frame.locals.push(Local { name, ty });
+        LocalId(frame.locals.len() as u32 - 1)
+    }
+
+    fn declare_local(&mut self, ident: &'a Ident<'a>, ty: Ty<'a>) -> LocalId {
+        let id = self.new_local(ident.name, ty);
+        self.frames.last_mut().unwrap().local_ids.insert(ident.span, id);
+        id
+    }
+
+    /// Finds the local declared at `def`. A local of an enclosing function is
+    /// captured by each lambda between its declaration and this use.
+    /// Returns `None` for a parameter used by a default argument, which is lowered at the call.
+    fn local(&mut self, def: Span) -> Option<(LocalId, Ty<'a>)> {
+        let depth = self.frames.iter().rposition(|frame| frame.local_ids.contains_key(&def))
+            .filter(|&depth| self.default_frames.is_none_or(|frames| depth >= frames))?;
+        let mut id = self.frames[depth].local_ids[&def];
+        for i in depth + 1..self.frames.len() {
+            let local = self.frames[i - 1].locals[id.0 as usize];
+            let frame = &mut self.frames[i];
+            let inner = LocalId(frame.locals.len() as u32);
+            frame.locals.push(local);
+            frame.local_ids.insert(def, inner);
+            frame.captures.push(Capture { outer: id, inner });
+            id = inner;
+        }
+        let frame = self.frames.last().unwrap();
+        Some((id, frame.locals[id.0 as usize].ty))
+    }
+
+    fn block(&mut self, stmt_list: &'a StmtList<'a>, class_level: bool) -> &'a Block<'a> {
+        let stmts: Vec<_> = stmt_list.stmts.iter().filter_map(|stmt| self.stmt(stmt, class_level)).collect();
+        self.ctx.alloc(Block { span: stmt_list.span, stmts: self.ctx.alloc_slice_copy(&stmts) })
+    }
+
+    /// Lowers a statement, or returns `None` for declarations that aren't run and for unsupported statements.
+    /// At class level, variables are members, whose initial value is stored where they're declared.
+    fn stmt(&mut self, stmt: &'a ast::Stmt<'a>, class_level: bool) -> Option<&'a Stmt<'a>> {
+        let kind = match stmt.kind {
+            ast::StmtKind::Expr(expr) => StmtKind::Expr(self.expr(expr)),
+            ast::StmtKind::VarDef(var_def) if class_level => {
+                let span = var_def.def.name.span;
+                let val = *self.field_vals.get(&span)?;
+                StmtKind::Assign { place: Place::Global(self.global_ids[&span]), op: None, val }
+            }
+            ast::StmtKind::VarDef(var_def) => self.var(var_def.def, false),
+            ast::StmtKind::ConstDef(const_def) if !class_level => self.var(const_def.def, true),
+            ast::StmtKind::Assign(assign) => self.assign(assign)?,
+            ast::StmtKind::If(if_) => self.if_(if_.cond, if_.body, if_.elifs, if_.else_),
+            ast::StmtKind::While(while_) => StmtKind::While {
+                cond: self.expr(while_.cond),
+                body: self.block(while_.body, false),
+            },
+            ast::StmtKind::For(for_) => self.for_(for_)?,
+            ast::StmtKind::Match(match_) => self.match_(match_),
+            ast::StmtKind::Break => StmtKind::Break,
+            ast::StmtKind::Continue => StmtKind::Continue,
+            ast::StmtKind::Return(val) => StmtKind::Return(val.map(|val| self.return_val(val))),
+            ast::StmtKind::Pass | ast::StmtKind::FuncDef(_) | ast::StmtKind::ConstDef(_) | ast::StmtKind::EnumDef(_)
+                | ast::StmtKind::SignalDef(_) | ast::StmtKind::Class(_) | ast::StmtKind::Annotations(_) => return None,
+            ast::StmtKind::Error => unreachable!("scripts with syntax errors aren't lowered"),
+        };
+        Some(self.ctx.alloc(Stmt { span: stmt.span, kind }))
+    }
+
+    /// Declares a local. Constants always have the type of their value.
+    fn var(&mut self, def: &'a IdentDef<'a>, is_const: bool) -> StmtKind<'a> {
+        let val = def.val.map(|val| self.expr(val));
+        let ty = match val {
+            Some(val) if is_const && def.ty.is_none() => val.ty,
+            _ => self.var_ty(def, val),
+        };
+        let val = val.map(|val| self.coerce(val, ty));
+        let local = self.declare_local(def.name, ty);
+        StmtKind::Var { local, val }
+    }
+
+    fn return_val(&mut self, val: &'a ast::Expr<'a>) -> &'a Expr<'a> {
+        let frame = self.frames.last().unwrap();
+        let (ret_ty, is_lambda) = (frame.ret_ty, frame.is_lambda);
+        let val = self.expr(val);
+        let val = self.coerce(val, ret_ty);
+        if is_lambda {
+            return self.coerce(val, self.variant());
+        }
+        val
+    }
+
+    fn if_(
+        &mut self,
+        cond: &'a ast::Expr<'a>,
+        then: &'a StmtList<'a>,
+        elifs: &'a [&'a Elif<'a>],
+        else_: Option<&'a StmtList<'a>>,
+    ) -> StmtKind<'a> {
+        let cond = self.expr(cond);
+        let then = self.block(then, false);
+        let else_ = match elifs.split_first() {
+            Some((elif, elifs)) => {
+                let kind = self.if_(elif.cond, elif.body, elifs, else_);
+                let stmt: &Stmt = self.ctx.alloc(Stmt { span: elif.span, kind });
+                let block: &Block = self.ctx.alloc(Block { span: elif.span, stmts: self.ctx.alloc_slice_copy(&[stmt]) });
+                Some(block)
+            }
+            None => else_.map(|else_| self.block(else_, false)),
+        };
+        StmtKind::If { cond, then, else_ }
+    }
+
+    /// Lowers a loop over `range(...)` or over an integer, counting from 0. Other collections aren't supported.
+    fn for_(&mut self, for_: &'a ast::For<'a>) -> Option<StmtKind<'a>> {
+        let int = self.ty(TyKind::Int(IntKind::I64));
+        let span = for_.iter.span;
+        let range_args = match for_.iter.kind {
+            ast::ExprKind::Call(call) => match call.callee.kind {
+                ast::ExprKind::Ident(ident) if matches!(self.binding(ident), Binding::Builtin(name) if name.as_str() == "range") => {
+                    Some(call.arg_list.args)
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        let (start, end, step) = match range_args {
+            Some(args) => {
+                let args: Vec<_> = args.iter().map(|arg| {
+                    let arg = self.expr(arg);
+                    self.coerce(arg, int)
+                }).collect();
+                match args[..] {
+                    [end] => (self.int(0, span), end, self.int(1, span)),
+                    [start, end] => (start, end, self.int(1, span)),
+                    [start, end, step] => (start, end, step),
+                    _ => {
+                        self.error(span, LowerErrorKind::Unsupported("`range` with no arguments or more than three"));
+                        return None;
+                    }
+                }
+            }
+            None => {
+                let iter = self.expr(for_.iter);
+                if !matches!(*iter.ty, TyKind::Int(_) | TyKind::Float | TyKind::Variant) {
+                    self.error(span, LowerErrorKind::Unsupported("loops over anything but ranges and numbers"));
+                    return None;
+                }
+                (self.int(0, span), self.coerce(iter, int), self.int(1, span))
+            }
+        };
+        let ty = self.resolve_ty(for_.var.ty).unwrap_or_else(|| self.variant());
+        if !ty.is_numeric() && *ty != TyKind::Variant {
+            self.error(for_.var.span, LowerErrorKind::Unsupported("loop variables of types other than numbers"));
+            return None;
+        }
+        let var = self.declare_local(for_.var.name, ty);
+        let body = self.block(for_.body, false);
+        Some(StmtKind::For { var, start, end, step, body })
+    }
+
+    /// Lowers `match` to a test of the patterns of each branch against a local holding the value.
+    fn match_(&mut self, match_: &'a ast::Match<'a>) -> StmtKind<'a> {
+        let val = self.expr(match_.expr);
+        let local = self.new_local(self.ctx.new_ident_name("match"), val.ty);
+        let subject: &Expr = self.ctx.alloc(Expr { span: match_.expr.span, ty: val.ty, kind: ExprKind::Local(local) });
+        let mut branches = Vec::new();
+        for branch in match_.branches {
+            let mut tests = Vec::new();
+            let mut binds = Vec::new();
+            let mut matches_all = false;
+            for pattern in branch.patterns {
+                match pattern.kind {
+                    PatternKind::Expr(expr) => {
+                        let expr = self.expr(expr);
+                        // Values of unrelated types never match, which comparing `Variant`s handles.
+                        let (lhs, rhs) = if expr.ty == subject.ty || (expr.ty.is_numeric() && subject.ty.is_numeric()) {
+                            (subject, expr)
+                        } else {
+                            (self.coerce(subject, self.variant()), self.coerce(expr, self.variant()))
+                        };
+                        tests.push(self.bin_op(pattern.span, BinOpKind::Eq, lhs, rhs));
+                    }
+                    PatternKind::Bind(name) => {
+                        matches_all = true;
+                        let val = self.coerce(subject, self.variant());
+                        let bind = self.declare_local(name, self.variant());
+                        binds.push(self.ctx.alloc(Stmt { span: pattern.span, kind: StmtKind::Var { local: bind, val: Some(val) } }));
+                    }
+                    PatternKind::Wildcard => matches_all = true,
+                    PatternKind::Array { .. } | PatternKind::Dict { .. } => {
+                        self.error(pattern.span, LowerErrorKind::Unsupported("array and dictionary patterns"));
+                    }
+                }
+            }
+            let cond = if matches_all {
+                None
+            } else {
+                let cond = tests.into_iter().reduce(|lhs, rhs| self.bin_op(branch.span, BinOpKind::Or, lhs, rhs));
+                // Only patterns that aren't supported, which were reported.
+                Some(cond.unwrap_or_else(|| self.ctx.alloc(Expr { span: branch.span, ty: self.ty(TyKind::Bool), kind: ExprKind::Bool(false) })))
+            };
+            let guard = branch.guard.map(|guard| self.expr(guard));
+            let body = self.block(branch.body, false);
+            branches.push(MatchBranch { span: branch.span, cond, binds: self.ctx.alloc_slice_copy(&binds), guard, body });
+        }
+        StmtKind::Match { local, val, branches: self.ctx.alloc_slice_copy(&branches) }
+    }
+
+    fn assign(&mut self, assign: &'a ast::Assign<'a>) -> Option<StmtKind<'a>> {
+        let var_def = match assign.target.kind {
+            ast::ExprKind::Ident(ident) => match self.binding(ident) {
+                Binding::Local(def) | Binding::Param(def) => {
+                    let Some((id, _)) = self.local(def.span) else {
+                        self.error(assign.target.span, LowerErrorKind::Unsupported("default values that use other parameters"));
+                        return None;
+                    };
+                    return Some(self.store(assign, Place::Local(id)));
+                }
+                Binding::Field(var_def) => Some(var_def),
+                _ => None,
+            },
+            ast::ExprKind::Attr(attr) => self.self_field(attr),
+            _ => None,
+        };
+        let Some(var_def) = var_def else {
+            self.error(assign.target.span, LowerErrorKind::Unsupported("assigning to subscripts and attributes of values"));
+            return None;
+        };
+        // Outside its accessors, a property is written through its setter.
+        if let Some(setter) = self.accessors(var_def).setter {
+            let mut val = self.expr(assign.val);
+            if let Some(op) = assign.op {
+                let lhs = self.expr(assign.target);
+                val = self.bin_op(assign.span, op, lhs, val);
+            }
+            return Some(StmtKind::Expr(self.call(assign.span, setter, vec![val])));
+        }
+        Some(self.store(assign, Place::Global(self.global_ids[&var_def.def.name.span])))
+    }
+
+    /// Lowers an assignment to a local or global.
+    fn store(&mut self, assign: &'a ast::Assign<'a>, place: Place) -> StmtKind<'a> {
+        let ty = match place {
+            Place::Local(id) => self.frames.last().unwrap().locals[id.0 as usize].ty,
+            Place::Global(id) => self.globals[id.0 as usize].ty,
+        };
+        let val = self.expr(assign.val);
+        match assign.op {
+            // Only float arithmetic maps onto C's compound assignments. The runtime combines everything
+            // else, so the operation is spelled out.
+            Some(op) if *ty != TyKind::Float || !val.ty.is_numeric() || op == BinOpKind::Pow => {
+                let kind = match place {
+                    Place::Local(id) => ExprKind::Local(id),
+                    Place::Global(id) => ExprKind::Global(id),
+                };
+                let lhs = self.ctx.alloc(Expr { span: assign.target.span, ty, kind });
+                let val = self.bin_op(assign.span, op, lhs, val);
+                StmtKind::Assign { place, op: None, val: self.coerce(val, ty) }
+            }
+            op => StmtKind::Assign { place, op, val: self.coerce(val, ty) },
+        }
+    }
+
+    /// The member variable `self.name` refers to.
+    fn self_field(&self, attr: &ast::Attr<'a>) -> Option<&'a VarDef<'a>> {
+        let ast::ExprKind::Self_ = attr.expr.kind else { return None };
+        let TyKind::Class(class) = &*self.class else { unreachable!() };
+        class.field(attr.name.name).map(|field| field.ast)
+    }
+
+    /// Calls a function of the class, converting the arguments to the types of the parameters.
+    /// Arguments left out take their default value, evaluated at the call.
+    fn call(&mut self, span: Span, func: FuncId, mut args: Vec<&'a Expr<'a>>) -> &'a Expr<'a> {
+        let defaults: Vec<_> = self.funcs[func.0 as usize].defaults.iter().skip(args.len()).map_while(|default| *default).collect();
+        let default_frames = self.default_frames.replace(self.frames.len());
+        args.extend(defaults.into_iter().map(|default| self.expr(default)));
+        self.default_frames = default_frames;
+
+        let decl = &self.funcs[func.0 as usize];
+        let args: Vec<_> = args.into_iter().zip(&decl.param_tys).map(|(arg, &ty)| self.coerce(arg, ty)).collect();
+        let kind = ExprKind::Call { func, args: self.ctx.alloc_slice_copy(&args) };
+        self.ctx.alloc(Expr { span, ty: decl.ret_ty, kind })
+    }
+
+    /// Types an operation. One on a `Variant` takes both operands as `Variant`s and
+    /// is carried out at runtime, except for `and` and `or`, which only test truthiness.
+    fn bin_op(&mut self, span: Span, kind: BinOpKind, mut lhs: &'a Expr<'a>, mut rhs: &'a Expr<'a>) -> &'a Expr<'a> {
+        use BinOpKind::*;
+
+        let ty = match kind {
+            In | NotIn => {
+                lhs = self.coerce(lhs, self.variant());
+                rhs = self.coerce(rhs, self.variant());
+                TyKind::Bool
+            }
+            And | Or | Is | IsNot => TyKind::Bool,
+            _ if *lhs.ty == TyKind::Variant || *rhs.ty == TyKind::Variant => {
+                lhs = self.coerce(lhs, self.variant());
+                rhs = self.coerce(rhs, self.variant());
+                TyKind::Variant
+            }
+            // Strings are only concatenated and compared.
+            _ if (*lhs.ty == TyKind::String || *rhs.ty == TyKind::String) && !matches!(kind, Add | Eq | Ne | Lt | Le | Gt | Ge) => {
+                return self.unsupported(span, "this operator on strings");
+            }
+            Eq | Ne | Lt | Le | Gt | Ge => TyKind::Bool,
+            _ => lhs.ty.builtin_name().zip(rhs.ty.builtin_name())
+                .and_then(|(lhs, rhs)| builtins::bin_op(kind, lhs, rhs))
+                .and_then(TyKind::builtin)
+                .unwrap_or(TyKind::Variant),
+        };
+        self.ctx.alloc(Expr { span, ty: self.ty(ty), kind: ExprKind::BinOp { kind, lhs, rhs } })
+    }
+
+    fn expr(&mut self, expr: &'a ast::Expr<'a>) -> &'a Expr<'a> {
+        let span = expr.span;
+        let (kind, ty) = match expr.kind {
+            ast::ExprKind::Lit(lit) => match lit.kind {
+                LitKind::Int(val) => {
+                    let val = i64::try_from(val).expect("the lexer rejects integers out of range");
+                    (ExprKind::Int(val), self.ty(TyKind::Int(IntKind::I64)))
+                }
+                LitKind::Float(val) => (ExprKind::Float(val.0), self.ty(TyKind::Float)),
+                LitKind::Str(val) => (ExprKind::Str(val), self.ty(TyKind::String)),
+                LitKind::StringName(_) | LitKind::NodePath(_) => return self.unsupported(span, "`StringName` and `NodePath` values"),
+            },
+            ast::ExprKind::Ident(ident) => return self.ident(span, ident),
+            ast::ExprKind::BinOp(op) if op.kind == BinOpKind::As => {
+                let val = self.expr(op.lhs);
+                let Some(ty) = self.resolve_ty(Some(op.rhs)) else {
+                    return self.unsupported(op.rhs.span, "casts to types other than builtin ones");
+                };
+                return self.coerce(val, ty);
+            }
+            ast::ExprKind::BinOp(op) if matches!(op.kind, BinOpKind::Is | BinOpKind::IsNot) => {
+                let val = self.expr(op.lhs);
+                let Some(ty) = ty::resolve(op.rhs, self.tys).filter(|ty| has_runtime_tag(ty)) else {
+                    return self.unsupported(op.rhs.span, "`is` with types other than `int`, `float`, `bool`, `String` and `Callable`");
+                };
+                let bool = self.ty(TyKind::Bool);
+                let is = self.ctx.alloc(Expr { span, ty: bool, kind: ExprKind::Is { expr: self.coerce(val, self.variant()), ty } });
+                if op.kind == BinOpKind::Is {
+                    return is;
+                }
+                (ExprKind::UnaryOp { kind: UnaryOpKind::Not, expr: is }, bool)
+            }
+            ast::ExprKind::BinOp(op) => {
+                let lhs = self.expr(op.lhs);
+                let rhs = self.expr(op.rhs);
+                return self.bin_op(span, op.kind, lhs, rhs);
+            }
+            ast::ExprKind::UnaryOp(op) => {
+                let operand = self.expr(op.expr);
+                let ty = match op.kind {
+                    UnaryOpKind::Not => self.ty(TyKind::Bool),
+                    _ => operand.ty,
+                };
+                (ExprKind::UnaryOp { kind: op.kind, expr: operand }, ty)
+            }
+            ast::ExprKind::Ternary(ternary) => {
+                let cond = self.expr(ternary.cond);
+                let then = self.expr(ternary.then);
+                let else_ = self.expr(ternary.else_);
+                // Branches of different types make a `Variant`.
+                let ty = if then.ty == else_.ty { then.ty } else { self.variant() };
+                (ExprKind::Ternary { cond, then: self.coerce(then, ty), else_: self.coerce(else_, ty) }, ty)
+            }
+            ast::ExprKind::Call(call) => return self.call_expr(span, call),
+            ast::ExprKind::Attr(attr) => return self.attr(span, attr),
+            ast::ExprKind::Lambda(lambda) => (ExprKind::Lambda(self.lambda(lambda)), self.ty(TyKind::Callable)),
+            ast::ExprKind::Self_ => return self.unsupported(span, "`self` as a value"),
+            ast::ExprKind::Super => return self.unsupported(span, "`super`"),
+            ast::ExprKind::GetNode(_) => return self.unsupported(span, "node paths"),
+            ast::ExprKind::Index(_) => return self.unsupported(span, "subscripts"),
+            ast::ExprKind::Await(_) => return self.unsupported(span, "`await`"),
+            ast::ExprKind::Array(_) => return self.unsupported(span, "arrays"),
+            ast::ExprKind::Dict(_) => return self.unsupported(span, "dictionaries"),
+            ast::ExprKind::Error => unreachable!("scripts with syntax errors aren't lowered"),
+        };
+        self.ctx.alloc(Expr { span, ty, kind })
+    }
+
+    fn call_expr(&mut self, span: Span, call: &'a ast::Call<'a>) -> &'a Expr<'a> {
+        let args: Vec<_> = call.arg_list.args.iter().map(|arg| self.expr(arg)).collect();
+        let func = match call.callee.kind {
+            ast::ExprKind::Ident(ident) => match self.binding(ident) {
+                Binding::Func(func_def) => Some(func_def),
+                Binding::Builtin(name) => {
+                    let (func, ty) = match name.as_str() {
+                        "print" => (BuiltinFunc::Print, TyKind::Variant),
+                        "str" => (BuiltinFunc::Str, TyKind::String),
+                        _ => return self.unsupported(call.callee.span, "this builtin function"),
+                    };
+                    let args: Vec<_> = args.into_iter().map(|arg| self.coerce(arg, self.variant())).collect();
+                    let kind = ExprKind::CallBuiltin { func, args: self.ctx.alloc_slice_copy(&args) };
+                    return self.ctx.alloc(Expr { span, ty: self.ty(ty), kind });
+                }
+                _ => None,
+            },
+            // `self.f()` calls a function of the script.
+            ast::ExprKind::Attr(attr) if matches!(attr.expr.kind, ast::ExprKind::Self_) => {
+                let TyKind::Class(class) = &*self.class else { unreachable!() };
+                class.func(attr.name.name).map(|func| func.ast)
+            }
+            ast::ExprKind::Attr(attr) if matches!(attr.expr.kind, ast::ExprKind::Super) => {
+                return self.unsupported(attr.expr.span, "`super`");
+            }
+            ast::ExprKind::Attr(attr) if attr.name.name.as_str() == "call" => {
+                let callee = self.expr(attr.expr);
+                let callee = self.coerce(callee, self.ty(TyKind::Callable));
+                let args: Vec<_> = args.into_iter().map(|arg| self.coerce(arg, self.variant())).collect();
+                let args = self.ctx.alloc_slice_copy(&args);
+                return self.ctx.alloc(Expr { span, ty: self.variant(), kind: ExprKind::CallClosure { callee, args } });
+            }
+            _ => None,
+        };
+        match func.and_then(|func_def| self.func_ids.get(&func_def.name.span)) {
+            Some(&id) => self.call(span, id, args),
+            None => self.unsupported(call.callee.span, "calls to anything but functions of the script and `Callable`s"),
+        }
+    }
+
+    fn attr(&mut self, span: Span, attr: &'a ast::Attr<'a>) -> &'a Expr<'a> {
+        if let Some(var_def) = self.self_field(attr) {
+            return self.field(span, var_def);
+        }
+        if let ast::ExprKind::Ident(base) = attr.expr.kind {
+            if let Binding::Enum(enum_def) = self.binding(base) {
+                let TyKind::Class(class) = &*self.class else { unreachable!() };
+                let val = class.enums.iter()
+                    .find(|enum_| std::ptr::eq(enum_.ast, enum_def))
+                    .and_then(|enum_| enum_.member(attr.name.name));
+                if let Some(val) = val {
+                    return self.int(val, span);
+                }
+            }
+        }
+        self.unsupported(span, "attributes other than members of the script and of its enums")
+    }
+
+    /// Reads a member variable. Outside its accessors, a property is read through its getter.
+    fn field(&mut self, span: Span, var_def: &'a VarDef<'a>) -> &'a Expr<'a> {
+        if let Some(getter) = self.accessors(var_def).getter {
+            return self.call(span, getter, Vec::new());
+        }
+        let id = self.global_ids[&var_def.def.name.span];
+        self.ctx.alloc(Expr { span, ty: self.globals[id.0 as usize].ty, kind: ExprKind::Global(id) })
+    }
+
+    fn ident(&mut self, span: Span, ident: &'a Ident<'a>) -> &'a Expr<'a> {
+        let (kind, ty) = match self.binding(ident) {
+            Binding::Local(def) | Binding::Param(def) => match self.local(def.span) {
+                Some((id, ty)) => (ExprKind::Local(id), ty),
+                None => return self.unsupported(span, "default values that use other parameters"),
+            },
+            Binding::Field(var_def) => return self.field(span, var_def),
+            Binding::Const(_) | Binding::EnumMember(_) => {
+                let TyKind::Class(class) = &*self.class else { unreachable!() };
+                match class.const_val(ident.name) {
+                    Some(ConstVal::Int(val)) => (ExprKind::Int(val), self.ty(TyKind::Int(IntKind::I64))),
+                    Some(ConstVal::Float(val)) => (ExprKind::Float(val.0), self.ty(TyKind::Float)),
+                    Some(ConstVal::Bool(val)) => (ExprKind::Bool(val), self.ty(TyKind::Bool)),
+                    Some(ConstVal::Str(val)) => (ExprKind::Str(self.ctx.alloc_str(&val)), self.ty(TyKind::String)),
+                    Some(ConstVal::Null) => (ExprKind::Nil, self.variant()),
+                    None => return self.unsupported(span, "constants of inner classes"),
+                }
+            }
+            Binding::Builtin(name) => match name.as_str() {
+                "true" | "false" => (ExprKind::Bool(name.as_str() == "true"), self.ty(TyKind::Bool)),
+                "null" => (ExprKind::Nil, self.variant()),
+                "PI" => (ExprKind::Float(std::f64::consts::PI), self.ty(TyKind::Float)),
+                "TAU" => (ExprKind::Float(std::f64::consts::TAU), self.ty(TyKind::Float)),
+                "INF" => (ExprKind::Float(f64::INFINITY), self.ty(TyKind::Float)),
+                "NAN" => (ExprKind::Float(f64::NAN), self.ty(TyKind::Float)),
+                _ => return self.unsupported(span, "builtin functions and types as values"),
+            },
+            Binding::Func(_) => return self.unsupported(span, "functions as values"),
+            Binding::Signal(_) => return self.unsupported(span, "signals"),
+            Binding::Enum(_) => return self.unsupported(span, "enums as values"),
+            Binding::Class(_) => return self.unsupported(span, "classes as values"),
+            Binding::Global(_) | Binding::Inherited(_) => return self.unsupported(span, "names declared outside the script"),
+        };
+        self.ctx.alloc(Expr { span, ty, kind })
+    }
+
+    fn lambda(&mut self, lambda: &'a ast::Lambda<'a>) -> &'a Lambda<'a> {
+        let ret_ty = self.ret_ty(lambda.result_ty);
+        self.frames.push(self.new_frame(ret_ty, true));
+        let params: Vec<_> = lambda.param_list.params.iter().map(|param| (param.name, self.param_ty(param))).collect();
+        let params = self.params(params);
+        let body = self.block(lambda.body, false);
+        let frame = self.frames.pop().unwrap();
+        self.ctx.alloc(Lambda {
+            span: lambda.span,
+            params: self.ctx.alloc_slice_copy(&params),
+            ret_ty,
+            captures: self.ctx.alloc_slice_copy(&frame.captures),
+            locals: self.ctx.alloc_slice_copy(&frame.locals),
+            body,
+        })
+    }
+
+    fn params(&mut self, params: impl IntoIterator<Item = (&'a Ident<'a>, Ty<'a>)>) -> Vec<LocalId> {
+        params.into_iter().map(|(name, ty)| self.declare_local(name, ty)).collect()
+    }
+}
+
+/// Whether code generation has a C representation for values of `ty`.
+fn has_c_type(ty: &TyKind) -> bool {
+    matches!(ty, TyKind::Void | TyKind::Variant) || has_runtime_tag(ty)
+}
+
+/// Whether a `Variant` records holding a value of type `ty`, which `is` tests.
+fn has_runtime_tag(ty: &TyKind) -> bool {
+    matches!(ty, TyKind::Int(_) | TyKind::Float | TyKind::Bool | TyKind::String | TyKind::Callable)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use internment::Arena;
+
+    use crate::{context::{self, Ctx}, lexer, parser, resolve, thir::{Class, Expr, ExprKind, FuncKind, Place, StmtKind, ty::{IntKind, TyKind}}};
+
+    use super::LowerErrorKind;
+
+    /// Lowers a script, describing each error as the source it covers and what isn't supported.
+    fn lower_source<'a>(ctx: &'a Ctx, source: &'a str) -> Result<&'a Class<'a>, Vec<(&'a str, &'static str)>> {
+        let tokens = lexer::tokenize(source);
+        let ast = parser::parse(source, &tokens, ctx).unwrap();
+        let (resolution, errors) = resolve::resolve(ast, &[]);
+        assert!(errors.is_empty());
+        super::lower(ctx, ast, &resolution, ctx.alloc(Arena::new())).map_err(|errors| {
+            errors.into_iter().map(|error| {
+                let LowerErrorKind::Unsupported(what) = error.kind;
+                (&source[error.span.start as usize..error.span.end as usize], what)
+            }).collect()
+        })
+    }
+
+    #[test]
+    fn lower() {
+        let source = indoc! {"
+            var hp: int = 10:
+                set(value):
+                    hp = value
+            static var count = 0
+            var k := 3
+            var f = func(x):
+                var big = x > k
+                var small := not big
+                return small
+            hp += 1
+            count = hp
+        "};
+        let ctx = context::Ctx::new();
+        let class = lower_source(&ctx, source).unwrap();
+
+        // Member variables are globals, like static ones.
+        let globals: Vec<_> = class.globals.iter().map(|global| (global.name.as_str(), (*global.ty).clone())).collect();
+        assert_eq!(globals, [
+            ("hp", TyKind::Int(IntKind::I64)),
+            ("count", TyKind::Variant),
+            ("k", TyKind::Int(IntKind::I64)),
+            ("f", TyKind::Variant),
+        ]);
+        // Only static variables start out with their value.
+        assert!(class.globals[0].val.is_none() && class.globals[1].val.is_some());
+        let [setter] = class.funcs else { panic!() };
+        assert_eq!((setter.kind, setter.name.as_str(), (*setter.ret_ty).clone()), (FuncKind::Setter, "hp", TyKind::Void));
+        // Inside the setter, the property is stored directly.
+        assert!(matches!(setter.body.stmts[0].kind, StmtKind::Assign { place: Place::Global(_), op: None, .. }));
+
+        let main = class.main;
+        assert!(main.locals.is_empty());
+        // Member variables get their value where they're declared, bypassing the setter.
+        let [init_hp, _, f, add, store] = main.body.stmts else { panic!() };
+        assert!(matches!(init_hp.kind, StmtKind::Assign { place: Place::Global(_), val: Expr { kind: ExprKind::Int(10), .. }, .. }));
+
+        let StmtKind::Assign { place: Place::Global(_), val, .. } = f.kind else { panic!() };
+        // `f` is untyped, so it holds the closure in a `Variant`.
+        let ExprKind::ToVariant(Expr { kind: ExprKind::Lambda(lambda), .. }) = val.kind else { panic!() };
+        let lambda_locals: Vec<_> = lambda.locals.iter().map(|local| (local.name.as_str(), (*local.ty).clone())).collect();
+        assert_eq!(lambda_locals, [("x", TyKind::Variant), ("big", TyKind::Variant), ("small", TyKind::Bool)]);
+        // Member variables are read where they're stored rather than captured.
+        assert!(lambda.captures.is_empty());
+        // Comparing with a `Variant` happens at runtime, on two `Variant`s.
+        let StmtKind::Var { val: Some(big), .. } = lambda.body.stmts[0].kind else { panic!() };
+        let ExprKind::BinOp { rhs, .. } = big.kind else { panic!() };
+        assert_eq!(*big.ty, TyKind::Variant);
+        assert!(matches!(rhs.kind, ExprKind::ToVariant(Expr { kind: ExprKind::Global(_), .. })));
+        // Lambdas return `Variant`s.
+        let StmtKind::Return(Some(ret)) = lambda.body.stmts[2].kind else { panic!() };
+        let ExprKind::ToVariant(small) = ret.kind else { panic!() };
+        assert_eq!(*small.ty, TyKind::Bool);
+
+        // Outside its accessors, `hp += 1` becomes a call to the setter with the updated value.
+        let StmtKind::Expr(call) = add.kind else { panic!() };
+        let ExprKind::Call { func, args: [arg] } = call.kind else { panic!() };
+        assert_eq!(func.0, 0);
+        let ExprKind::BinOp { lhs, .. } = arg.kind else { panic!() };
+        assert!(matches!(lhs.kind, ExprKind::Global(_)));
+        assert_eq!(*arg.ty, TyKind::Int(IntKind::I64));
+
+        let StmtKind::Assign { place: Place::Global(count), val, .. } = store.kind else { panic!() };
+        assert_eq!(count.0, 1);
+        assert!(matches!(val.kind, ExprKind::ToVariant(Expr { kind: ExprKind::Global(_), .. })));
+    }
+
+    #[test]
+    fn statements() {
+        let source = indoc! {"
+            var hp = 1
+            func f(n: int, scale = 2):
+                for i in range(n, 0, -1):
+                    hp += i * scale
+                match hp:
+                    1, 2:
+                        pass
+                    var other when other > 3:
+                        print(\"big \", other)
+                return hp
+            f(3)
+        "};
+        let ctx = context::Ctx::new();
+        let class = lower_source(&ctx, source).unwrap();
+        let [f] = class.funcs else { panic!() };
+        // The loop counts from `n` down to 1.
+        let StmtKind::For { var, start, step, body, .. } = f.body.stmts[0].kind else { panic!() };
+        assert_eq!(f.locals[var.0 as usize].name.as_str(), "i");
+        assert!(matches!(start.kind, ExprKind::Local(_)));
+        assert!(matches!(step.kind, ExprKind::UnaryOp { .. }));
+        assert!(matches!(body.stmts[0].kind, StmtKind::Assign { place: Place::Global(_), op: None, .. }));
+
+        let StmtKind::Match { local, val, branches: [numbers, other] } = f.body.stmts[1].kind else { panic!() };
+        assert!(matches!(val.kind, ExprKind::Global(_)));
+        assert_eq!(*f.locals[local.0 as usize].ty, TyKind::Variant);
+        assert!(matches!(numbers.cond, Some(Expr { kind: ExprKind::BinOp { .. }, .. })) && numbers.binds.is_empty());
+        // A binding matches anything, and is declared before the guard is tested.
+        assert!(other.cond.is_none() && other.binds.len() == 1 && other.guard.is_some());
+        let [print] = other.body.stmts else { panic!() };
+        let StmtKind::Expr(Expr { kind: ExprKind::CallBuiltin { args: [text, _], .. }, .. }) = print.kind else { panic!() };
+        assert!(matches!(text.kind, ExprKind::ToVariant(Expr { kind: ExprKind::Str("big "), .. })));
+
+        // The call leaves out `scale`, so it passes the default value.
+        let StmtKind::Expr(call) = class.main.body.stmts[1].kind else { panic!() };
+        let ExprKind::Call { args: [_, scale], .. } = call.kind else { panic!() };
+        assert!(matches!(scale.kind, ExprKind::ToVariant(Expr { kind: ExprKind::Int(2), .. })));
+    }
+
+    #[test]
+    fn unsupported() {
+        let source = indoc! {"
+            var a = [1, 2]
+            var d = {}
+            func f(x, y = x):
+                print(self, super.f(), $Node, x[0], len(x))
+                match x:
+                    [1, ..]:
+                        pass
+                for c in \"abc\":
+                    pass
+                return f(1)
+        "};
+        let ctx = context::Ctx::new();
+        let errors = lower_source(&ctx, source).unwrap_err();
+        assert_eq!(errors, [
+            ("[1, 2]", "arrays"),
+            ("{}", "dictionaries"),
+            ("self", "`self` as a value"),
+            ("super", "`super`"),
+            ("$Node", "node paths"),
+            ("x[0]", "subscripts"),
+            ("len", "this builtin function"),
+            ("[1, ..]", "array and dictionary patterns"),
+            ("\"abc\"", "loops over anything but ranges and numbers"),
+            ("x", "default values that use other parameters"),
+        ]);
+    }
+
+    #[test]
+    fn unsupported_types() {
+        let source = indoc! {"
+            var s: String
+            var v := Vector2(1, 2)
+            var a: Array
+            func f(p: Color) -> Rect2:
+                return p
+            var ok = s is String
+            var not_ok = s is Vector2
+        "};
+        let ctx = context::Ctx::new();
+        let errors = lower_source(&ctx, source).unwrap_err();
+        let types = "values of types other than `int`, `float`, `bool`, `String`, `Callable` and `Variant`";
+        // Functions are declared before the member variables.
+        assert_eq!(errors, [
+            ("Color", types),
+            ("Rect2", types),
+            ("Vector2", "this builtin function"),
+            ("Array", types),
+            ("Vector2", "`is` with types other than `int`, `float`, `bool`, `String` and `Callable`"),
+        ]);
+    }
+}
@@ -0,0 +1,781 @@
+use std::collections::{HashMap, HashSet};
+
+use internment::Arena;
+
+use crate::{
+    ast::{
+        self, AccessorKind, BinOpKind, ConstDef, DictKey, Expr, ExprKind, FuncDef, IdentDef, LitKind, PatternKind,
+        Stmt, StmtKind, StmtList, UnaryOpKind, VarDef,
+    },
+    lexer::Span,
+    resolve::{Binding, Resolution},
+    thir::{
+        builtins::{self, BuiltinTy, Sig},
+        ty::{self, FuncSig, IdentTyPair, IntKind, Ty, TyKind},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeError<'a> {
+    pub span: Span,
+    pub kind: TypeErrorKind<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeErrorKind<'a> {
+    /// A value whose type can't be stored where it's used, such as an argument or an initializer.
+    Mismatch { expected: Ty<'a>, found: Ty<'a> },
+    /// `var x := val`, where `val` has no static type. The span is that of the name.
+    CannotInfer { found: Ty<'a> },
+    /// A call with `found` arguments to a function declared at `def`, or to a builtin method.
+    /// `max` is `None` when any number of further arguments is taken.
+    ArgCount { min: usize, max: Option<usize>, found: usize, def: Option<Span> },
+    /// A call to a builtin type, like `Vector2(1, 2)`, that none of its constructors take.
+    NoConstructor { ty: Ty<'a>, args: Vec<Ty<'a>> },
+    InvalidOperands { op: BinOpKind, lhs: Ty<'a>, rhs: Ty<'a> },
+    InvalidOperand { op: UnaryOpKind, ty: Ty<'a> },
+    /// `return val` in a function declared `-> void`.
+    ReturnValueInVoid,
+    /// A bare `return` in a function that returns `expected`.
+    MissingReturnValue { expected: Ty<'a> },
+    /// An assignment to the constant declared at `def`.
+    AssignToConst { def: Span },
+}
+
+/// Checks the types of a script, using the bindings `resolution` holds for it.
+///
+/// Only bindings with a static type are checked: those with a type annotation, and
+/// those declared with `:=`, which take the type of their value. Everything else holds
+/// a `Variant`, which converts to and from any type and is checked at runtime instead.
+pub fn check<'a>(class: &'a ast::Class<'a>, resolution: &Resolution<'a>, tys: &'a Arena<TyKind<'a>>) -> Vec<TypeError<'a>> {
+    let mut checker = Checker {
+        tys,
+        resolution,
+        locals: HashMap::new(),
+        local_consts: HashSet::new(),
+        members: HashMap::new(),
+        sigs: HashMap::new(),
+        ret_tys: Vec::new(),
+        errors: Vec::new(),
+    };
+    checker.class(class);
+    checker.errors
+}
+
+struct Checker<'a, 'r> {
+    tys: &'a Arena<TyKind<'a>>,
+    resolution: &'r Resolution<'a>,
+    /// The static types of locals and parameters, by the span of the identifier declaring them.
+    locals: HashMap<Span, Ty<'a>>,
+    /// Locals declared with `const`.
+    local_consts: HashSet<Span>,
+    /// The static types of member variables and constants, by the span of their name.
+    /// `None` while the type is being inferred, to stop at cycles.
+    members: HashMap<Span, Option<Ty<'a>>>,
+    /// By the span of the function's name.
+    sigs: HashMap<Span, FuncSig<'a>>,
+    /// The return type of the function being checked, followed by those of the lambdas inside it.
+    ret_tys: Vec<Ty<'a>>,
+    errors: Vec<TypeError<'a>>,
+}
+
+impl<'a> Checker<'a, '_> {
+    fn error(&mut self, span: Span, kind: TypeErrorKind<'a>) {
+        self.errors.push(TypeError { span, kind });
+    }
+
+    fn ty(&self, kind: TyKind<'a>) -> Ty<'a> {
+        self.tys.intern(kind).into()
+    }
+
+    fn variant(&self) -> Ty<'a> {
+        self.ty(TyKind::Variant)
+    }
+
+    /// A type named in the builtins table. Those it doesn't know, like `Object`, are `Variant`s.
+    fn named(&self, name: &str) -> Ty<'a> {
+        self.ty(TyKind::builtin(name).unwrap_or(TyKind::Variant))
+    }
+
+    /// Resolves a type annotation. Types that aren't known yet aren't checked.
+    fn annotation(&self, ty: Option<&Expr>) -> Option<Ty<'a>> {
+        ty.map(|ty| ty::resolve(ty, self.tys).unwrap_or_else(|| self.variant()))
+    }
+
+    /// Reports a value of type `found` used where `expected` is.
+    fn expect(&mut self, span: Span, expected: Ty<'a>, found: Ty<'a>) {
+        if !assignable(expected, found) {
+            self.error(span, TypeErrorKind::Mismatch { expected, found });
+        }
+    }
+
+    /// Types an expression without reporting anything, for inferring the
+    /// type of a member before the statement declaring it is checked.
+    fn quiet_expr(&mut self, expr: &'a Expr<'a>) -> Ty<'a> {
+        let len = self.errors.len();
+        let ty = self.expr(expr);
+        self.errors.truncate(len);
+        ty
+    }
+
+    /// The static type of a member variable or constant, which its initializer may decide.
+    fn member_ty(&mut self, def: &'a IdentDef<'a>, is_const: bool) -> Ty<'a> {
+        match self.members.get(&def.name.span) {
+            Some(Some(ty)) => return *ty,
+            Some(None) => return self.variant(),
+            None => (),
+        }
+        self.members.insert(def.name.span, None);
+        let ty = match (self.annotation(def.ty), def.val) {
+            (Some(ty), _) => ty,
+            // Constants always have the type of their value.
+            (None, Some(val)) if def.strict_type || is_const => self.quiet_expr(val),
+            _ => self.variant(),
+        };
+        self.members.insert(def.name.span, Some(ty));
+        ty
+    }
+
+    fn sig(&mut self, func_def: &'a FuncDef<'a>) -> FuncSig<'a> {
+        if let Some(sig) = self.sigs.get(&func_def.name.span) {
+            return sig.clone();
+        }
+        let params = func_def.param_list.params;
+        let sig = FuncSig {
+            params: params.iter().map(|param| {
+                let ty = match (self.annotation(param.ty), param.val) {
+                    (Some(ty), _) => ty,
+                    (None, Some(val)) if param.strict_type => self.quiet_expr(val),
+                    _ => self.variant(),
+                };
+                IdentTyPair { ident: param.name.name, ty }
+            }).collect(),
+            required: params.iter().take_while(|param| param.val.is_none()).count(),
+            ret_ty: self.annotation(func_def.result_ty).unwrap_or_else(|| self.variant()),
+        };
+        self.sigs.insert(func_def.name.span, sig.clone());
+        sig
+    }
+
+    fn class(&mut self, class: &'a ast::Class<'a>) {
+        self.ret_tys.push(self.variant());
+        for stmt in class.stmt_list.stmts {
+            self.stmt(stmt, true);
+        }
+        self.ret_tys.pop();
+    }
+
+    /// Checks an initializer against the annotation, and returns the static type of the binding.
+    fn ident_def(&mut self, def: &'a IdentDef<'a>) -> Ty<'a> {
+        let val = def.val.map(|val| (val.span, self.expr(val)));
+        match (self.annotation(def.ty), val) {
+            (Some(ty), Some((span, found))) => {
+                self.expect(span, ty, found);
+                ty
+            }
+            (Some(ty), None) => ty,
+            (None, Some((_, found))) if def.strict_type => {
+                if matches!(*found, TyKind::Variant | TyKind::Void) {
+                    self.error(def.name.span, TypeErrorKind::CannotInfer { found });
+                    return self.variant();
+                }
+                found
+            }
+            _ => self.variant(),
+        }
+    }
+
+    fn func(&mut self, params: &'a [&'a IdentDef<'a>], ret_ty: Ty<'a>, body: &'a StmtList<'a>) {
+        for param in params {
+            let ty = self.ident_def(param);
+            self.locals.insert(param.name.span, ty);
+        }
+        self.ret_tys.push(ret_ty);
+        self.stmts(body);
+        self.ret_tys.pop();
+    }
+
+    fn stmts(&mut self, stmt_list: &'a StmtList<'a>) {
+        for stmt in stmt_list.stmts {
+            self.stmt(stmt, false);
+        }
+    }
+
+    /// Checks a statement. At class level, variables and constants are members.
+    fn stmt(&mut self, stmt: &'a Stmt<'a>, class_level: bool) {
+        match stmt.kind {
+            StmtKind::Expr(expr) => {
+                self.expr(expr);
+            }
+            StmtKind::Assign(assign) => self.assign(assign),
+            StmtKind::VarDef(var_def) => self.var_def(var_def, class_level),
+            StmtKind::ConstDef(ConstDef { def, .. }) => {
+                let ty = self.ident_def(def);
+                if !class_level {
+                    // Constants have the type of their value even without `:=`.
+                    let ty = match (def.ty, def.val) {
+                        (None, Some(val)) if !def.strict_type => self.quiet_expr(val),
+                        _ => ty,
+                    };
+                    self.locals.insert(def.name.span, ty);
+                    self.local_consts.insert(def.name.span);
+                }
+            }
+            StmtKind::FuncDef(func_def) => {
+                let ret_ty = self.sig(func_def).ret_ty;
+                self.func(func_def.param_list.params, ret_ty, func_def.body);
+            }
+            StmtKind::Class(class) => self.class(class),
+            StmtKind::If(if_) => {
+                self.expr(if_.cond);
+                self.stmts(if_.body);
+                for elif in if_.elifs {
+                    self.expr(elif.cond);
+                    self.stmts(elif.body);
+                }
+                if let Some(else_) = if_.else_ {
+                    self.stmts(else_);
+                }
+            }
+            StmtKind::While(while_) => {
+                self.expr(while_.cond);
+                self.stmts(while_.body);
+            }
+            StmtKind::For(for_) => {
+                self.expr(for_.iter);
+                let ty = self.annotation(for_.var.ty).unwrap_or_else(|| self.variant());
+                self.locals.insert(for_.var.name.span, ty);
+                self.stmts(for_.body);
+            }
+            StmtKind::Match(match_) => {
+                self.expr(match_.expr);
+                for branch in match_.branches {
+                    for pattern in branch.patterns {
+                        self.pattern(pattern);
+                    }
+                    if let Some(guard) = branch.guard {
+                        self.expr(guard);
+                    }
+                    self.stmts(branch.body);
+                }
+            }
+            StmtKind::Return(val) => self.return_(stmt.span, val),
+            StmtKind::Pass | StmtKind::Break | StmtKind::Continue | StmtKind::EnumDef(_) | StmtKind::SignalDef(_)
+                | StmtKind::Annotations(_) | StmtKind::Error => (),
+        }
+    }
+
+    fn var_def(&mut self, var_def: &'a VarDef<'a>, class_level: bool) {
+        let ty = self.ident_def(var_def.def);
+        if !class_level {
+            self.locals.insert(var_def.def.name.span, ty);
+        }
+        for (accessor, ret_ty) in [(var_def.getter, ty), (var_def.setter, self.variant())] {
+            if let Some(&ast::Accessor { kind: AccessorKind::Body { param, body }, .. }) = accessor {
+                if let Some(param) = param {
+                    self.locals.insert(param.span, ty);
+                }
+                self.ret_tys.push(ret_ty);
+                self.stmts(body);
+                self.ret_tys.pop();
+            }
+        }
+    }
+
+    fn return_(&mut self, span: Span, val: Option<&'a Expr<'a>>) {
+        let expected = *self.ret_tys.last().unwrap();
+        match val {
+            Some(val) => {
+                let found = self.expr(val);
+                if *expected == TyKind::Void {
+                    self.error(val.span, TypeErrorKind::ReturnValueInVoid);
+                } else {
+                    self.expect(val.span, expected, found);
+                }
+            }
+            None if !matches!(*expected, TyKind::Void | TyKind::Variant) => {
+                self.error(span, TypeErrorKind::MissingReturnValue { expected });
+            }
+            None => (),
+        }
+    }
+
+    fn assign(&mut self, assign: &'a ast::Assign<'a>) {
+        let binding = match assign.target.kind {
+            ExprKind::Ident(ident) => self.resolution.get(ident),
+            ExprKind::Attr(attr) => self.self_member(attr),
+            _ => None,
+        };
+        let def = match binding {
+            Some(Binding::Const(const_def)) => Some(const_def.def.name.span),
+            Some(Binding::EnumMember(variant)) => Some(variant.name.span),
+            Some(Binding::Local(def)) if self.local_consts.contains(&def.span) => Some(def.span),
+            _ => None,
+        };
+        if let Some(def) = def {
+            self.error(assign.target.span, TypeErrorKind::AssignToConst { def });
+        }
+        let target = self.expr(assign.target);
+        let val = self.expr(assign.val);
+        let val = match assign.op {
+            Some(op) => self.bin_op(assign.span, op, target, val),
+            None => val,
+        };
+        self.expect(assign.val.span, target, val);
+    }
+
+    fn pattern(&mut self, pattern: &'a ast::Pattern<'a>) {
+        match pattern.kind {
+            PatternKind::Expr(expr) => {
+                self.expr(expr);
+            }
+            PatternKind::Bind(ident) => {
+                self.locals.insert(ident.span, self.variant());
+            }
+            PatternKind::Array { items, .. } => {
+                for item in items {
+                    self.pattern(item);
+                }
+            }
+            PatternKind::Dict { entries, .. } => {
+                for entry in entries {
+                    self.expr(entry.key);
+                    if let Some(val) = entry.val {
+                        self.pattern(val);
+                    }
+                }
+            }
+            PatternKind::Wildcard => (),
+        }
+    }
+
+    /// Returns the static type of an expression, checking the operations inside it.
+    fn expr(&mut self, expr: &'a Expr<'a>) -> Ty<'a> {
+        let int = || TyKind::Int(IntKind::I64);
+        let kind = match expr.kind {
+            ExprKind::Lit(lit) => match lit.kind {
+                LitKind::Int(_) => int(),
+                LitKind::Float(_) => TyKind::Float,
+                LitKind::Str(_) => TyKind::String,
+                LitKind::StringName(_) => TyKind::StringName,
+                LitKind::NodePath(_) => TyKind::NodePath,
+            },
+            ExprKind::Ident(ident) => return self.ident(ident),
+            ExprKind::BinOp(op) => match op.kind {
+                BinOpKind::As => {
+                    self.expr(op.lhs);
+                    return self.annotation(Some(op.rhs)).unwrap();
+                }
+                BinOpKind::Is | BinOpKind::IsNot => {
+                    self.expr(op.lhs);
+                    TyKind::Bool
+                }
+                _ => {
+                    let lhs = self.expr(op.lhs);
+                    let rhs = self.expr(op.rhs);
+                    return self.bin_op(expr.span, op.kind, lhs, rhs);
+                }
+            },
+            ExprKind::UnaryOp(op) => {
+                let ty = self.expr(op.expr);
+                match (op.kind, &*ty) {
+                    (UnaryOpKind::Not, _) => TyKind::Bool,
+                    (_, TyKind::Variant) => TyKind::Variant,
+                    _ => match ty.builtin_name().and_then(|name| builtins::unary_op(op.kind, name)) {
+                        Some(name) => return self.named(name),
+                        None => {
+                            self.error(expr.span, TypeErrorKind::InvalidOperand { op: op.kind, ty });
+                            TyKind::Variant
+                        }
+                    },
+                }
+            }
+            ExprKind::Ternary(ternary) => {
+                self.expr(ternary.cond);
+                let then = self.expr(ternary.then);
+                let else_ = self.expr(ternary.else_);
+                // Branches of different types make a `Variant`.
+                if then == else_ {
+                    return then;
+                }
+                TyKind::Variant
+            }
+            ExprKind::Call(call) => return self.call(call),
+            ExprKind::Attr(attr) if self.self_member(attr).is_some() => return self.ident(attr.name),
+            ExprKind::Attr(attr) => {
+                let name = attr.name.name.as_str();
+                if let ExprKind::Ident(base) = attr.expr.kind {
+                    match self.resolution.get(base) {
+                        Some(Binding::Enum(_)) => return self.ty(int()),
+                        Some(Binding::Builtin(base)) => if let Some(builtin) = builtins::ty(base.as_str()) {
+                            return self.named(builtin.constant(name).unwrap_or("Variant"));
+                        },
+                        _ => (),
+                    }
+                }
+                let ty = self.expr(attr.expr);
+                return self.named(builtin_ty(ty).and_then(|builtin| builtin.member(name)).unwrap_or("Variant"));
+            }
+            ExprKind::Index(index) => {
+                let ty = self.expr(index.expr);
+                self.expr(index.index);
+                return self.named(builtin_ty(ty).and_then(|builtin| builtin.index).unwrap_or("Variant"));
+            }
+            ExprKind::Await(await_) => {
+                self.expr(await_.expr);
+                TyKind::Variant
+            }
+            ExprKind::Lambda(lambda) => {
+                let ret_ty = self.annotation(lambda.result_ty).unwrap_or_else(|| self.variant());
+                self.func(lambda.param_list.params, ret_ty, lambda.body);
+                TyKind::Callable
+            }
+            ExprKind::Array(array) => {
+                for item in array.items {
+                    self.expr(item);
+                }
+                TyKind::Array
+            }
+            ExprKind::Dict(dict) => {
+                for entry in dict.entries {
+                    if let DictKey::Expr(key) = entry.key {
+                        self.expr(key);
+                    }
+                    self.expr(entry.val);
+                }
+                TyKind::Dictionary
+            }
+            ExprKind::Self_ | ExprKind::Super | ExprKind::GetNode(_) | ExprKind::Error => TyKind::Variant,
+        };
+        self.ty(kind)
+    }
+
+    /// The type of an identifier, or of the member named by `self.name`.
+    fn ident(&mut self, ident: &'a ast::Ident<'a>) -> Ty<'a> {
+        let kind = match self.resolution.get(ident) {
+            Some(Binding::Local(def) | Binding::Param(def)) => {
+                return self.locals.get(&def.span).copied().unwrap_or_else(|| self.variant());
+            }
+            Some(Binding::Field(var_def)) => return self.member_ty(var_def.def, false),
+            Some(Binding::Const(const_def)) => return self.member_ty(const_def.def, true),
+            Some(Binding::EnumMember(_)) => TyKind::Int(IntKind::I64),
+            Some(Binding::Func(_)) => TyKind::Callable,
+            Some(Binding::Builtin(name)) => match name.as_str() {
+                "true" | "false" => TyKind::Bool,
+                "PI" | "TAU" | "INF" | "NAN" => TyKind::Float,
+                _ => TyKind::Variant,
+            },
+            _ => TyKind::Variant,
+        };
+        self.ty(kind)
+    }
+
+    /// The member of the script that `self.name` refers to, when the script declares it.
+    fn self_member(&self, attr: &ast::Attr<'a>) -> Option<Binding<'a>> {
+        match attr.expr.kind {
+            ExprKind::Self_ => self.resolution.get(attr.name),
+            _ => None,
+        }
+    }
+
+    /// Checks a call to a function of the script, or to a builtin constructor or method.
+    /// Calls to anything else return a `Variant`.
+    fn call(&mut self, call: &'a ast::Call<'a>) -> Ty<'a> {
+        let args: Vec<_> = call.arg_list.args.iter().map(|arg| (arg.span, self.expr(arg))).collect();
+        let callee = match call.callee.kind {
+            ExprKind::Attr(attr) if self.self_member(attr).is_some() => &ExprKind::Ident(attr.name),
+            ref kind => kind,
+        };
+        match *callee {
+            ExprKind::Ident(ident) => match self.resolution.get(ident) {
+                Some(Binding::Func(func_def)) => {
+                    let sig = self.sig(func_def);
+                    let params: Vec<_> = sig.params.iter().map(|param| param.ty).collect();
+                    self.args(call.span, &params, sig.required, false, args, Some(func_def.name.span));
+                    sig.ret_ty
+                }
+                Some(Binding::Builtin(name)) => match builtins::ty(name.as_str()) {
+                    Some(builtin) => self.construct(call.span, builtin, args),
+                    None => self.variant(),
+                },
+                _ => self.variant(),
+            },
+            ExprKind::Attr(attr) => {
+                // `Vector2.from_angle(a)` calls a static method, `v.angle()` any other.
+                let (builtin, is_static) = match attr.expr.kind {
+                    ExprKind::Ident(base) => match self.resolution.get(base) {
+                        Some(Binding::Builtin(name)) if builtins::ty(name.as_str()).is_some() => {
+                            (builtins::ty(name.as_str()), true)
+                        }
+                        _ => (builtin_ty(self.expr(attr.expr)), false),
+                    },
+                    _ => (builtin_ty(self.expr(attr.expr)), false),
+                };
+                let sig = builtin.and_then(|builtin| builtin.method(attr.name.name.as_str()));
+                match sig {
+                    Some(sig) if sig.is_static || !is_static => self.builtin_call(call.span, &sig, args),
+                    _ => self.variant(),
+                }
+            }
+            _ => {
+                self.expr(call.callee);
+                self.variant()
+            }
+        }
+    }
+
+    fn builtin_call(&mut self, span: Span, sig: &Sig, args: Vec<(Span, Ty<'a>)>) -> Ty<'a> {
+        let params: Vec<_> = sig.params.iter().map(|param| self.named(param)).collect();
+        self.args(span, &params, sig.required, sig.vararg, args, None);
+        self.named(sig.ret)
+    }
+
+    /// Checks arguments against the types of the parameters, the first `required` of which
+    /// have no default.
+    fn args(&mut self, span: Span, params: &[Ty<'a>], required: usize, vararg: bool, args: Vec<(Span, Ty<'a>)>, def: Option<Span>) {
+        if args.len() < required || (args.len() > params.len() && !vararg) {
+            let max = (!vararg).then_some(params.len());
+            self.error(span, TypeErrorKind::ArgCount { min: required, max, found: args.len(), def });
+        }
+        for (&param, (span, found)) in params.iter().zip(args) {
+            self.expect(span, param, found);
+        }
+    }
+
+    /// Checks a call to a builtin type against its constructors.
+    fn construct(&mut self, span: Span, builtin: &BuiltinTy, args: Vec<(Span, Ty<'a>)>) -> Ty<'a> {
+        let ty = self.named(builtin.name);
+        let args: Vec<_> = args.into_iter().map(|(_, arg)| arg).collect();
+        let takes = |sig: &Sig| {
+            (sig.required..=sig.params.len()).contains(&args.len())
+                && sig.params.iter().zip(&args).all(|(param, &arg)| assignable(self.named(param), arg))
+        };
+        if !builtin.constructors().any(|sig| takes(&sig)) {
+            self.error(span, TypeErrorKind::NoConstructor { ty, args });
+        }
+        ty
+    }
+
+    /// Returns the type of `lhs op rhs`, reporting operands the operator doesn't take.
+    fn bin_op(&mut self, span: Span, op: BinOpKind, lhs: Ty<'a>, rhs: Ty<'a>) -> Ty<'a> {
+        use BinOpKind::*;
+
+        let dynamic = matches!(*lhs, TyKind::Variant) || matches!(*rhs, TyKind::Variant);
+        let kind = match op {
+            And | Or | In | NotIn | Is | IsNot => Some(TyKind::Bool),
+            Eq | Ne | Lt | Le | Gt | Ge if dynamic => Some(TyKind::Bool),
+            _ if dynamic => Some(TyKind::Variant),
+            Eq | Ne if lhs == rhs => Some(TyKind::Bool),
+            _ => lhs.builtin_name().zip(rhs.builtin_name())
+                .and_then(|(lhs, rhs)| builtins::bin_op(op, lhs, rhs))
+                .and_then(TyKind::builtin),
+        };
+        match kind {
+            Some(kind) => self.ty(kind),
+            None => {
+                self.error(span, TypeErrorKind::InvalidOperands { op, lhs, rhs });
+                let kind = if matches!(op, Eq | Ne | Lt | Le | Gt | Ge) { TyKind::Bool } else { TyKind::Variant };
+                self.ty(kind)
+            }
+        }
+    }
+}
+
+/// Where members, methods and indexing of values of type `ty` are looked up.
+fn builtin_ty(ty: Ty) -> Option<&'static BuiltinTy> {
+    ty.builtin_name().and_then(builtins::ty)
+}
+
+/// Whether a value of type `found` can be stored where `expected` is.
+/// Integers and floats convert to each other implicitly, and `Variant`s to and from
+/// anything, which lowering turns into a check at runtime.
+fn assignable(expected: Ty, found: Ty) -> bool {
+    expected == found
+        || matches!(*expected, TyKind::Variant)
+        || matches!(*found, TyKind::Variant)
+        || (expected.is_numeric() && found.is_numeric())
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use internment::Arena;
+
+    use crate::{context, lexer, parser, resolve};
+
+    use super::{check, TypeErrorKind};
+
+    /// Returns each error's kind, with its type names, and the text it points at.
+    fn errors(source: &str) -> Vec<(String, &str)> {
+        let tokens = lexer::tokenize(source);
+        let ctx = context::Ctx::new();
+        let class = parser::parse(source, &tokens, &ctx).unwrap();
+        let (resolution, resolve_errors) = resolve::resolve(class, &[]);
+        assert_eq!(resolve_errors, []);
+        let tys = ctx.alloc(Arena::new());
+        check(class, &resolution, tys).into_iter().map(|error| {
+            let kind = match error.kind {
+                TypeErrorKind::Mismatch { expected, found } => format!("Mismatch({expected}, {found})"),
+                TypeErrorKind::CannotInfer { found } => format!("CannotInfer({found})"),
+                TypeErrorKind::ArgCount { min, max: Some(max), found, .. } => format!("ArgCount({min}..={max}, {found})"),
+                TypeErrorKind::ArgCount { min, max: None, found, .. } => format!("ArgCount({min}.., {found})"),
+                TypeErrorKind::NoConstructor { ty, args } => {
+                    format!("NoConstructor({ty}({}))", args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>().join(", "))
+                }
+                TypeErrorKind::InvalidOperands { op, lhs, rhs } => format!("InvalidOperands({lhs} {} {rhs})", op.as_str()),
+                TypeErrorKind::InvalidOperand { op, ty } => format!("InvalidOperand({} {ty})", op.as_str()),
+                kind => format!("{kind:?}").split_whitespace().next().unwrap().to_owned(),
+            };
+            (kind, &source[error.span.start as usize..error.span.end as usize])
+        }).collect()
+    }
+
+    #[test]
+    fn inference() {
+        let source = indoc! {r#"
+            var a := 1
+            var b := a + 2.5
+            var c: int = b
+            var d := "x" + "y"
+            var e: String = a
+            var f = 1
+            var g := f
+            var h := untyped()
+            var i: bool = a > 0 and d
+            func untyped():
+                pass
+            func done() -> void:
+                pass
+            func use():
+                var j := done()
+                var k: float = d
+                f = "now a string"
+                a = "not an int"
+        "#};
+        assert_eq!(errors(source), [
+            ("Mismatch(String, int)".to_owned(), "a"),
+            ("CannotInfer(Variant)".to_owned(), "g"),
+            ("CannotInfer(Variant)".to_owned(), "h"),
+            ("CannotInfer(void)".to_owned(), "j"),
+            ("Mismatch(float, String)".to_owned(), "d"),
+            ("Mismatch(int, String)".to_owned(), "\"not an int\""),
+        ]);
+    }
+
+    #[test]
+    fn calls_and_returns() {
+        let source = indoc! {r#"
+            func add(x: int, y := 1.0) -> float:
+                return x + y
+            func name() -> String:
+                if true:
+                    return
+                return 3
+            func nothing() -> void:
+                return 1
+            func use():
+                add(1)
+                add(1, 2, 3)
+                add()
+                add("1", "2")
+                var s: String = add(1)
+                var l := func(n: int) -> int: return "n"
+            var n: int
+            const MAX = 3
+            func through_self():
+                self.add("a", 2, 3)
+                var s: String = self.add(1)
+                self.n = "str"
+                self.MAX = 4
+                var fine: int = self.n + self.untyped
+        "#};
+        assert_eq!(errors(source), [
+            ("MissingReturnValue".to_owned(), "return"),
+            ("Mismatch(String, int)".to_owned(), "3"),
+            ("ReturnValueInVoid".to_owned(), "1"),
+            ("ArgCount(1..=2, 3)".to_owned(), "add(1, 2, 3)"),
+            ("ArgCount(1..=2, 0)".to_owned(), "add()"),
+            ("Mismatch(int, String)".to_owned(), "\"1\""),
+            ("Mismatch(float, String)".to_owned(), "\"2\""),
+            ("Mismatch(String, float)".to_owned(), "add(1)"),
+            ("Mismatch(int, String)".to_owned(), "\"n\""),
+            ("ArgCount(1..=2, 3)".to_owned(), "self.add(\"a\", 2, 3)"),
+            ("Mismatch(int, String)".to_owned(), "\"a\""),
+            ("Mismatch(String, float)".to_owned(), "self.add(1)"),
+            ("Mismatch(int, String)".to_owned(), "\"str\""),
+            ("AssignToConst".to_owned(), "self.MAX"),
+        ]);
+    }
+
+    #[test]
+    fn operators_and_consts() {
+        let source = indoc! {r#"
+            const LIMIT = 10
+            enum {NORTH, SOUTH}
+            enum Dir {UP, DOWN}
+            var s := "a"
+            var n := 2
+            func use(untyped):
+                var ok := n * LIMIT + Dir.UP - NORTH
+                var fmt := "%d" % n
+                var bad1 = s - n
+                var bad2 = -s
+                var bad3 = 1.5 % 2
+                var bad4 = n == s
+                var fine = untyped + s
+                const LOCAL = 1
+                LIMIT = 3
+                NORTH += 1
+                LOCAL = 2
+                n += 0.5
+                s += n
+        "#};
+        assert_eq!(errors(source), [
+            ("InvalidOperands(String - int)".to_owned(), "s - n"),
+            ("InvalidOperand(- String)".to_owned(), "-s"),
+            ("InvalidOperands(float % int)".to_owned(), "1.5 % 2"),
+            ("InvalidOperands(int == String)".to_owned(), "n == s"),
+            ("AssignToConst".to_owned(), "LIMIT"),
+            ("AssignToConst".to_owned(), "NORTH"),
+            ("AssignToConst".to_owned(), "LOCAL"),
+            ("InvalidOperands(String + int)".to_owned(), "s += n"),
+        ]);
+    }
+
+    #[test]
+    fn builtin_types() {
+        let source = indoc! {r#"
+            var v := Vector2(1, 2)
+            var w: Vector2i = Vector2i(3, 4)
+            func use(n: int, s: String):
+                var scaled := n * v
+                var back: Vector2i = w * 0.5
+                var t := Transform2D.IDENTITY * v
+                var x: float = v.x + v[1]
+                var len: String = v.length()
+                var angle := Vector2.from_angle(0.5).angle_to(v)
+                var mixed = v + w
+                v.x = "left"
+                v.lerp(v)
+                v.rotated("up")
+                Vector2(1, "2")
+                var parts := s.split(",")
+                var first: String = parts[0]
+                var c := Color.RED * 0.5
+                var name := &"node" + s
+                var bound := use.bind(1, 2, 3)
+                var arr := [1, 2] + [3]
+                var bad: Dictionary = arr
+        "#};
+        assert_eq!(errors(source), [
+            ("Mismatch(Vector2i, Vector2)".to_owned(), "w * 0.5"),
+            ("Mismatch(String, float)".to_owned(), "v.length()"),
+            ("InvalidOperands(Vector2 + Vector2i)".to_owned(), "v + w"),
+            ("Mismatch(float, String)".to_owned(), "\"left\""),
+            ("ArgCount(2..=2, 1)".to_owned(), "v.lerp(v)"),
+            ("Mismatch(float, String)".to_owned(), "\"up\""),
+            ("NoConstructor(Vector2(int, String))".to_owned(), "Vector2(1, \"2\")"),
+            ("Mismatch(Dictionary, Array)".to_owned(), "arr"),
+        ]);
+    }
+}